@@ -0,0 +1,81 @@
+//! Account reads shared by `status`, `lottery winners` and the `token` commands.
+
+use anyhow::{Context, Result};
+use pepeball_sdk::accounts::{self, ParticipantAccount};
+use pepeball_sdk::program_ids;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+
+use anchor_lang::Discriminator;
+
+/// `None` if the account does not exist.
+pub fn fetch(client: &RpcClient, address: &Pubkey) -> Result<Option<Account>> {
+    let resp = client
+        .get_account_with_commitment(address, client.commitment())
+        .with_context(|| format!("fetching {address}"))?;
+    Ok(resp.value)
+}
+
+/// Owner of a token account of either token program; both keep it at bytes 32..64.
+pub fn token_account_owner(client: &RpcClient, address: &Pubkey) -> Result<Pubkey> {
+    let account = fetch(client, address)?.with_context(|| format!("token account {address} not found"))?;
+    let owner = account.data.get(32..64).with_context(|| format!("{address} is not a token account"))?;
+    Ok(Pubkey::try_from(owner)?)
+}
+
+/// All participant accounts of `lottery`, sorted by wallet so every tool sees the same order.
+pub fn participants(client: &RpcClient, lottery: &Pubkey) -> Result<Vec<ParticipantAccount>> {
+    let filters = vec![
+        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &ParticipantAccount::DISCRIMINATOR)),
+        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(8, lottery.as_ref())),
+    ];
+    let raw = client
+        .get_program_accounts_with_config(
+            &program_ids::LOTTERY,
+            RpcProgramAccountsConfig {
+                filters: Some(filters),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(solana_account_decoder::UiAccountEncoding::Base64),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .context("fetching participant accounts")?;
+
+    let mut out = raw
+        .iter()
+        .map(|(_, acc)| accounts::decode_participant(&acc.data))
+        .collect::<Result<Vec<_>, _>>()?;
+    out.sort_by_key(|p| p.wallet);
+    Ok(out)
+}
+
+/// Token-2022 accounts of `mint` holding withheld transfer fees, largest first.
+pub fn withheld_sources(client: &RpcClient, mint: &Pubkey) -> Result<Vec<(Pubkey, u64)>> {
+    let filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, mint.as_ref()))];
+    let raw = client
+        .get_program_accounts_with_config(
+            &program_ids::TOKEN_2022,
+            RpcProgramAccountsConfig {
+                filters: Some(filters),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(solana_account_decoder::UiAccountEncoding::Base64),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .context("fetching token accounts")?;
+
+    let mut out: Vec<(Pubkey, u64)> = raw
+        .iter()
+        .filter_map(|(key, acc)| accounts::withheld_fee(&acc.data).map(|w| (*key, w)))
+        .filter(|(_, withheld)| *withheld > 0)
+        .collect();
+    out.sort_by_key(|(_, withheld)| std::cmp::Reverse(*withheld));
+    Ok(out)
+}
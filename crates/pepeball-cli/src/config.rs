@@ -0,0 +1,71 @@
+//! CLI config file. Flags override file values; missing file falls back to devnet defaults.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    pub rpc_url: Option<String>,
+    /// Admin / game-day keypair (Solana CLI JSON format)
+    pub keypair: Option<PathBuf>,
+    /// processed | confirmed | finalized
+    pub commitment: Option<String>,
+    /// tax-harvest vault token account
+    pub tax_vault: Option<Pubkey>,
+    /// PEPBALL mint; its pepball-token TokenInfo is the PDA derived from it
+    pub token_mint: Option<Pubkey>,
+}
+
+#[derive(Debug)]
+pub struct Config {
+    pub rpc_url: String,
+    pub keypair: PathBuf,
+    pub commitment: String,
+    pub tax_vault: Option<Pubkey>,
+    pub token_mint: Option<Pubkey>,
+}
+
+pub fn default_config_path() -> PathBuf {
+    home_dir().join(".config/pepeball/cli.toml")
+}
+
+fn home_dir() -> PathBuf {
+    std::env::var_os("HOME").map(PathBuf::from).unwrap_or_default()
+}
+
+/// `~/` prefix is expanded so config files can be shared across machines.
+fn expand_home(path: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => home_dir().join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path, rpc_url: Option<String>, keypair: Option<PathBuf>) -> Result<Self> {
+        let file = if path.exists() {
+            let raw = std::fs::read_to_string(path)
+                .with_context(|| format!("reading {}", path.display()))?;
+            toml::from_str::<FileConfig>(&raw).with_context(|| format!("parsing {}", path.display()))?
+        } else {
+            FileConfig::default()
+        };
+
+        let keypair = keypair
+            .or(file.keypair)
+            .unwrap_or_else(|| PathBuf::from("~/.config/solana/id.json"));
+
+        Ok(Self {
+            rpc_url: rpc_url.or(file.rpc_url).unwrap_or_else(|| DEFAULT_RPC_URL.to_string()),
+            keypair: expand_home(&keypair),
+            commitment: file.commitment.unwrap_or_else(|| "confirmed".to_string()),
+            tax_vault: file.tax_vault,
+            token_mint: file.token_mint,
+        })
+    }
+}
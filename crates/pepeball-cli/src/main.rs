@@ -0,0 +1,938 @@
+//! `pepeball` — admin CLI for the game-day runbook.
+//! Replaces the one-off scripts in `scripts/` with typed subcommands built on `pepeball-sdk`.
+//!
+//! Keypair and cluster come from `~/.config/pepeball/cli.toml` (or `--config`);
+//! `--dry-run` simulates every transaction, `--output json` prints machine-readable results.
+
+mod chain;
+mod config;
+mod output;
+mod rpc;
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use output::{OutputFormat, Report};
+use pepeball_sdk::programs::game_registry::instruction as registry_ix;
+use pepeball_sdk::programs::pepball_token::instruction as token_ix;
+use pepeball_sdk::{accounts, decode, harvest, hook, lottery, pda, program_ids, registry, token};
+use rpc::Ctx;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use std::path::PathBuf;
+
+/// Largest round whose participant accounts still fit in a `set_winners` tx.
+const VERIFY_MAX_PARTICIPANTS: usize = 16;
+/// Withheld-fee source accounts harvested per `withdraw_withheld_fees` tx.
+const HARVEST_MAX_SOURCES: usize = 16;
+
+#[derive(Parser)]
+#[command(name = "pepeball", version, about = "PEPEBALL admin CLI")]
+struct Cli {
+    /// Config file (TOML)
+    #[arg(long, global = true, env = "PEPEBALL_CONFIG")]
+    config: Option<PathBuf>,
+    /// RPC URL, overrides config
+    #[arg(long, short = 'u', global = true)]
+    url: Option<String>,
+    /// Signer keypair, overrides config
+    #[arg(long, short = 'k', global = true)]
+    keypair: Option<PathBuf>,
+    /// Simulate transactions instead of sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+    #[arg(long, short = 'o', global = true, value_enum, default_value_t)]
+    output: OutputFormat,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// game-registry: init, register mints, seal
+    #[command(subcommand)]
+    Registry(RegistryCmd),
+    /// lottery: init, timing, tiers, snapshot, winners, payout, pause
+    #[command(subcommand)]
+    Lottery(LotteryCmd),
+    /// pepball-token admin
+    #[command(subcommand)]
+    Token(TokenCmd),
+    /// tax-harvest: swap vault tokens to SOL for the jackpot
+    Harvest(HarvestArgs),
+    /// Print lottery, registry and harvest state
+    Status,
+}
+
+#[derive(Subcommand)]
+enum RegistryCmd {
+    Init {
+        #[arg(long)]
+        jackpot_sol_dest: Pubkey,
+    },
+    Register(RegisterArgs),
+    /// Irreversible
+    Seal,
+}
+
+#[derive(Args)]
+struct RegisterArgs {
+    #[arg(long)]
+    master_mint: Pubkey,
+    #[arg(long)]
+    pump_shell_mint: Pubkey,
+    #[arg(long)]
+    trix_yang_mint: Pubkey,
+    /// Defaults to the master mint (the registry requires them to match)
+    #[arg(long)]
+    trix_yin_mint: Option<Pubkey>,
+    #[arg(long)]
+    trix_bridge_mint: Pubkey,
+}
+
+#[derive(Subcommand)]
+enum LotteryCmd {
+    Init {
+        #[arg(long)]
+        jackpot_lamports: u64,
+        #[arg(long, default_value_t = 2000)]
+        entry_min_cents: u64,
+        #[arg(long, default_value_t = 10000)]
+        tier2_min_cents: u64,
+        #[arg(long, default_value_t = 50000)]
+        tier3_min_cents: u64,
+    },
+    /// Snapshot intervals (seconds) and fast-mode threshold (lamports of fees)
+    Timing {
+        #[arg(long)]
+        base_secs: u64,
+        #[arg(long)]
+        fast_secs: u64,
+        #[arg(long)]
+        fast_threshold: u64,
+    },
+    /// Ticket tier thresholds in USD cents
+    Tiers {
+        #[arg(long)]
+        entry_min_cents: u64,
+        #[arg(long)]
+        tier2_min_cents: u64,
+        #[arg(long)]
+        tier3_min_cents: u64,
+    },
+    Snapshot,
+    /// Compute winners from the current seed; `--submit` sends set_winners
+    Winners {
+        #[arg(long)]
+        submit: bool,
+    },
+    Payout,
+    /// Toggle the emergency pause
+    Pause,
+}
+
+#[derive(Subcommand)]
+enum TokenCmd {
+    /// Toggle the transfer pause
+    Pause,
+    /// Lifetime taxed volume and creator/jackpot totals
+    Stats,
+    /// Exact net/creator/jackpot amounts of a transfer (simulated, nothing sent)
+    Quote {
+        /// Source token account
+        #[arg(long)]
+        from: Pubkey,
+        /// Destination token account
+        #[arg(long)]
+        to: Pubkey,
+        amount: u64,
+    },
+    /// Record a wallet's ATA balance at the current slot (Token-2022 mints)
+    Checkpoint {
+        /// Defaults to this keypair
+        owner: Option<Pubkey>,
+    },
+    /// A wallet's checkpointed balance at a slot and the lowest since another
+    Holdings {
+        owner: Pubkey,
+        /// Snapshot slot, defaults to the current slot
+        #[arg(long)]
+        slot: Option<u64>,
+        /// Round start slot for the held-throughout balance, defaults to `slot`
+        #[arg(long)]
+        from_slot: Option<u64>,
+    },
+    /// Let relayers move up to `amount` from this keypair's token account with signed permits
+    ApprovePermits {
+        amount: u64,
+        /// Defaults to this keypair's ATA
+        #[arg(long)]
+        from: Option<Pubkey>,
+    },
+    /// Sign a permit for a relayer's transfer_with_tax_permit (nothing sent)
+    SignPermit {
+        /// Destination token account
+        #[arg(long)]
+        to: Pubkey,
+        amount: u64,
+        /// Seconds the permit stays valid
+        #[arg(long, default_value_t = 3_600)]
+        expires_in: i64,
+        /// Defaults to this keypair's ATA
+        #[arg(long)]
+        from: Option<Pubkey>,
+    },
+    /// Token-2022 mode: create pepball-hook's account list for the mint (once, after init)
+    InitHook,
+    /// Token-2022 mode: collect withheld transfer fees and pay them out 245/5
+    WithdrawFees {
+        /// Jackpot token account (owned by the token's jackpot vault owner), overrides config tax_vault
+        #[arg(long)]
+        jackpot_pool: Option<Pubkey>,
+        /// Creator fund token account, defaults to the creator's Token-2022 ATA
+        #[arg(long)]
+        creator_fund: Option<Pubkey>,
+    },
+    /// Exempt a token account owner from the tax (until renounce)
+    Exempt {
+        address: Pubkey,
+        /// Untaxed when this owner sends
+        #[arg(long)]
+        sender: bool,
+        /// Untaxed when this owner receives
+        #[arg(long)]
+        recipient: bool,
+    },
+    /// Remove a tax exemption (until renounce)
+    Unexempt { address: Pubkey },
+    /// Set one direction's creator/jackpot/burn rates in bps (until renounce)
+    SetRates {
+        #[arg(value_enum)]
+        direction: Direction,
+        #[arg(long)]
+        creator: u16,
+        #[arg(long)]
+        jackpot: u16,
+        /// Share burned through the token program
+        #[arg(long, default_value_t = 0)]
+        burn: u16,
+    },
+    /// Register an AMM pool token account: out of it is a buy, into it a sell
+    AddPool { pool: Pubkey },
+    /// Unregister an AMM pool token account
+    RemovePool { pool: Pubkey },
+    /// Lock an allocation for a beneficiary: nothing before the cliff, then
+    /// linear from start to end (unix timestamps; until renounce)
+    Vest {
+        beneficiary: Pubkey,
+        #[arg(long, value_enum)]
+        category: Category,
+        #[arg(long)]
+        amount: u64,
+        #[arg(long)]
+        start: i64,
+        #[arg(long)]
+        cliff: i64,
+        #[arg(long)]
+        end: i64,
+        /// Admin may return the unvested rest before renounce
+        #[arg(long)]
+        revocable: bool,
+        /// Funding token account owned by this CLI's keypair, defaults to its ATA
+        #[arg(long)]
+        source: Option<Pubkey>,
+    },
+    /// Show a beneficiary's vesting schedule
+    Vesting { beneficiary: Pubkey },
+    /// Claim what has vested for this CLI's keypair
+    ClaimVested {
+        /// Defaults to this keypair's ATA
+        #[arg(long)]
+        destination: Option<Pubkey>,
+    },
+    /// Return the unvested rest of a revocable schedule (until renounce)
+    RevokeVesting {
+        beneficiary: Pubkey,
+        #[arg(long)]
+        refund: Pubkey,
+    },
+    /// Point the mint metadata (Token-2022 or Metaplex) at a new JSON URI (until renounce)
+    SetUri { uri: String },
+    /// Hand admin to another key (e.g. a multisig); it must run accept-admin
+    ProposeAdmin { new_admin: Pubkey },
+    /// Accept a proposed handoff with this CLI's keypair
+    AcceptAdmin,
+    /// Give up one admin capability for good
+    RenounceCapability {
+        #[arg(value_enum)]
+        capability: CapabilityArg,
+    },
+    /// Anti-bot launch window; 0 disables a limit (until renounce)
+    LaunchProtection {
+        /// First slot anything but exempt transfers may move (at most ~1 day ahead)
+        #[arg(long)]
+        start_slot: u64,
+        /// Window length in slots (~150 per minute, at most 9000)
+        #[arg(long)]
+        window_slots: u64,
+        #[arg(long, default_value_t = 0)]
+        max_tx: u64,
+        #[arg(long, default_value_t = 0)]
+        max_wallet: u64,
+        #[arg(long, default_value_t = 0)]
+        cooldown_slots: u64,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Category {
+    Team,
+    CreatorFund,
+    Marketing,
+}
+
+impl From<Category> for accounts::VestingCategory {
+    fn from(c: Category) -> Self {
+        match c {
+            Category::Team => Self::Team,
+            Category::CreatorFund => Self::CreatorFund,
+            Category::Marketing => Self::Marketing,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CapabilityArg {
+    Rates,
+    Exemptions,
+    Pause,
+}
+
+impl From<CapabilityArg> for accounts::Capability {
+    fn from(c: CapabilityArg) -> Self {
+        match c {
+            CapabilityArg::Rates => Self::Rates,
+            CapabilityArg::Exemptions => Self::Exemptions,
+            CapabilityArg::Pause => Self::Pause,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Direction {
+    Buy,
+    Sell,
+    Transfer,
+}
+
+impl From<Direction> for accounts::TaxDirection {
+    fn from(d: Direction) -> Self {
+        match d {
+            Direction::Buy => Self::Buy,
+            Direction::Sell => Self::Sell,
+            Direction::Transfer => Self::Transfer,
+        }
+    }
+}
+
+#[derive(Args)]
+struct HarvestArgs {
+    /// Raw token amount to harvest
+    #[arg(long)]
+    amount: u64,
+    /// Tax vault token account, overrides config
+    #[arg(long)]
+    tax_vault: Option<Pubkey>,
+    /// Vault token mint, overrides config
+    #[arg(long)]
+    mint: Option<Pubkey>,
+    /// Orca Whirlpool pairing the token with wrapped SOL
+    #[arg(long)]
+    whirlpool: Pubkey,
+    /// Largest shortfall from the pool's spot price to accept, in bps
+    #[arg(long, default_value_t = 100)]
+    slippage_bps: u16,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let format = cli.output;
+    match run(cli) {
+        Ok(report) => report.print(format),
+        Err(err) => {
+            let mut report = Report::new("error");
+            report.insert("error", format!("{err:#}"));
+            report.print(format);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<Report> {
+    let path = cli.config.unwrap_or_else(config::default_config_path);
+    let cfg = config::Config::load(&path, cli.url, cli.keypair)?;
+    let ctx = Ctx::new(&cfg, cli.dry_run)?;
+    let admin = ctx.payer.pubkey();
+
+    match cli.command {
+        Command::Registry(cmd) => run_registry(&ctx, &admin, cmd),
+        Command::Lottery(cmd) => run_lottery(&ctx, &admin, cmd),
+        Command::Token(TokenCmd::Pause) => {
+            let token_info = token_info(&cfg)?;
+            let mut report = Report::new("token pause");
+            ctx.submit(&[token::emergency_pause(&token_info, &admin)], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::Stats) => {
+            let token_info = token_info(&cfg)?;
+            let account = chain::fetch(&ctx.client, &token_info)?.context("token info not found")?;
+            let info = accounts::decode_token_info(&account.data)?;
+            let stats = info.stats;
+            let mut report = Report::new("token stats");
+            report.insert("transfer_count", stats.transfer_count.to_string());
+            report.insert("total_volume", stats.total_volume.to_string());
+            report.insert("total_creator_tax", stats.total_creator_tax.to_string());
+            report.insert("total_jackpot_tax", stats.total_jackpot_tax.to_string());
+            report.insert("total_burned", stats.total_burned.to_string());
+            report.insert("circulating_supply", info.circulating_supply().to_string());
+            Ok(report)
+        }
+        Command::Token(TokenCmd::Quote { from, to, amount }) => {
+            let token_info = token_info(&cfg)?;
+            let account = chain::fetch(&ctx.client, &token_info)?.context("token info not found")?;
+            let info = accounts::decode_token_info(&account.data)?;
+            let logs = ctx.simulate(&[token::quote_transfer(&token_info, &info.mint, &from, &to, amount)])?;
+            let quote = decode::transfer_quote(&logs).context("no quote in the simulation's return data")?;
+            let mut report = Report::new("token quote");
+            report.insert("direction", format!("{:?}", quote.direction));
+            report.insert("exempt", quote.exempt);
+            report.insert("amount", quote.amount);
+            report.insert("net_amount", quote.net_amount);
+            report.insert("creator_tax", quote.creator_tax);
+            report.insert("jackpot_tax", quote.jackpot_tax);
+            report.insert("burn_tax", quote.burn_tax);
+            Ok(report)
+        }
+        Command::Token(TokenCmd::Checkpoint { owner }) => {
+            let (mint, token_program) = token_mint(&ctx, &cfg)?;
+            require_hooked(&token_program)?;
+            let owner = owner.unwrap_or(admin);
+            let token_account = token::ata(&owner, &mint, &token_program);
+            let mut report = Report::new("token checkpoint");
+            report.insert("owner", owner.to_string());
+            report.insert("token_account", token_account.to_string());
+            ctx.submit(&[hook::checkpoint_balance(&mint, &owner, &token_account, &admin)], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::Holdings { owner, slot, from_slot }) => {
+            let (mint, token_program) = token_mint(&ctx, &cfg)?;
+            require_hooked(&token_program)?;
+            let address = pda::hook_checkpoints(&mint, &owner).0;
+            let account = chain::fetch(&ctx.client, &address)?.context("no checkpoints for this wallet")?;
+            let history = accounts::decode_hook_checkpoints(&account.data)?.history;
+            let slot = match slot {
+                Some(slot) => slot,
+                None => ctx.client.get_slot()?,
+            };
+            let from_slot = from_slot.unwrap_or(slot);
+            let mut report = Report::new("token holdings");
+            report.insert("checkpoints", address.to_string());
+            report.insert("slot", slot);
+            report.insert("balance_at_slot", history.balance_at(slot)?);
+            report.insert("from_slot", from_slot);
+            report.insert("held_throughout", history.min_balance(from_slot, slot)?);
+            report.insert("recorded", history.checkpoints.len());
+            Ok(report)
+        }
+        Command::Token(TokenCmd::ApprovePermits { amount, from }) => {
+            let token_info = token_info(&cfg)?;
+            let (mint, token_program) = token_mint(&ctx, &cfg)?;
+            let from = from.unwrap_or_else(|| token::ata(&admin, &mint, &token_program));
+            let mut report = Report::new("token approve-permits");
+            report.insert("from", from.to_string());
+            report.insert("delegate", pda::permit_authority(&token_info).0.to_string());
+            report.insert("amount", amount);
+            ctx.submit(&[token::approve_permits(&token_info, &from, &admin, amount)], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::SignPermit { to, amount, expires_in, from }) => {
+            let token_info = token_info(&cfg)?;
+            let (mint, token_program) = token_mint(&ctx, &cfg)?;
+            let from = from.unwrap_or_else(|| token::ata(&admin, &mint, &token_program));
+            let nonce = match chain::fetch(&ctx.client, &pda::permit_nonce(&token_info, &admin).0)? {
+                Some(account) => accounts::decode_permit_nonce(&account.data)?.nonce,
+                None => 0,
+            };
+            let expiry = ctx.client.get_block_time(ctx.client.get_slot()?)? + expires_in;
+            let signature = ctx.payer.sign_message(&token::permit_message(&from, &to, amount, nonce, expiry));
+            let mut report = Report::new("token sign-permit");
+            report.insert("owner", admin.to_string());
+            report.insert("from", from.to_string());
+            report.insert("to", to.to_string());
+            report.insert("amount", amount);
+            report.insert("nonce", nonce);
+            report.insert("expiry", expiry);
+            report.insert("signature", signature.to_string());
+            Ok(report)
+        }
+        Command::Token(TokenCmd::InitHook) => {
+            let token_info = token_info(&cfg)?;
+            let account = chain::fetch(&ctx.client, &token_info)?.context("token info not found")?;
+            let info = accounts::decode_token_info(&account.data)?;
+            if !info.transfer_fee_mode {
+                bail!("token {token_info} is not in Token-2022 transfer-fee mode");
+            }
+            let mut report = Report::new("token init-hook");
+            report.insert("mint", info.mint.to_string());
+            report.insert("extra_account_metas", pda::extra_account_metas(&info.mint).0.to_string());
+            let ix = hook::initialize_extra_account_meta_list(&admin, &info.mint, &token_info);
+            ctx.submit(&[ix], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::WithdrawFees {
+            jackpot_pool,
+            creator_fund,
+        }) => withdraw_fees(&ctx, &cfg, &admin, jackpot_pool, creator_fund),
+        Command::Token(TokenCmd::Exempt {
+            address,
+            sender,
+            recipient,
+        }) => {
+            if !sender && !recipient {
+                bail!("pass --sender, --recipient or both");
+            }
+            let token_info = token_info(&cfg)?;
+            let mut report = Report::new("token exempt");
+            report.insert("address", address.to_string());
+            report.insert("sender", sender.to_string());
+            report.insert("recipient", recipient.to_string());
+            let ix = token::add_exemption(&token_info, &admin, address, sender, recipient);
+            ctx.submit(&[ix], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::Unexempt { address }) => {
+            let token_info = token_info(&cfg)?;
+            let mut report = Report::new("token unexempt");
+            report.insert("address", address.to_string());
+            ctx.submit(&[token::remove_exemption(&token_info, &admin, address)], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::SetRates {
+            direction,
+            creator,
+            jackpot,
+            burn,
+        }) => {
+            let token_info = token_info(&cfg)?;
+            let mut report = Report::new("token set-rates");
+            report.insert("total_bps", (creator as u64 + jackpot as u64 + burn as u64).to_string());
+            let ix = token::set_tax_rates(&token_info, &admin, direction.into(), creator, jackpot, burn);
+            ctx.submit(&[ix], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::AddPool { pool }) => {
+            let token_info = token_info(&cfg)?;
+            let mut report = Report::new("token add-pool");
+            report.insert("pool", pool.to_string());
+            ctx.submit(&[token::register_amm_pool(&token_info, &admin, pool)], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::RemovePool { pool }) => {
+            let token_info = token_info(&cfg)?;
+            let mut report = Report::new("token remove-pool");
+            report.insert("pool", pool.to_string());
+            ctx.submit(&[token::unregister_amm_pool(&token_info, &admin, pool)], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::Vest {
+            beneficiary,
+            category,
+            amount,
+            start,
+            cliff,
+            end,
+            revocable,
+            source,
+        }) => {
+            let (mint, token_program) = token_mint(&ctx, &cfg)?;
+            let source = source
+                .unwrap_or_else(|| token::ata(&admin, &mint, &token_program));
+            let mut report = Report::new("token vest");
+            report.insert("beneficiary", beneficiary.to_string());
+            report.insert("escrow", token::vesting_escrow(&mint, &token_program, &beneficiary).to_string());
+            let args = token_ix::CreateVesting {
+                category: category.into(),
+                amount,
+                start_ts: start,
+                cliff_ts: cliff,
+                end_ts: end,
+                revocable,
+            };
+            let ix = token::create_vesting(&mint, &token_program, &admin, &admin, &source, &beneficiary, args);
+            ctx.submit(&[ix], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::Vesting { beneficiary }) => {
+            let token_info = token_info(&cfg)?;
+            let address = pda::vesting(&token_info, &beneficiary).0;
+            let account = chain::fetch(&ctx.client, &address)?.context("no vesting for this beneficiary")?;
+            let v = accounts::decode_vesting(&account.data)?;
+            let now = ctx.client.get_block_time(ctx.client.get_slot()?)?;
+            let mut report = Report::new("token vesting");
+            report.insert("vesting", address.to_string());
+            report.insert("category", format!("{:?}", v.category));
+            report.insert("total_amount", v.total_amount);
+            report.insert("vested", v.vested(now));
+            report.insert("claimed", v.claimed);
+            report.insert("start_ts", v.start_ts);
+            report.insert("cliff_ts", v.cliff_ts);
+            report.insert("end_ts", v.end_ts);
+            report.insert("revocable", v.revocable);
+            report.insert("revoked", v.revoked);
+            Ok(report)
+        }
+        Command::Token(TokenCmd::ClaimVested { destination }) => {
+            let (mint, token_program) = token_mint(&ctx, &cfg)?;
+            let destination = destination
+                .unwrap_or_else(|| token::ata(&admin, &mint, &token_program));
+            let mut report = Report::new("token claim-vested");
+            report.insert("destination", destination.to_string());
+            let destination_owner = chain::token_account_owner(&ctx.client, &destination)?;
+            let ix = token::claim_vested(&mint, &token_program, &admin, &destination, &destination_owner);
+            ctx.submit(&[ix], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::RevokeVesting { beneficiary, refund }) => {
+            let (mint, token_program) = token_mint(&ctx, &cfg)?;
+            let mut report = Report::new("token revoke-vesting");
+            report.insert("beneficiary", beneficiary.to_string());
+            let refund_owner = chain::token_account_owner(&ctx.client, &refund)?;
+            let ix = token::revoke_vesting(&mint, &token_program, &admin, &beneficiary, &refund, &refund_owner);
+            ctx.submit(&[ix], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::SetUri { uri }) => {
+            let token_info = token_info(&cfg)?;
+            let (mint, token_program) = token_mint(&ctx, &cfg)?;
+            let mut report = Report::new("token set-uri");
+            report.insert("uri", uri.clone());
+            ctx.submit(&[token::update_metadata_uri(&token_info, &admin, &mint, &token_program, uri)], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::ProposeAdmin { new_admin }) => {
+            let token_info = token_info(&cfg)?;
+            let mut report = Report::new("token propose-admin");
+            report.insert("new_admin", new_admin.to_string());
+            ctx.submit(&[token::propose_admin(&token_info, &admin, new_admin)], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::AcceptAdmin) => {
+            let token_info = token_info(&cfg)?;
+            let mut report = Report::new("token accept-admin");
+            report.insert("admin", admin.to_string());
+            ctx.submit(&[token::accept_admin(&token_info, &admin)], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::RenounceCapability { capability }) => {
+            let token_info = token_info(&cfg)?;
+            let mut report = Report::new("token renounce-capability");
+            let ix = token::renounce_capability(&token_info, &admin, capability.into());
+            ctx.submit(&[ix], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Token(TokenCmd::LaunchProtection {
+            start_slot,
+            window_slots,
+            max_tx,
+            max_wallet,
+            cooldown_slots,
+        }) => {
+            let token_info = token_info(&cfg)?;
+            let mut report = Report::new("token launch-protection");
+            report.insert("start_slot", start_slot.to_string());
+            report.insert("end_slot", start_slot.saturating_add(window_slots).to_string());
+            let args = token_ix::ConfigureLaunchProtection {
+                start_slot,
+                window_slots,
+                max_tx_amount: max_tx,
+                max_wallet_balance: max_wallet,
+                cooldown_slots,
+            };
+            ctx.submit(&[token::configure_launch_protection(&token_info, &admin, args)], &[], &mut report)?;
+            Ok(report)
+        }
+        Command::Harvest(args) => run_harvest(&ctx, &cfg, args),
+        Command::Status => status(&ctx),
+    }
+}
+
+/// The configured token's mint and the token program that owns it.
+/// The mint's `TokenInfo` PDA.
+fn token_info(cfg: &config::Config) -> Result<Pubkey> {
+    let mint = cfg.token_mint.ok_or_else(|| anyhow!("token_mint missing from config"))?;
+    Ok(pda::token_info(&mint).0)
+}
+
+fn token_mint(ctx: &Ctx, cfg: &config::Config) -> Result<(Pubkey, Pubkey)> {
+    let mint = cfg.token_mint.ok_or_else(|| anyhow!("token_mint missing from config"))?;
+    let token_program = chain::fetch(&ctx.client, &mint)?.context("mint not found")?.owner;
+    Ok((mint, token_program))
+}
+
+/// Balance checkpoints only hold for Token-2022 mints, where pepball-hook
+/// sees every transfer; plain transfers of a legacy mint bypass the program.
+fn require_hooked(token_program: &Pubkey) -> Result<()> {
+    if *token_program != program_ids::TOKEN_2022 {
+        bail!("legacy SPL Token mints keep no usable checkpoints: plain transfers bypass the program");
+    }
+    Ok(())
+}
+
+fn run_registry(ctx: &Ctx, admin: &Pubkey, cmd: RegistryCmd) -> Result<Report> {
+    match cmd {
+        RegistryCmd::Init { jackpot_sol_dest } => {
+            let mut report = Report::new("registry init");
+            let ix = registry::initialize_registry(
+                admin,
+                registry_ix::InitializeRegistry {
+                    jackpot_sol_dest,
+                    lottery_program: program_ids::LOTTERY,
+                    pepball_token_program: program_ids::PEPBALL_TOKEN,
+                    tax_harvest_program: program_ids::TAX_HARVEST,
+                    lp_manager_program: program_ids::LP_MANAGER,
+                },
+            );
+            report.insert("registry", pda::game_registry().0.to_string());
+            ctx.submit(&[ix], &[], &mut report)?;
+            Ok(report)
+        }
+        RegistryCmd::Register(args) => {
+            let mut report = Report::new("registry register");
+            let ix = registry::register_mints(
+                admin,
+                registry_ix::RegisterMints {
+                    master_mint: args.master_mint,
+                    pump_shell_mint: args.pump_shell_mint,
+                    trix_yang_mint: args.trix_yang_mint,
+                    trix_yin_mint: args.trix_yin_mint.unwrap_or(args.master_mint),
+                    trix_bridge_mint: args.trix_bridge_mint,
+                },
+            );
+            ctx.submit(&[ix], &[], &mut report)?;
+            Ok(report)
+        }
+        RegistryCmd::Seal => {
+            let mut report = Report::new("registry seal");
+            ctx.submit(&[registry::seal_registry(admin)], &[], &mut report)?;
+            Ok(report)
+        }
+    }
+}
+
+fn run_lottery(ctx: &Ctx, admin: &Pubkey, cmd: LotteryCmd) -> Result<Report> {
+    let (report_name, ix) = match cmd {
+        LotteryCmd::Init {
+            jackpot_lamports,
+            entry_min_cents,
+            tier2_min_cents,
+            tier3_min_cents,
+        } => (
+            "lottery init",
+            lottery::initialize_lottery(admin, jackpot_lamports, entry_min_cents, tier2_min_cents, tier3_min_cents),
+        ),
+        LotteryCmd::Timing {
+            base_secs,
+            fast_secs,
+            fast_threshold,
+        } => (
+            "lottery timing",
+            lottery::configure_timing(admin, base_secs, fast_secs, fast_threshold),
+        ),
+        LotteryCmd::Tiers {
+            entry_min_cents,
+            tier2_min_cents,
+            tier3_min_cents,
+        } => (
+            "lottery tiers",
+            lottery::set_tier_thresholds(admin, entry_min_cents, tier2_min_cents, tier3_min_cents),
+        ),
+        LotteryCmd::Snapshot => ("lottery snapshot", lottery::take_snapshot(admin)),
+        LotteryCmd::Winners { submit } => return lottery_winners(ctx, admin, submit),
+        LotteryCmd::Payout => ("lottery payout", lottery::payout_winners(admin)),
+        LotteryCmd::Pause => ("lottery pause", lottery::emergency_pause_lottery(admin)),
+    };
+    let mut report = Report::new(report_name);
+    ctx.submit(&[ix], &[], &mut report)?;
+    Ok(report)
+}
+
+fn lottery_winners(ctx: &Ctx, admin: &Pubkey, submit: bool) -> Result<Report> {
+    let mut report = Report::new("lottery winners");
+    let lottery_pda = pda::lottery().0;
+    let account = chain::fetch(&ctx.client, &lottery_pda)?.context("lottery account not found")?;
+    let state = accounts::decode_lottery(&account.data)?;
+    if state.snapshot_seed == 0 {
+        bail!("no payout seed: take an ODD snapshot first (pepe_ball_count = {})", state.pepe_ball_count);
+    }
+
+    let participants = chain::participants(&ctx.client, &lottery_pda)?;
+    let ticket_counts: Vec<u32> = participants.iter().map(|p| p.ticket_count).collect();
+    let selection = pepeball_draw::select_winners(state.snapshot_seed, &ticket_counts)
+        .context("no tickets in this round")?;
+    let main_winner = participants[selection.main].wallet;
+    let minor_winners: Vec<Pubkey> = selection.minors.iter().map(|i| participants[*i].wallet).collect();
+
+    report.insert("seed", state.snapshot_seed);
+    report.insert("pepe_ball_count", state.pepe_ball_count);
+    report.insert("participants", participants.len());
+    report.insert("main_winner", main_winner.to_string());
+    report.insert(
+        "minor_winners",
+        minor_winners.iter().map(|w| w.to_string()).collect::<Vec<_>>(),
+    );
+
+    if submit {
+        // Small rounds fit every participant account in the tx: let the program re-check the draw
+        let verified = participants.len() <= VERIFY_MAX_PARTICIPANTS;
+        let ix = if verified {
+            let pdas: Vec<Pubkey> = participants
+                .iter()
+                .map(|p| pda::participant(&lottery_pda, &p.wallet).0)
+                .collect();
+            lottery::set_winners_verified(admin, main_winner, minor_winners, &pdas)
+        } else {
+            lottery::set_winners(admin, main_winner, minor_winners)
+        };
+        report.insert("verified_on_chain", verified);
+        ctx.submit(&[ix], &[], &mut report)?;
+    }
+    Ok(report)
+}
+
+fn withdraw_fees(
+    ctx: &Ctx,
+    cfg: &config::Config,
+    payer: &Pubkey,
+    jackpot_pool: Option<Pubkey>,
+    creator_fund: Option<Pubkey>,
+) -> Result<Report> {
+    let token_info = token_info(cfg)?;
+    let account = chain::fetch(&ctx.client, &token_info)?.context("token info not found")?;
+    let info = accounts::decode_token_info(&account.data)?;
+    if !info.transfer_fee_mode {
+        bail!("token {token_info} is not in Token-2022 transfer-fee mode");
+    }
+    let jackpot_pool = jackpot_pool
+        .or(cfg.tax_vault)
+        .ok_or_else(|| anyhow!("jackpot pool not set (--jackpot-pool or tax_vault in config)"))?;
+    let creator_fund =
+        creator_fund.unwrap_or_else(|| token::token_2022_ata(&info.creator_fund_address, &info.mint));
+
+    let sources = chain::withheld_sources(&ctx.client, &info.mint)?;
+    let mut report = Report::new("token withdraw-fees");
+    report.insert("mint", info.mint.to_string());
+    report.insert("withheld_sources", sources.len());
+    report.insert("withheld_in_sources", sources.iter().map(|(_, w)| w).sum::<u64>());
+    let batch: Vec<Pubkey> = sources.iter().take(HARVEST_MAX_SOURCES).map(|(k, _)| *k).collect();
+    if sources.len() > batch.len() {
+        report.insert("sources_left_for_next_run", sources.len() - batch.len());
+    }
+    let ix = token::withdraw_withheld_fees(
+        &token_info,
+        &info.mint,
+        &jackpot_pool,
+        &info.jackpot_vault_owner,
+        &creator_fund,
+        &info.creator_fund_address,
+        payer,
+        &batch,
+    );
+    ctx.submit(&[ix], &[], &mut report)?;
+    Ok(report)
+}
+
+fn run_harvest(ctx: &Ctx, cfg: &config::Config, args: HarvestArgs) -> Result<Report> {
+    let tax_vault = args
+        .tax_vault
+        .or(cfg.tax_vault)
+        .ok_or_else(|| anyhow!("tax vault not set (--tax-vault or tax_vault in config)"))?;
+    let mint = args
+        .mint
+        .or(cfg.token_mint)
+        .ok_or_else(|| anyhow!("token mint not set (--mint or token_mint in config)"))?;
+
+    let config_pda = pda::config().0;
+    let account = chain::fetch(&ctx.client, &config_pda)?.context("harvest config not found")?;
+    let harvest_cfg = accounts::decode_harvest_config(&account.data)?;
+
+    let mut report = Report::new("harvest");
+    report.insert("jackpot_sol_dest", harvest_cfg.jackpot_sol_dest.to_string());
+    let pool_account = chain::fetch(&ctx.client, &args.whirlpool)?.context("whirlpool not found")?;
+    let pool = harvest::WhirlpoolPool::decode(&pool_account.data)?;
+    let route = pool
+        .route(&args.whirlpool, &mint)
+        .ok_or_else(|| anyhow!("whirlpool does not pair {mint} with wrapped SOL"))?;
+    let spot = pool.spot_sol_out(&mint, args.amount).unwrap_or_default();
+    let min_sol_out = (spot as u128 * (10_000 - args.slippage_bps.min(10_000)) as u128 / 10_000) as u64;
+    report.insert("spot_sol_out", spot);
+    report.insert("min_sol_out", min_sol_out);
+
+    let ix = harvest::process_harvest(
+        &ctx.payer.pubkey(),
+        &tax_vault,
+        &mint,
+        &harvest_cfg.jackpot_sol_dest,
+        args.amount,
+        min_sol_out,
+        &route,
+    );
+    ctx.submit(&[ix], &[], &mut report)?;
+    Ok(report)
+}
+
+fn status(ctx: &Ctx) -> Result<Report> {
+    let mut report = Report::new("status");
+
+    let lottery_pda = pda::lottery().0;
+    report.insert("lottery", lottery_pda.to_string());
+    match chain::fetch(&ctx.client, &lottery_pda)? {
+        Some(acc) => {
+            let l = accounts::decode_lottery(&acc.data)?;
+            report.insert("lottery_active", l.is_active);
+            report.insert("jackpot_lamports", l.jackpot_amount);
+            report.insert("total_participants", l.total_participants);
+            report.insert("total_tickets", l.total_tickets);
+            report.insert("total_snapshots", l.total_snapshots);
+            report.insert("last_snapshot", l.last_snapshot);
+            report.insert("snapshot_seed", l.snapshot_seed);
+            report.insert("pepe_ball_count", l.pepe_ball_count);
+            report.insert("rollover_count", l.rollover_count);
+            report.insert("fast_mode", l.is_fast_mode);
+            report.insert(
+                "main_winner",
+                l.winners.main_winner.map(|w| w.to_string()).unwrap_or_default(),
+            );
+        }
+        None => report.insert("lottery_active", "not initialized"),
+    }
+
+    let registry_pda = pda::game_registry().0;
+    match chain::fetch(&ctx.client, &registry_pda)? {
+        Some(acc) => {
+            let r = accounts::decode_game_registry(&acc.data)?;
+            report.insert("registry_sealed", r.sealed);
+            report.insert("registry_mints_registered", r.mints_registered);
+            report.insert("master_mint", r.master_mint.to_string());
+        }
+        None => report.insert("registry_sealed", "not initialized"),
+    }
+
+    match chain::fetch(&ctx.client, &pda::config().0)? {
+        Some(acc) => {
+            let h = accounts::decode_harvest_config(&acc.data)?;
+            report.insert("harvest_jackpot_sol_dest", h.jackpot_sol_dest.to_string());
+        }
+        None => report.insert("harvest_jackpot_sol_dest", "not initialized"),
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,51 @@
+//! Command results as ordered key/value reports, printed as text or JSON.
+
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Default)]
+pub struct Report(Map<String, Value>);
+
+impl Report {
+    pub fn new(command: &str) -> Self {
+        let mut r = Self::default();
+        r.insert("command", command);
+        r
+    }
+
+    pub fn insert(&mut self, key: &str, value: impl Into<Value>) {
+        self.0.insert(key.to_string(), value.into());
+    }
+
+    pub fn print(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&self.0).unwrap_or_default())
+            }
+            OutputFormat::Text => {
+                for (k, v) in &self.0 {
+                    match v {
+                        Value::String(s) => println!("{k}: {s}"),
+                        Value::Array(items) => {
+                            println!("{k}:");
+                            for item in items {
+                                match item {
+                                    Value::String(s) => println!("  {s}"),
+                                    other => println!("  {other}"),
+                                }
+                            }
+                        }
+                        other => println!("{k}: {other}"),
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Transaction submission. `--dry-run` simulates instead of sending.
+
+use crate::output::Report;
+use anyhow::{anyhow, Context, Result};
+use pepeball_sdk::PepeballError;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+use std::str::FromStr;
+
+pub struct Ctx {
+    pub client: RpcClient,
+    pub payer: Keypair,
+    pub dry_run: bool,
+}
+
+impl Ctx {
+    pub fn new(cfg: &crate::config::Config, dry_run: bool) -> Result<Self> {
+        let commitment = CommitmentConfig::from_str(&cfg.commitment)
+            .map_err(|_| anyhow!("invalid commitment {}", cfg.commitment))?;
+        let payer = read_keypair_file(&cfg.keypair)
+            .map_err(|e| anyhow!("reading keypair {}: {}", cfg.keypair.display(), e))?;
+        Ok(Self {
+            client: RpcClient::new_with_commitment(cfg.rpc_url.clone(), commitment),
+            payer,
+            dry_run,
+        })
+    }
+
+    /// Simulates regardless of `--dry-run` and returns the logs, for
+    /// read-only instructions that answer through return data.
+    pub fn simulate(&self, ixs: &[Instruction]) -> Result<Vec<String>> {
+        let blockhash = self.client.get_latest_blockhash().context("fetching blockhash")?;
+        let tx = Transaction::new_signed_with_payer(ixs, Some(&self.payer.pubkey()), &[&self.payer], blockhash);
+        let sim = self
+            .client
+            .simulate_transaction(&tx)
+            .context("simulating transaction")?
+            .value;
+        if let Some(err) = sim.err {
+            return Err(anyhow!("simulation failed: {}", describe_error(ixs, &err)));
+        }
+        Ok(sim.logs.unwrap_or_default())
+    }
+
+    /// Sign with the payer (plus `extra` signers) and send or simulate.
+    pub fn submit(&self, ixs: &[Instruction], extra: &[&Keypair], report: &mut Report) -> Result<()> {
+        let blockhash = self.client.get_latest_blockhash().context("fetching blockhash")?;
+        let mut signers: Vec<&Keypair> = vec![&self.payer];
+        signers.extend_from_slice(extra);
+        let tx = Transaction::new_signed_with_payer(ixs, Some(&self.payer.pubkey()), &signers, blockhash);
+
+        if self.dry_run {
+            let sim = self
+                .client
+                .simulate_transaction_with_config(
+                    &tx,
+                    RpcSimulateTransactionConfig {
+                        sig_verify: true,
+                        ..Default::default()
+                    },
+                )
+                .context("simulating transaction")?
+                .value;
+            report.insert("dry_run", true);
+            report.insert("units_consumed", sim.units_consumed.unwrap_or_default());
+            report.insert("logs", sim.logs.unwrap_or_default());
+            if let Some(err) = sim.err {
+                report.insert("error", describe_error(ixs, &err));
+                return Err(anyhow!("simulation failed: {}", describe_error(ixs, &err)));
+            }
+            return Ok(());
+        }
+
+        let sig = self
+            .client
+            .send_and_confirm_transaction(&tx)
+            .map_err(|e| match e.get_transaction_error() {
+                Some(err) => anyhow!("transaction failed: {}", describe_error(ixs, &err)),
+                None => anyhow!("transaction failed: {}", e),
+            })?;
+        report.insert("signature", sig.to_string());
+        Ok(())
+    }
+}
+
+/// Resolve `custom program error: 0x...` to the program's error name where possible.
+fn describe_error(ixs: &[Instruction], err: &TransactionError) -> String {
+    if let TransactionError::InstructionError(idx, InstructionError::Custom(code)) = err {
+        if let Some(ix) = ixs.get(*idx as usize) {
+            if let Some(e) = PepeballError::from_code(&ix.program_id, *code) {
+                return e.to_string();
+            }
+        }
+    }
+    err.to_string()
+}
@@ -0,0 +1,176 @@
+//! Draw logic shared by the lottery program and off-chain tools.
+//!
+//! Everything here is a pure function of on-chain values, so a published
+//! `snapshot_seed` plus the participant set always reproduces the published
+//! winners. Participants are always ordered by wallet pubkey (byte order).
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+/// Pepe balls drawn per snapshot: 1..=MAX_BALLS.
+pub const MAX_BALLS: u64 = 30;
+/// Minor winners per payout round (40% pool, 5% each).
+pub const MINOR_WINNERS: usize = 8;
+/// Minimum participants before a snapshot can run.
+pub const MIN_PARTICIPANTS: u64 = 9;
+
+/// Payout split of the jackpot on an ODD draw, in basis points. Minor share is per winner.
+pub const MAIN_PRIZE_BPS: u64 = 5_000;
+pub const MINOR_PRIZE_BPS: u64 = 500;
+pub const ROLLOVER_RESERVE_BPS: u64 = 800;
+pub const DEV_BPS: u64 = 200;
+
+/// EVEN rollovers alternate 48h / 72h extensions.
+pub const ROLLOVER_SHORT_SECS: i64 = 48 * 3600;
+pub const ROLLOVER_LONG_SECS: i64 = 72 * 3600;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Parity {
+    /// Payout round
+    Odd,
+    /// Rollover round
+    Even,
+}
+
+/// Snapshot seed from clock and lottery counters, as taken in `take_snapshot`.
+pub fn derive_seed(slot: u64, unix_timestamp: i64, total_participants: u64, total_snapshots: u64) -> u64 {
+    slot.wrapping_mul(unix_timestamp as u64)
+        .wrapping_add(total_participants)
+        .wrapping_add(total_snapshots)
+}
+
+/// Pepe ball count, 1..=30.
+pub fn ball_count(seed: u64) -> u8 {
+    ((seed % MAX_BALLS) as u8) + 1
+}
+
+pub fn parity(ball_count: u8) -> Parity {
+    if ball_count % 2 == 1 {
+        Parity::Odd
+    } else {
+        Parity::Even
+    }
+}
+
+/// Timer extension after an EVEN draw. `rollover_count` is the count after incrementing.
+pub fn rollover_extension_secs(rollover_count: u8) -> i64 {
+    if rollover_count % 2 == 1 {
+        ROLLOVER_SHORT_SECS
+    } else {
+        ROLLOVER_LONG_SECS
+    }
+}
+
+/// Winning ticket index for the main prize, in `0..total_tickets`.
+pub fn main_ticket_index(seed: u64, total_tickets: u64) -> Option<u64> {
+    if total_tickets == 0 {
+        None
+    } else {
+        Some(seed % total_tickets)
+    }
+}
+
+/// Participant holding ticket `ticket_index`, where participant `i` owns the
+/// next `ticket_counts[i]` tickets.
+pub fn ticket_owner(ticket_counts: impl IntoIterator<Item = u32>, ticket_index: u64) -> Option<usize> {
+    let mut acc = 0u64;
+    for (i, count) in ticket_counts.into_iter().enumerate() {
+        acc += count as u64;
+        if acc > ticket_index {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Draw `count` distinct indices from `0..candidates` (without replacement).
+pub fn sample_distinct(seed: u64, candidates: usize, count: usize) -> Vec<usize> {
+    let mut pool: Vec<usize> = (0..candidates).collect();
+    let mut picked = Vec::with_capacity(count.min(candidates));
+    let mut s = (seed as u128).wrapping_mul(7);
+    while picked.len() < count && !pool.is_empty() {
+        let idx = (s % pool.len() as u128) as usize;
+        picked.push(pool.remove(idx));
+        s = s.wrapping_mul(13).wrapping_add(1);
+    }
+    picked
+}
+
+/// Winner indices into the (wallet-sorted) participant list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Selection {
+    pub main: usize,
+    pub minors: Vec<usize>,
+}
+
+/// Ticket-weighted main winner, then up to `MINOR_WINNERS` distinct minor
+/// winners drawn uniformly from everyone else.
+pub fn select_winners(seed: u64, ticket_counts: &[u32]) -> Option<Selection> {
+    let total: u64 = ticket_counts.iter().map(|c| *c as u64).sum();
+    let main = ticket_owner(ticket_counts.iter().copied(), main_ticket_index(seed, total)?)?;
+    let minors = sample_distinct(seed, ticket_counts.len() - 1, MINOR_WINNERS)
+        .into_iter()
+        .map(|i| if i >= main { i + 1 } else { i })
+        .collect();
+    Some(Selection { main, minors })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn ticket_owner_at_the_boundaries() {
+        let counts = [3, 0, 2];
+        assert_eq!(ticket_owner(counts, 0), Some(0));
+        assert_eq!(ticket_owner(counts, 2), Some(0));
+        // The first ticket past a participant's last goes to the next one holding any.
+        assert_eq!(ticket_owner(counts, 3), Some(2));
+        assert_eq!(ticket_owner(counts, 4), Some(2));
+        assert_eq!(ticket_owner(counts, 5), None);
+        assert_eq!(ticket_owner([], 0), None);
+    }
+
+    #[test]
+    fn zero_ticket_participants_never_win_the_main_prize() {
+        let counts = [0, 4, 0, 1, 0];
+        for seed in 0..200 {
+            let selection = select_winners(seed, &counts).unwrap();
+            assert!(counts[selection.main] > 0);
+        }
+        assert_eq!(select_winners(7, &[0, 0, 0]), None);
+        assert_eq!(select_winners(7, &[]), None);
+    }
+
+    #[test]
+    fn fewer_candidates_than_minor_winners() {
+        let counts = [1, 2, 3, 4, 5];
+        for seed in 0..200 {
+            let selection = select_winners(seed, &counts).unwrap();
+            let mut minors = selection.minors.clone();
+            assert_eq!(minors.len(), counts.len() - 1);
+            assert!(!minors.contains(&selection.main));
+            minors.sort_unstable();
+            minors.dedup();
+            assert_eq!(minors.len(), counts.len() - 1);
+        }
+        let alone = select_winners(3, &[5]).unwrap();
+        assert_eq!((alone.main, alone.minors), (0, vec![]));
+        assert_eq!(sample_distinct(3, 4, MINOR_WINNERS).len(), 4);
+    }
+
+    #[test]
+    fn selection_is_fixed_by_the_seed() {
+        let counts = [5, 1, 0, 3, 2, 8, 1, 1, 4, 6, 2];
+        let seed = derive_seed(250_000_000, 1_700_000_000, 11, 3);
+        let selection = select_winners(seed, &counts).unwrap();
+        assert_eq!(select_winners(seed, &counts), Some(selection.clone()));
+        // Pinned: the program and the verifier must keep agreeing on past draws.
+        // Minors are drawn from everyone else, the zero-ticket wallet included.
+        assert_eq!(selection, Selection { main: 3, minors: vec![9, 10, 0, 2, 5, 1, 4, 8] });
+    }
+}
@@ -0,0 +1,168 @@
+//! Read-only JSON API over the indexer database. `/lottery-state` keeps the
+//! response shape of the `api/lottery-state.js` endpoint.
+
+use anyhow::{anyhow, Result};
+use rusqlite::types::Value;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::{json, Map, Value as Json};
+use std::path::Path;
+use tiny_http::{Header, Method, Response, Server};
+
+/// Main winner share of the jackpot (percent); each minor winner gets `MINOR_PCT`.
+const MAIN_PCT: u64 = 50;
+const MINOR_PCT: u64 = 5;
+/// Default/maximum page size for list endpoints.
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+pub fn serve(db_path: &Path, listen: &str) -> Result<()> {
+    let conn = Connection::open(db_path)?;
+    conn.pragma_update(None, "busy_timeout", 5000)?;
+    let server = Server::http(listen).map_err(|e| anyhow!("bind {listen}: {e}"))?;
+    eprintln!("API listening on http://{listen}");
+
+    for request in server.incoming_requests() {
+        if *request.method() != Method::Get {
+            let _ = request.respond(json_response(405, &json!({ "error": "method not allowed" })));
+            continue;
+        }
+        let (path, query) = match request.url().split_once('?') {
+            Some((p, q)) => (p.to_string(), q.to_string()),
+            None => (request.url().to_string(), String::new()),
+        };
+        let (status, body) = match route(&conn, &path, &query) {
+            Ok(Some(body)) => (200, body),
+            Ok(None) => (404, json!({ "error": "not found" })),
+            Err(e) => (500, json!({ "error": e.to_string() })),
+        };
+        let _ = request.respond(json_response(status, &body));
+    }
+    Ok(())
+}
+
+fn json_response(status: u16, body: &Json) -> Response<std::io::Cursor<Vec<u8>>> {
+    Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").unwrap())
+        .with_header(Header::from_bytes("Access-Control-Allow-Origin", "*").unwrap())
+}
+
+fn route(conn: &Connection, path: &str, query: &str) -> Result<Option<Json>> {
+    let limit = query
+        .split('&')
+        .find_map(|kv| kv.strip_prefix("limit="))
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_LIMIT)
+        .clamp(1, MAX_LIMIT);
+    // The frontend's `ApiService` prefixes everything with `/api`.
+    let path = path.trim_matches('/');
+    let path = path.strip_prefix("api/").unwrap_or(path);
+    let segments: Vec<&str> = path.split('/').collect();
+
+    match segments.as_slice() {
+        ["health"] => Ok(Some(json!({ "ok": true }))),
+        ["lottery-state"] | ["lottery", "state"] => lottery_state(conn),
+        ["rounds"] => rows(conn, "SELECT * FROM rounds ORDER BY id DESC LIMIT ?1", params![limit]).map(Some),
+        ["rounds", id] => {
+            let Ok(id) = id.parse::<i64>() else { return Ok(None) };
+            let mut round = rows(conn, "SELECT * FROM rounds WHERE id = ?1", params![id])?;
+            let Some(Json::Object(mut round)) = round.as_array_mut().and_then(|r| r.pop()) else {
+                return Ok(None);
+            };
+            round.insert(
+                "draws".into(),
+                rows(conn, "SELECT * FROM draws WHERE round_id = ?1 ORDER BY snapshot_number", params![id])?,
+            );
+            round.insert(
+                "payout".into(),
+                rows(conn, "SELECT * FROM payouts WHERE round_id = ?1", params![id])?
+                    .as_array()
+                    .and_then(|p| p.first().cloned())
+                    .unwrap_or(Json::Null),
+            );
+            Ok(Some(Json::Object(round)))
+        }
+        ["rounds", id, "participants"] => rows(
+            conn,
+            "SELECT wallet, ticket_count, usd_value, first_entry_slot FROM participants
+             WHERE round_id = ?1 ORDER BY wallet",
+            params![id],
+        )
+        .map(Some),
+        ["rounds", id, "tickets"] => rows(
+            conn,
+            "SELECT * FROM tickets WHERE round_id = ?1 ORDER BY slot, ix_index LIMIT ?2",
+            params![id, limit],
+        )
+        .map(Some),
+        ["draws"] => rows(conn, "SELECT * FROM draws ORDER BY snapshot_number DESC LIMIT ?1", params![limit]).map(Some),
+        ["payouts"] => rows(conn, "SELECT * FROM payouts ORDER BY slot DESC LIMIT ?1", params![limit]).map(Some),
+        ["token-events"] => rows(conn, "SELECT * FROM token_events ORDER BY slot DESC LIMIT ?1", params![limit]).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Cached lottery account plus the latest payout, in the legacy endpoint's shape.
+fn lottery_state(conn: &Connection) -> Result<Option<Json>> {
+    let Some(state) = conn
+        .query_row("SELECT json FROM lottery_state WHERE id = 1", [], |r| r.get::<_, String>(0))
+        .optional()?
+    else {
+        return Ok(Some(json!({ "error": "lottery not indexed yet" })));
+    };
+    let mut state: Map<String, Json> = serde_json::from_str(&state)?;
+    let jackpot: u64 = state["jackpot"].as_str().and_then(|s| s.parse().ok()).unwrap_or(0);
+    let carry_over: u64 = state["carryOver"].as_str().and_then(|s| s.parse().ok()).unwrap_or(0);
+
+    if let Some(Json::Object(winners)) = state.get_mut("winners") {
+        if let Some(Json::Array(minors)) = winners.get_mut("minorWinners") {
+            minors.retain(|w| w.as_str() != Some("11111111111111111111111111111111"));
+        }
+    }
+    state.insert(
+        "payouts".into(),
+        json!({
+            "mainPayout": jackpot * MAIN_PCT / 100,
+            "minorPayout": jackpot * MINOR_PCT / 100,
+            "carryOver": carry_over,
+        }),
+    );
+
+    let last_payout: Option<(String, Option<i64>)> = conn
+        .query_row(
+            "SELECT signature, block_time FROM payouts ORDER BY slot DESC LIMIT 1",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .optional()?;
+    state.insert("payoutTx".into(), json!(last_payout.as_ref().map(|p| &p.0)));
+    state.insert("payoutTime".into(), json!(last_payout.and_then(|p| p.1)));
+    Ok(Some(Json::Object(state)))
+}
+
+/// Runs `sql` and returns every row as a JSON object keyed by column name.
+fn rows(conn: &Connection, sql: &str, params: impl rusqlite::Params) -> Result<Json> {
+    let mut stmt = conn.prepare(sql)?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let out = stmt
+        .query_map(params, |row| {
+            let mut obj = Map::new();
+            for (i, name) in columns.iter().enumerate() {
+                let value = match row.get::<_, Value>(i)? {
+                    Value::Null => Json::Null,
+                    Value::Integer(n) => json!(n),
+                    Value::Real(f) => json!(f),
+                    Value::Text(t) => match name.as_str() {
+                        // JSON arrays stored as text come back as arrays.
+                        "minor_winners" => serde_json::from_str(&t).unwrap_or(Json::String(t)),
+                        _ => Json::String(t),
+                    },
+                    Value::Blob(_) => Json::Null,
+                };
+                obj.insert(name.clone(), value);
+            }
+            Ok(Json::Object(obj))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(Json::Array(out))
+}
@@ -0,0 +1,289 @@
+//! SQLite schema and writes. One writer (the ingest loop); the API opens its own read connection.
+
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS cursors (
+    program         TEXT PRIMARY KEY,
+    last_signature  TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS rounds (
+    id              INTEGER PRIMARY KEY,
+    status          TEXT NOT NULL DEFAULT 'open',
+    opened_slot     INTEGER,
+    closed_slot     INTEGER,
+    closed_at       INTEGER,
+    payout_signature TEXT
+);
+CREATE TABLE IF NOT EXISTS participants (
+    round_id        INTEGER NOT NULL,
+    wallet          TEXT NOT NULL,
+    ticket_count    INTEGER NOT NULL,
+    usd_value       INTEGER NOT NULL,
+    first_entry_slot INTEGER NOT NULL,
+    PRIMARY KEY (round_id, wallet)
+);
+CREATE TABLE IF NOT EXISTS tickets (
+    signature       TEXT NOT NULL,
+    ix_index        INTEGER NOT NULL,
+    round_id        INTEGER NOT NULL,
+    slot            INTEGER NOT NULL,
+    block_time      INTEGER,
+    wallet          TEXT NOT NULL,
+    kind            TEXT NOT NULL,
+    ticket_count    INTEGER NOT NULL,
+    usd_value       INTEGER NOT NULL,
+    PRIMARY KEY (signature, ix_index)
+);
+CREATE TABLE IF NOT EXISTS draws (
+    snapshot_number INTEGER PRIMARY KEY,
+    round_id        INTEGER NOT NULL,
+    signature       TEXT NOT NULL,
+    slot            INTEGER NOT NULL,
+    block_time      INTEGER,
+    seed            TEXT NOT NULL,
+    pepe_ball_count INTEGER NOT NULL,
+    parity          TEXT NOT NULL,
+    total_participants INTEGER NOT NULL,
+    total_tickets   INTEGER NOT NULL,
+    main_winner     TEXT,
+    minor_winners   TEXT
+);
+CREATE TABLE IF NOT EXISTS payouts (
+    signature       TEXT PRIMARY KEY,
+    round_id        INTEGER NOT NULL,
+    slot            INTEGER NOT NULL,
+    block_time      INTEGER,
+    snapshot_number INTEGER NOT NULL,
+    seed            TEXT NOT NULL,
+    jackpot_amount  INTEGER NOT NULL,
+    main_winner     TEXT NOT NULL,
+    minor_winners   TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS token_events (
+    signature       TEXT NOT NULL,
+    ix_index        INTEGER NOT NULL,
+    slot            INTEGER NOT NULL,
+    block_time      INTEGER,
+    program         TEXT NOT NULL,
+    kind            TEXT NOT NULL,
+    amount          INTEGER,
+    PRIMARY KEY (signature, ix_index)
+);
+CREATE TABLE IF NOT EXISTS lottery_state (
+    id              INTEGER PRIMARY KEY CHECK (id = 1),
+    slot            INTEGER NOT NULL,
+    json            TEXT NOT NULL
+);
+";
+
+pub fn open(path: &Path) -> Result<Connection> {
+    let conn = Connection::open(path)?;
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    conn.pragma_update(None, "busy_timeout", 5000)?;
+    conn.execute_batch(SCHEMA)?;
+    Ok(conn)
+}
+
+pub fn cursor(conn: &Connection, program: &str) -> Result<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT last_signature FROM cursors WHERE program = ?1",
+            [program],
+            |r| r.get(0),
+        )
+        .optional()?)
+}
+
+pub fn set_cursor(conn: &Connection, program: &str, signature: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO cursors (program, last_signature) VALUES (?1, ?2)
+         ON CONFLICT(program) DO UPDATE SET last_signature = excluded.last_signature",
+        params![program, signature],
+    )?;
+    Ok(())
+}
+
+/// Current open round, created on first use.
+pub fn open_round(conn: &Connection, slot: u64) -> Result<i64> {
+    let id: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM rounds WHERE status = 'open' ORDER BY id DESC LIMIT 1",
+            [],
+            |r| r.get(0),
+        )
+        .optional()?;
+    match id {
+        Some(id) => Ok(id),
+        None => {
+            conn.execute("INSERT INTO rounds (status, opened_slot) VALUES ('open', ?1)", [slot as i64])?;
+            Ok(conn.last_insert_rowid())
+        }
+    }
+}
+
+pub struct TicketRow<'a> {
+    pub signature: &'a str,
+    pub ix_index: usize,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub wallet: &'a str,
+    pub kind: &'a str,
+    pub ticket_count: u32,
+    pub usd_value: u64,
+}
+
+pub fn insert_ticket(conn: &Connection, t: &TicketRow) -> Result<()> {
+    let round_id = open_round(conn, t.slot)?;
+    let inserted = conn.execute(
+        "INSERT OR IGNORE INTO tickets
+         (signature, ix_index, round_id, slot, block_time, wallet, kind, ticket_count, usd_value)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            t.signature,
+            t.ix_index as i64,
+            round_id,
+            t.slot as i64,
+            t.block_time,
+            t.wallet,
+            t.kind,
+            t.ticket_count,
+            t.usd_value as i64
+        ],
+    )?;
+    if inserted > 0 {
+        conn.execute(
+            "INSERT INTO participants (round_id, wallet, ticket_count, usd_value, first_entry_slot)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(round_id, wallet) DO UPDATE SET
+                ticket_count = ticket_count + excluded.ticket_count,
+                usd_value = usd_value + excluded.usd_value",
+            params![round_id, t.wallet, t.ticket_count, t.usd_value as i64, t.slot as i64],
+        )?;
+    }
+    Ok(())
+}
+
+pub struct DrawRow<'a> {
+    pub snapshot_number: u64,
+    pub signature: &'a str,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub seed: u64,
+    pub pepe_ball_count: u8,
+    pub parity: &'a str,
+    pub total_participants: u64,
+    pub total_tickets: u64,
+}
+
+pub fn insert_draw(conn: &Connection, d: &DrawRow) -> Result<()> {
+    let round_id = open_round(conn, d.slot)?;
+    conn.execute(
+        "INSERT OR IGNORE INTO draws
+         (snapshot_number, round_id, signature, slot, block_time, seed, pepe_ball_count, parity,
+          total_participants, total_tickets)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            d.snapshot_number as i64,
+            round_id,
+            d.signature,
+            d.slot as i64,
+            d.block_time,
+            d.seed.to_string(),
+            d.pepe_ball_count,
+            d.parity,
+            d.total_participants as i64,
+            d.total_tickets as i64
+        ],
+    )?;
+    Ok(())
+}
+
+/// `set_winners` attaches to the latest draw of the open round.
+pub fn set_draw_winners(conn: &Connection, main_winner: &str, minor_winners: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE draws SET main_winner = ?1, minor_winners = ?2
+         WHERE snapshot_number = (SELECT MAX(snapshot_number) FROM draws)",
+        params![main_winner, minor_winners],
+    )?;
+    Ok(())
+}
+
+pub struct PayoutRow<'a> {
+    pub signature: &'a str,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub snapshot_number: u64,
+    pub seed: u64,
+    pub jackpot_amount: u64,
+    pub main_winner: &'a str,
+    pub minor_winners: &'a str,
+}
+
+/// Records the payout and rolls over to a fresh round.
+pub fn insert_payout(conn: &Connection, p: &PayoutRow) -> Result<()> {
+    let round_id = open_round(conn, p.slot)?;
+    let inserted = conn.execute(
+        "INSERT OR IGNORE INTO payouts
+         (signature, round_id, slot, block_time, snapshot_number, seed, jackpot_amount, main_winner, minor_winners)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            p.signature,
+            round_id,
+            p.slot as i64,
+            p.block_time,
+            p.snapshot_number as i64,
+            p.seed.to_string(),
+            p.jackpot_amount as i64,
+            p.main_winner,
+            p.minor_winners
+        ],
+    )?;
+    if inserted > 0 {
+        conn.execute(
+            "UPDATE rounds SET status = 'paid', closed_slot = ?1, closed_at = ?2, payout_signature = ?3
+             WHERE id = ?4",
+            params![p.slot as i64, p.block_time, p.signature, round_id],
+        )?;
+        conn.execute("INSERT INTO rounds (status, opened_slot) VALUES ('open', ?1)", [p.slot as i64])?;
+    }
+    Ok(())
+}
+
+pub struct TokenEventRow<'a> {
+    pub signature: &'a str,
+    pub ix_index: usize,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub program: &'a str,
+    pub kind: &'a str,
+    pub amount: Option<u64>,
+}
+
+pub fn insert_token_event(conn: &Connection, e: &TokenEventRow) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO token_events (signature, ix_index, slot, block_time, program, kind, amount)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            e.signature,
+            e.ix_index as i64,
+            e.slot as i64,
+            e.block_time,
+            e.program,
+            e.kind,
+            e.amount.map(|a| a as i64)
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn set_lottery_state(conn: &Connection, slot: u64, json: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO lottery_state (id, slot, json) VALUES (1, ?1, ?2)
+         ON CONFLICT(id) DO UPDATE SET slot = excluded.slot, json = excluded.json",
+        params![slot as i64, json],
+    )?;
+    Ok(())
+}
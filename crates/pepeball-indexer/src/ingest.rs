@@ -0,0 +1,347 @@
+//! Poll loop: new signatures per program -> decoded instructions/events -> SQLite.
+//! Plain JSON-RPC only (no Helius APIs), so any endpoint works, including
+//! `solana-test-validator`.
+
+use crate::db;
+use anyhow::{Context, Result};
+use pepeball_sdk::accounts;
+use pepeball_sdk::decode::{self, HarvestIx, LotteryEvent, LotteryIx, TokenIx};
+use pepeball_sdk::programs::lottery;
+use pepeball_sdk::{pda, program_ids};
+use rusqlite::Connection;
+use serde_json::json;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::UiTransactionEncoding;
+use std::str::FromStr;
+
+/// Signatures per `getSignaturesForAddress` page.
+const PAGE_LIMIT: usize = 1000;
+
+pub struct Ingest {
+    pub client: RpcClient,
+    pub conn: Connection,
+    /// (entry_min, tier2_min, tier3_min) cents from the last lottery refresh, for
+    /// USD entries that predate the `UsdEntryTiered` event.
+    tiers: (u64, u64, u64),
+}
+
+/// One confirmed, successful transaction with resolved account keys.
+struct Tx {
+    signature: String,
+    slot: u64,
+    block_time: Option<i64>,
+    account_keys: Vec<Pubkey>,
+    instructions: Vec<(Pubkey, Vec<u8>, Vec<usize>)>,
+    logs: Vec<String>,
+}
+
+impl Ingest {
+    pub fn new(client: RpcClient, conn: Connection) -> Self {
+        Self { client, conn, tiers: (0, 0, 0) }
+    }
+
+    /// Lottery state first so pre-event USD entries are tiered with current thresholds.
+    pub fn tick(&mut self) -> Result<()> {
+        self.refresh_lottery_state()?;
+        for program in [program_ids::LOTTERY, program_ids::PEPBALL_TOKEN, program_ids::TAX_HARVEST] {
+            self.sync_program(&program)?;
+        }
+        Ok(())
+    }
+
+    /// New signatures since the stored cursor, oldest first.
+    fn new_signatures(&self, program: &Pubkey) -> Result<Vec<String>> {
+        let until = db::cursor(&self.conn, &program.to_string())?
+            .map(|s| Signature::from_str(&s))
+            .transpose()
+            .context("bad cursor signature")?;
+        let mut before = None;
+        let mut out = Vec::new();
+        loop {
+            let page = self.client.get_signatures_for_address_with_config(
+                program,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: Some(PAGE_LIMIT),
+                    commitment: Some(self.client.commitment()),
+                },
+            )?;
+            let full = page.len() == PAGE_LIMIT;
+            before = page.last().map(|s| Signature::from_str(&s.signature)).transpose()?;
+            out.extend(page.into_iter().filter(|s| s.err.is_none()).map(|s| s.signature));
+            if !full {
+                break;
+            }
+        }
+        out.reverse();
+        Ok(out)
+    }
+
+    fn sync_program(&mut self, program: &Pubkey) -> Result<()> {
+        for sig in self.new_signatures(program)? {
+            if let Some(tx) = self.fetch_tx(&sig)? {
+                let db_tx = self.conn.transaction()?;
+                apply(&db_tx, &tx, self.tiers)?;
+                db::set_cursor(&db_tx, &program.to_string(), &sig)?;
+                db_tx.commit()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fetch_tx(&self, sig: &str) -> Result<Option<Tx>> {
+        let signature = Signature::from_str(sig)?;
+        let confirmed = self.client.get_transaction_with_config(
+            &signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(self.client.commitment()),
+                max_supported_transaction_version: Some(0),
+            },
+        )?;
+        let meta = match confirmed.transaction.meta {
+            Some(meta) if meta.err.is_none() => meta,
+            _ => return Ok(None),
+        };
+        let versioned = confirmed
+            .transaction
+            .transaction
+            .decode()
+            .context("undecodable transaction")?;
+
+        let mut account_keys = versioned.message.static_account_keys().to_vec();
+        if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+            for key in loaded.writable.iter().chain(loaded.readonly.iter()) {
+                account_keys.push(Pubkey::from_str(key)?);
+            }
+        }
+        let instructions = versioned
+            .message
+            .instructions()
+            .iter()
+            .map(|ix| {
+                (
+                    account_keys[ix.program_id_index as usize],
+                    ix.data.clone(),
+                    ix.accounts.iter().map(|a| *a as usize).collect(),
+                )
+            })
+            .collect();
+
+        Ok(Some(Tx {
+            signature: sig.to_string(),
+            slot: confirmed.slot,
+            block_time: confirmed.block_time,
+            account_keys,
+            instructions,
+            logs: match meta.log_messages {
+                OptionSerializer::Some(logs) => logs,
+                _ => Vec::new(),
+            },
+        }))
+    }
+
+    /// Latest lottery account, stored as the JSON the `/lottery-state` API serves.
+    fn refresh_lottery_state(&mut self) -> Result<()> {
+        let resp = self
+            .client
+            .get_account_with_commitment(&pda::lottery().0, self.client.commitment())?;
+        let Some(account) = resp.value else {
+            return Ok(());
+        };
+        let l = accounts::decode_lottery(&account.data)?;
+        self.tiers = (l.entry_min_cents, l.tier2_min_cents, l.tier3_min_cents);
+        let state = json!({
+            "jackpot": l.jackpot_amount.to_string(),
+            "carryOver": l.carry_over_amount.to_string(),
+            "lastSnapshot": l.last_snapshot.to_string(),
+            "totalSnapshots": l.total_snapshots.to_string(),
+            "totalParticipants": l.total_participants.to_string(),
+            "totalTickets": l.total_tickets.to_string(),
+            "isActive": l.is_active,
+            "isFastMode": l.is_fast_mode,
+            "pepeBallCount": l.pepe_ball_count,
+            "rolloverCount": l.rollover_count,
+            "snapshotSeed": l.snapshot_seed.to_string(),
+            "winners": {
+                "mainWinner": l.winners.main_winner.map(|w| w.to_string()),
+                "minorWinners": l.winners.minor_winners.iter().map(|w| w.to_string()).collect::<Vec<_>>(),
+            },
+        });
+        db::set_lottery_state(&self.conn, resp.context.slot, &state.to_string())
+    }
+}
+
+/// Position of the `participant` account in each entry instruction, in the
+/// order of its Anchor `Accounts` struct. A relayer or fee payer may sign
+/// too, so the wallet is never taken from the signers.
+fn participant_index(ix: &LotteryIx) -> Option<usize> {
+    match ix {
+        // EnterLottery / UpdateParticipant: lottery, participant_account, participant
+        LotteryIx::EnterLottery(_) | LotteryIx::EnterLotteryWithUsdValue(_) | LotteryIx::UpdateParticipantTickets(_) => {
+            Some(2)
+        }
+        // lottery, paid_entry, participant_account, vault, dev_wallet, participant
+        LotteryIx::EnterLotteryPaidSol(_) => Some(5),
+        // lottery, paid_entry, participant_account, vault, payment_mint,
+        // participant_token_account, vault_token_account, dev_token_account, participant
+        LotteryIx::EnterLotteryPaidToken(_) => Some(8),
+        _ => None,
+    }
+}
+
+/// Entry wallet: the instruction's `participant` account.
+fn participant_of(tx: &Tx, ix: &LotteryIx, accounts: &[usize]) -> Option<String> {
+    let key = accounts.get(participant_index(ix)?)?;
+    tx.account_keys.get(*key).map(|k| k.to_string())
+}
+
+fn apply(conn: &Connection, tx: &Tx, tiers: (u64, u64, u64)) -> Result<()> {
+    for (ix_index, (program, data, accounts)) in tx.instructions.iter().enumerate() {
+        if *program == program_ids::LOTTERY {
+            apply_lottery_ix(conn, tx, ix_index, data, accounts, tiers)?;
+        } else if *program == program_ids::PEPBALL_TOKEN {
+            if let Some(ix) = decode::decode_token_ix(data) {
+                let amount = match &ix {
+                    TokenIx::TransferWithTax(a) => Some(a.amount),
+                    TokenIx::BatchTransferWithTax(a) => Some(a.amounts.iter().sum()),
+                    _ => None,
+                };
+                db::insert_token_event(conn, &token_event(tx, ix_index, "pepball_token", ix.name(), amount))?;
+            }
+        } else if *program == program_ids::TAX_HARVEST {
+            if let Some(ix) = decode::decode_harvest_ix(data) {
+                let amount = match &ix {
+                    HarvestIx::ProcessHarvest(a) => Some(a.amount),
+                    _ => None,
+                };
+                db::insert_token_event(conn, &token_event(tx, ix_index, "tax_harvest", ix.name(), amount))?;
+            }
+        }
+    }
+
+    for payload in decode::event_payloads(&tx.logs) {
+        match decode::decode_lottery_event(&payload) {
+            Some(LotteryEvent::SnapshotTaken(e)) => {
+                let parity = if e.pepe_ball_count % 2 == 1 { "ODD" } else { "EVEN" };
+                db::insert_draw(
+                    conn,
+                    &db::DrawRow {
+                        snapshot_number: e.snapshot_number,
+                        signature: &tx.signature,
+                        slot: tx.slot,
+                        block_time: tx.block_time,
+                        seed: e.seed,
+                        pepe_ball_count: e.pepe_ball_count,
+                        parity,
+                        total_participants: e.total_participants,
+                        total_tickets: e.total_tickets,
+                    },
+                )?;
+            }
+            Some(LotteryEvent::RoundPaidOut(e)) => {
+                let minors = serde_json::to_string(
+                    &e.minor_winners.iter().map(|w| w.to_string()).collect::<Vec<_>>(),
+                )?;
+                db::insert_payout(
+                    conn,
+                    &db::PayoutRow {
+                        signature: &tx.signature,
+                        slot: tx.slot,
+                        block_time: tx.block_time,
+                        snapshot_number: e.snapshot_number,
+                        seed: e.seed,
+                        jackpot_amount: e.jackpot_amount,
+                        main_winner: &e.main_winner.to_string(),
+                        minor_winners: &minors,
+                    },
+                )?;
+            }
+            Some(LotteryEvent::UsdEntryTiered(_)) | None => {}
+        }
+    }
+    Ok(())
+}
+
+fn token_event<'a>(
+    tx: &'a Tx,
+    ix_index: usize,
+    program: &'a str,
+    kind: &'a str,
+    amount: Option<u64>,
+) -> db::TokenEventRow<'a> {
+    db::TokenEventRow {
+        signature: &tx.signature,
+        ix_index,
+        slot: tx.slot,
+        block_time: tx.block_time,
+        program,
+        kind,
+        amount,
+    }
+}
+
+/// Tickets the program granted this instruction's USD entry, from its
+/// `UsdEntryTiered` event. A wallet can enter this way once per round, so the
+/// event is matched by wallet. Entries from before the event existed fall back
+/// to the current thresholds.
+fn tiered_tickets(tx: &Tx, wallet: &str) -> Option<u32> {
+    decode::event_payloads(&tx.logs).iter().find_map(|payload| match decode::decode_lottery_event(payload) {
+        Some(LotteryEvent::UsdEntryTiered(e)) if e.wallet.to_string() == wallet => Some(e.ticket_count),
+        _ => None,
+    })
+}
+
+fn apply_lottery_ix(
+    conn: &Connection,
+    tx: &Tx,
+    ix_index: usize,
+    data: &[u8],
+    accounts: &[usize],
+    (entry_min, tier2_min, tier3_min): (u64, u64, u64),
+) -> Result<()> {
+    let Some(ix) = decode::decode_lottery_ix(data) else {
+        return Ok(());
+    };
+    let (ticket_count, usd_value) = match &ix {
+        LotteryIx::EnterLottery(a) => (a.ticket_count, 0),
+        LotteryIx::EnterLotteryPaidSol(a) => (a.ticket_count, 0),
+        LotteryIx::EnterLotteryPaidToken(a) => (a.ticket_count, 0),
+        LotteryIx::UpdateParticipantTickets(a) => (a.ticket_count, a.usd_value),
+        LotteryIx::EnterLotteryWithUsdValue(a) => (
+            participant_of(tx, &ix, accounts).and_then(|wallet| tiered_tickets(tx, &wallet)).unwrap_or_else(|| {
+                lottery::calculate_tickets_from_usd_value(a.usd_value, entry_min, tier2_min, tier3_min)
+            }),
+            a.usd_value,
+        ),
+        LotteryIx::SetWinners(a) => {
+            let minors = serde_json::to_string(
+                &a.minor_winners.iter().map(|w| w.to_string()).collect::<Vec<_>>(),
+            )?;
+            db::set_draw_winners(conn, &a.main_winner.to_string(), &minors)?;
+            return Ok(());
+        }
+        _ => return Ok(()),
+    };
+    let Some(wallet) = participant_of(tx, &ix, accounts) else {
+        return Ok(());
+    };
+    db::insert_ticket(
+        conn,
+        &db::TicketRow {
+            signature: &tx.signature,
+            ix_index,
+            slot: tx.slot,
+            block_time: tx.block_time,
+            wallet: &wallet,
+            kind: ix.name(),
+            ticket_count,
+            usd_value,
+        },
+    )
+}
@@ -0,0 +1,62 @@
+//! `pepeball-indexer` — follows the lottery, token and tax-harvest programs over
+//! plain JSON-RPC, stores rounds/tickets/draws/payouts in SQLite and serves them as JSON.
+//! Can replace `api/lottery-state.js` (same response shape); point `--rpc-url` at `solana-test-validator` for local runs.
+
+mod api;
+mod db;
+mod ingest;
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use ingest::Ingest;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(name = "pepeball-indexer", version, about = "PEPEBALL indexer and JSON API")]
+struct Cli {
+    #[arg(long, short = 'u', env = "PEPEBALL_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    rpc_url: String,
+    /// SQLite database file
+    #[arg(long, env = "PEPEBALL_INDEXER_DB", default_value = "pepeball-indexer.db")]
+    db: PathBuf,
+    /// API listen address
+    #[arg(long, default_value = "127.0.0.1:8787")]
+    listen: String,
+    /// Seconds between polls
+    #[arg(long, default_value_t = 5)]
+    poll_secs: u64,
+    /// Index once and exit (no API)
+    #[arg(long)]
+    once: bool,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let client = RpcClient::new_with_commitment(cli.rpc_url.clone(), CommitmentConfig::confirmed());
+    let mut ingest = Ingest::new(client, db::open(&cli.db)?);
+
+    if cli.once {
+        return ingest.tick();
+    }
+
+    let db_path = cli.db.clone();
+    let listen = cli.listen.clone();
+    let api = thread::spawn(move || api::serve(&db_path, &listen));
+
+    loop {
+        if api.is_finished() {
+            return match api.join() {
+                Ok(result) => result,
+                Err(_) => Err(anyhow!("API thread panicked")),
+            };
+        }
+        if let Err(e) = ingest.tick() {
+            eprintln!("ingest error: {e:#}");
+        }
+        thread::sleep(Duration::from_secs(cli.poll_secs));
+    }
+}
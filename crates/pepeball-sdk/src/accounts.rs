@@ -0,0 +1,86 @@
+//! Account decoders. Each checks the 8-byte Anchor discriminator before deserializing.
+
+use crate::error::SdkError;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeAmount;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_2022::spl_token_2022::state::Account as Token2022Account;
+
+pub use ::game_registry::GameRegistry;
+pub use ::lottery::{Lottery, PaidEntryConfig, ParticipantAccount};
+pub use ::lp_manager::LPManager;
+pub use ::pepball_token::{
+    AmmPools, BalanceCheckpoints, Capability, Checkpoint, CheckpointHistory, Exemption, ExemptionList, LaunchGuard, LaunchProtection, TaxDirection, TaxSplit, TaxStats, TokenInfo,
+    PermitNonce, VestingCategory, VestingSchedule,
+};
+pub use ::tax_harvest::HarvestConfig;
+
+fn decode<T: AccountDeserialize>(data: &[u8], name: &'static str) -> Result<T, SdkError> {
+    let mut buf = data;
+    T::try_deserialize(&mut buf).map_err(|_| SdkError::InvalidAccountData(name))
+}
+
+pub fn decode_lottery(data: &[u8]) -> Result<Lottery, SdkError> {
+    decode(data, "Lottery")
+}
+
+pub fn decode_participant(data: &[u8]) -> Result<ParticipantAccount, SdkError> {
+    decode(data, "ParticipantAccount")
+}
+
+pub fn decode_paid_entry(data: &[u8]) -> Result<PaidEntryConfig, SdkError> {
+    decode(data, "PaidEntryConfig")
+}
+
+pub fn decode_token_info(data: &[u8]) -> Result<TokenInfo, SdkError> {
+    decode(data, "TokenInfo")
+}
+
+pub fn decode_exemption_list(data: &[u8]) -> Result<ExemptionList, SdkError> {
+    decode(data, "ExemptionList")
+}
+
+pub fn decode_amm_pools(data: &[u8]) -> Result<AmmPools, SdkError> {
+    decode(data, "AmmPools")
+}
+
+pub fn decode_launch_guard(data: &[u8]) -> Result<LaunchGuard, SdkError> {
+    decode(data, "LaunchGuard")
+}
+
+pub fn decode_vesting(data: &[u8]) -> Result<VestingSchedule, SdkError> {
+    decode(data, "VestingSchedule")
+}
+
+pub fn decode_permit_nonce(data: &[u8]) -> Result<PermitNonce, SdkError> {
+    decode(data, "PermitNonce")
+}
+
+pub fn decode_checkpoints(data: &[u8]) -> Result<BalanceCheckpoints, SdkError> {
+    decode(data, "BalanceCheckpoints")
+}
+
+/// pepball-hook's checkpoints, kept for Token-2022 mints.
+pub fn decode_hook_checkpoints(data: &[u8]) -> Result<::pepball_hook::BalanceCheckpoints, SdkError> {
+    decode(data, "BalanceCheckpoints")
+}
+
+pub fn decode_harvest_config(data: &[u8]) -> Result<HarvestConfig, SdkError> {
+    decode(data, "HarvestConfig")
+}
+
+pub fn decode_lp_manager(data: &[u8]) -> Result<LPManager, SdkError> {
+    decode(data, "LPManager")
+}
+
+pub fn decode_game_registry(data: &[u8]) -> Result<GameRegistry, SdkError> {
+    decode(data, "GameRegistry")
+}
+
+/// Transfer fees withheld on a Token-2022 token account; `None` if the data is
+/// not a token account with the TransferFeeAmount extension.
+pub fn withheld_fee(data: &[u8]) -> Option<u64> {
+    let state = StateWithExtensions::<Token2022Account>::unpack(data).ok()?;
+    let amount = state.get_extension::<TransferFeeAmount>().ok()?;
+    Some(amount.withheld_amount.into())
+}
@@ -0,0 +1,194 @@
+//! Decode instruction data and Anchor events (`Program data:` logs) from
+//! confirmed transactions — used by the indexer and anything replaying history —
+//! and return data (`Program return:` logs) from simulations.
+
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::Engine;
+
+fn parse<T: Discriminator + AnchorDeserialize>(data: &[u8]) -> Option<T> {
+    if data.len() < 8 || data[..8] != T::DISCRIMINATOR {
+        return None;
+    }
+    T::deserialize(&mut &data[8..]).ok()
+}
+
+macro_rules! decoder {
+    ($(#[$meta:meta])* $decode:ident -> $enum:ident from $module:ident { $($variant:ident),* $(,)? }) => {
+        $(#[$meta])*
+        pub enum $enum {
+            $($variant($module::$variant),)*
+        }
+
+        impl $enum {
+            /// Instruction/event name as in the program source, e.g. `TakeSnapshot`.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant(_) => stringify!($variant),)*
+                }
+            }
+        }
+
+        pub fn $decode(data: &[u8]) -> Option<$enum> {
+            $(
+                if let Some(v) = parse::<$module::$variant>(data) {
+                    return Some($enum::$variant(v));
+                }
+            )*
+            None
+        }
+    };
+}
+
+use ::game_registry::instruction as registry_ix;
+use ::lottery as lottery_events;
+use ::lottery::instruction as lottery_ix;
+use ::lp_manager::instruction as lp_manager_ix;
+use ::pepball_hook::instruction as hook_ix;
+use ::pepball_token as token_events;
+use ::pepball_token::instruction as token_ix;
+use ::tax_harvest::instruction as harvest_ix;
+
+decoder!(
+    decode_lottery_ix -> LotteryIx from lottery_ix {
+        InitializeLottery,
+        SetTierThresholds,
+        EnterLotteryWithUsdValue,
+        EnterLottery,
+        UpdateParticipantTickets,
+        InitializePaidEntry,
+        UpdatePaidEntry,
+        EnterLotteryPaidSol,
+        EnterLotteryPaidToken,
+        TakeSnapshot,
+        SetWinners,
+        PayoutWinners,
+        UpdateFeesCollected,
+        EmergencyPauseLottery,
+        ConfigureTiming,
+        UpdateJackpotAmount,
+        WithdrawPaidEntrySol,
+        WithdrawPaidEntryToken,
+        CloseLottery,
+    }
+);
+
+decoder!(
+    decode_token_ix -> TokenIx from token_ix {
+        InitializeToken,
+        TransferWithTax,
+        TransferWithTaxPermit,
+        BatchTransferWithTax,
+        QuoteTransfer,
+        CirculatingSupply,
+        CreateVesting,
+        ClaimVested,
+        RevokeVesting,
+        WithdrawWithheldFees,
+        SetJackpotVaultOwner,
+        AddExemption,
+        RemoveExemption,
+        SetTaxRates,
+        RegisterAmmPool,
+        UnregisterAmmPool,
+        ConfigureLaunchProtection,
+        UpdateMetadataUri,
+        ProposeAdmin,
+        AcceptAdmin,
+        RenounceCapability,
+        RenounceAdmin,
+        EmergencyPause,
+    }
+);
+
+decoder!(
+    /// Token-2022's own `Execute` calls use the interface discriminator and
+    /// are not decoded here.
+    decode_hook_ix -> HookIx from hook_ix {
+        InitializeExtraAccountMetaList,
+        TransferHook,
+        CheckpointBalance,
+    }
+);
+
+decoder!(
+    decode_harvest_ix -> HarvestIx from harvest_ix {
+        Initialize,
+        ProcessHarvest,
+    }
+);
+
+decoder!(
+    decode_lp_manager_ix -> LpManagerIx from lp_manager_ix {
+        InitializeLpManager,
+        FundJackpot,
+        ConvertFeesToSol,
+        BoostJackpot,
+        EmergencyWithdraw,
+        InitializeOrcaPosition,
+        CalculateDelta,
+        AutoRebalance,
+        UpdatePositionTicks,
+        SetTargetDelta,
+    }
+);
+
+decoder!(
+    decode_registry_ix -> RegistryIx from registry_ix {
+        InitializeRegistry,
+        RegisterMints,
+        SealRegistry,
+    }
+);
+
+decoder!(
+    /// Events emitted by the lottery program.
+    decode_lottery_event -> LotteryEvent from lottery_events {
+        SnapshotTaken,
+        RoundPaidOut,
+        UsdEntryTiered,
+    }
+);
+
+decoder!(
+    /// Events emitted by pepball-token.
+    decode_token_event -> TokenEvent from token_events {
+        TaxedTransfer,
+        BatchTaxedTransfer,
+        VestingCreated,
+        VestingClaimed,
+        VestingRevoked,
+        ExemptionAdded,
+        ExemptionRemoved,
+    }
+);
+
+/// Return data `program` set, from its `Program return: <program> <base64>`
+/// log line (the last one wins, as in the runtime).
+pub fn return_data<'a>(logs: impl IntoIterator<Item = &'a String>, program: &Pubkey) -> Option<Vec<u8>> {
+    let prefix = format!("Program return: {program} ");
+    logs.into_iter()
+        .filter_map(|line| line.strip_prefix(&prefix))
+        .last()
+        .and_then(|b64| base64::engine::general_purpose::STANDARD.decode(b64).ok())
+}
+
+/// Result of a simulated `quote_transfer`.
+pub fn transfer_quote<'a>(logs: impl IntoIterator<Item = &'a String>) -> Option<::pepball_token::TransferQuote> {
+    let data = return_data(logs, &crate::program_ids::PEPBALL_TOKEN)?;
+    AnchorDeserialize::deserialize(&mut data.as_slice()).ok()
+}
+
+/// Result of a simulated `circulating_supply`.
+pub fn circulating_supply<'a>(logs: impl IntoIterator<Item = &'a String>) -> Option<u64> {
+    let data = return_data(logs, &crate::program_ids::PEPBALL_TOKEN)?;
+    AnchorDeserialize::deserialize(&mut data.as_slice()).ok()
+}
+
+/// Raw payloads of every `Program data: <base64>` log line.
+pub fn event_payloads<'a>(logs: impl IntoIterator<Item = &'a String>) -> Vec<Vec<u8>> {
+    logs.into_iter()
+        .filter_map(|line| line.strip_prefix("Program data: "))
+        .filter_map(|b64| base64::engine::general_purpose::STANDARD.decode(b64).ok())
+        .collect()
+}
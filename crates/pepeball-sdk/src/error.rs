@@ -0,0 +1,187 @@
+//! Map raw `custom program error: 0x...` codes back to typed program errors.
+
+use crate::program_ids;
+use anchor_lang::solana_program::pubkey::Pubkey;
+
+use ::game_registry::RegistryError;
+use ::lottery::ErrorCode as LotteryError;
+use ::lp_manager::ErrorCode as LpManagerError;
+use ::pepball_hook::HookError;
+use ::pepball_token::ErrorCode as TokenError;
+use ::tax_harvest::HarvestError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SdkError {
+    #[error("account data is not a valid {0}")]
+    InvalidAccountData(&'static str),
+}
+
+/// A custom error returned by one of the PEPEBALL programs.
+#[derive(Debug, Clone, Copy)]
+pub enum PepeballError {
+    Lottery(LotteryError),
+    Token(TokenError),
+    Hook(HookError),
+    Harvest(HarvestError),
+    LpManager(LpManagerError),
+    Registry(RegistryError),
+}
+
+const LOTTERY_ERRORS: &[LotteryError] = &[
+    LotteryError::LotteryInactive,
+    LotteryError::DrawTooEarly,
+    LotteryError::NoWinners,
+    LotteryError::NotEnoughParticipants,
+    LotteryError::Unauthorized,
+    LotteryError::InsufficientValue,
+    LotteryError::InvalidConfig,
+    LotteryError::PaidEntryDisabled,
+    LotteryError::TicketCapExceeded,
+    LotteryError::InvalidPaymentAccount,
+    LotteryError::InsufficientPoolBalance,
+    LotteryError::MathOverflow,
+    LotteryError::WinnersMismatch,
+    LotteryError::PaidEntryRequired,
+];
+
+const TOKEN_ERRORS: &[TokenError] = &[
+    TokenError::AlreadyRenounced,
+    TokenError::Unauthorized,
+    TokenError::TransfersPaused,
+    TokenError::AmountTooSmall,
+    TokenError::InvalidTaxCalculation,
+    TokenError::TransferFeeModeRequired,
+    TokenError::InvalidTaxDestination,
+    TokenError::NoWithheldFees,
+    TokenError::InvalidDecimals,
+    TokenError::MintMismatch,
+    TokenError::InvalidExemption,
+    TokenError::ExemptionListFull,
+    TokenError::ExemptionNotFound,
+    TokenError::TaxRateTooHigh,
+    TokenError::TransferRateFixedByMint,
+    TokenError::AmmPoolListFull,
+    TokenError::AmmPoolNotFound,
+    TokenError::InvalidBatch,
+    TokenError::InvalidLaunchWindow,
+    TokenError::LaunchNotStarted,
+    TokenError::MaxTransactionExceeded,
+    TokenError::MaxWalletExceeded,
+    TokenError::WalletCooldown,
+    TokenError::RenounceWhilePaused,
+    TokenError::CapabilityRenounced,
+    TokenError::MetadataTooLong,
+    TokenError::InvalidVestingSchedule,
+    TokenError::NothingVested,
+    TokenError::VestingNotRevocable,
+    TokenError::InvalidPermit,
+    TokenError::PermitExpired,
+    TokenError::InvalidPermitNonce,
+    TokenError::InvalidCheckpointAccount,
+    TokenError::LaunchWindowStarted,
+    TokenError::BatchToAmmPool,
+    TokenError::CheckpointHistoryTooShort,
+    TokenError::MetadataAccountsRequired,
+    TokenError::CooldownTableFull,
+];
+
+const HOOK_ERRORS: &[HookError] = &[
+    HookError::NotTransferring,
+    HookError::TransfersPaused,
+    HookError::AmountTooSmall,
+    HookError::LaunchNotStarted,
+    HookError::MaxTransactionExceeded,
+    HookError::MaxWalletExceeded,
+    HookError::WalletCooldown,
+    HookError::InvalidCheckpointAccount,
+    HookError::MintNotHooked,
+    HookError::CooldownTableFull,
+];
+
+const HARVEST_ERRORS: &[HarvestError] = &[
+    HarvestError::InvalidAmount,
+    HarvestError::InsufficientVaultBalance,
+    HarvestError::VaultNotOwnedByProgram,
+    HarvestError::InvalidSwapAccounts,
+    HarvestError::Token2022NotSupported,
+];
+
+const LP_MANAGER_ERRORS: &[LpManagerError] = &[
+    LpManagerError::NotInitialized,
+    LpManagerError::InsufficientFunds,
+    LpManagerError::Unauthorized,
+    LpManagerError::PositionNotInitialized,
+    LpManagerError::InvalidTickRange,
+];
+
+const REGISTRY_ERRORS: &[RegistryError] = &[
+    RegistryError::AlreadySealed,
+    RegistryError::Unauthorized,
+    RegistryError::InvalidMint,
+    RegistryError::MintsNotRegistered,
+    RegistryError::YinMustEqualMaster,
+];
+
+fn find<E: Copy + Into<u32>>(table: &[E], code: u32) -> Option<E> {
+    table.iter().copied().find(|e| (*e).into() == code)
+}
+
+impl PepeballError {
+    /// Decode `code` (e.g. 6004) raised by `program_id`. Anchor framework
+    /// errors (< 6000) and unknown programs return `None`.
+    pub fn from_code(program_id: &Pubkey, code: u32) -> Option<Self> {
+        if *program_id == program_ids::LOTTERY {
+            find(LOTTERY_ERRORS, code).map(Self::Lottery)
+        } else if *program_id == program_ids::PEPBALL_TOKEN {
+            find(TOKEN_ERRORS, code).map(Self::Token)
+        } else if *program_id == program_ids::PEPBALL_HOOK {
+            find(HOOK_ERRORS, code).map(Self::Hook)
+        } else if *program_id == program_ids::TAX_HARVEST {
+            find(HARVEST_ERRORS, code).map(Self::Harvest)
+        } else if *program_id == program_ids::LP_MANAGER {
+            find(LP_MANAGER_ERRORS, code).map(Self::LpManager)
+        } else if *program_id == program_ids::GAME_REGISTRY {
+            find(REGISTRY_ERRORS, code).map(Self::Registry)
+        } else {
+            None
+        }
+    }
+
+    pub fn code(&self) -> u32 {
+        match *self {
+            Self::Lottery(e) => e.into(),
+            Self::Token(e) => e.into(),
+            Self::Hook(e) => e.into(),
+            Self::Harvest(e) => e.into(),
+            Self::LpManager(e) => e.into(),
+            Self::Registry(e) => e.into(),
+        }
+    }
+
+    /// Variant name as it appears in the IDL, e.g. `DrawTooEarly`.
+    pub fn name(&self) -> String {
+        match self {
+            Self::Lottery(e) => e.name(),
+            Self::Token(e) => e.name(),
+            Self::Hook(e) => e.name(),
+            Self::Harvest(e) => e.name(),
+            Self::LpManager(e) => e.name(),
+            Self::Registry(e) => e.name(),
+        }
+    }
+}
+
+impl std::fmt::Display for PepeballError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Lottery(e) => write!(f, "lottery {} ({}): {}", e.name(), self.code(), e),
+            Self::Token(e) => write!(f, "pepball-token {} ({}): {}", e.name(), self.code(), e),
+            Self::Hook(e) => write!(f, "pepball-hook {} ({}): {}", e.name(), self.code(), e),
+            Self::Harvest(e) => write!(f, "tax-harvest {} ({}): {}", e.name(), self.code(), e),
+            Self::LpManager(e) => write!(f, "lp-manager {} ({}): {}", e.name(), self.code(), e),
+            Self::Registry(e) => write!(f, "game-registry {} ({}): {}", e.name(), self.code(), e),
+        }
+    }
+}
+
+impl std::error::Error for PepeballError {}
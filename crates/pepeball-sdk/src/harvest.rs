@@ -0,0 +1,174 @@
+//! tax-harvest instruction builders.
+
+use crate::error::SdkError;
+use crate::{build_ix, pda, program_ids};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::solana_program::system_program;
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token;
+use anchor_spl::token::spl_token::native_mint;
+use tax_harvest::swap::{Dex, OrcaWhirlpool};
+use tax_harvest::{accounts, instruction};
+
+/// Ticks per Orca tick array.
+const TICK_ARRAY_SIZE: i32 = 88;
+
+pub fn initialize(authority: &Pubkey, jackpot_sol_dest: Pubkey) -> Instruction {
+    build_ix(
+        program_ids::TAX_HARVEST,
+        accounts::Initialize {
+            config: pda::config().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::Initialize { jackpot_sol_dest },
+    )
+}
+
+/// Pool a harvest sells through, with the accounts its on-chain adapter reads.
+#[derive(Clone, Debug)]
+pub enum SwapRoute {
+    OrcaWhirlpool(WhirlpoolAccounts),
+}
+
+impl SwapRoute {
+    fn dex(&self) -> Dex {
+        match self {
+            SwapRoute::OrcaWhirlpool(_) => Dex::OrcaWhirlpool,
+        }
+    }
+
+    fn remaining_accounts(&self) -> Vec<AccountMeta> {
+        match self {
+            SwapRoute::OrcaWhirlpool(pool) => {
+                let mut metas = vec![
+                    AccountMeta::new_readonly(program_ids::ORCA_WHIRLPOOL, false),
+                    AccountMeta::new(pool.whirlpool, false),
+                    AccountMeta::new(pool.token_vault_a, false),
+                    AccountMeta::new(pool.token_vault_b, false),
+                ];
+                metas.extend(pool.tick_arrays.iter().map(|key| AccountMeta::new(*key, false)));
+                metas.push(AccountMeta::new(pool.oracle, false));
+                metas
+            }
+        }
+    }
+}
+
+/// Orca Whirlpool accounts for one sale.
+#[derive(Clone, Debug)]
+pub struct WhirlpoolAccounts {
+    pub whirlpool: Pubkey,
+    pub token_vault_a: Pubkey,
+    pub token_vault_b: Pubkey,
+    pub tick_arrays: [Pubkey; 3],
+    pub oracle: Pubkey,
+}
+
+/// The fields of an Orca `Whirlpool` account a harvest needs.
+#[derive(Clone, Debug)]
+pub struct WhirlpoolPool {
+    pub tick_spacing: u16,
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+    pub token_mint_a: Pubkey,
+    pub token_vault_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_vault_b: Pubkey,
+}
+
+impl WhirlpoolPool {
+    pub fn decode(data: &[u8]) -> Result<Self, SdkError> {
+        if data.len() < OrcaWhirlpool::TOKEN_VAULT_B + 32 || data[..8] != OrcaWhirlpool::ACCOUNT_DISCRIMINATOR {
+            return Err(SdkError::InvalidAccountData("Whirlpool"));
+        }
+        let bytes = |offset: usize, len: usize| &data[offset..offset + len];
+        let key = |offset: usize| Pubkey::new_from_array(bytes(offset, 32).try_into().unwrap());
+        Ok(Self {
+            tick_spacing: u16::from_le_bytes(bytes(OrcaWhirlpool::TICK_SPACING, 2).try_into().unwrap()),
+            sqrt_price: u128::from_le_bytes(bytes(OrcaWhirlpool::SQRT_PRICE, 16).try_into().unwrap()),
+            tick_current_index: i32::from_le_bytes(bytes(OrcaWhirlpool::TICK_CURRENT_INDEX, 4).try_into().unwrap()),
+            token_mint_a: key(OrcaWhirlpool::TOKEN_MINT_A),
+            token_vault_a: key(OrcaWhirlpool::TOKEN_VAULT_A),
+            token_mint_b: key(OrcaWhirlpool::TOKEN_MINT_B),
+            token_vault_b: key(OrcaWhirlpool::TOKEN_VAULT_B),
+        })
+    }
+
+    /// Whether selling `token_mint` swaps A -> B; `None` unless this is its SOL pool.
+    pub fn sells_a_to_b(&self, token_mint: &Pubkey) -> Option<bool> {
+        match (self.token_mint_a, self.token_mint_b) {
+            (a, b) if a == *token_mint && b == native_mint::ID => Some(true),
+            (a, b) if a == native_mint::ID && b == *token_mint => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Lamports `amount` of `token_mint` fetches at the current price, before
+    /// fees and price impact.
+    pub fn spot_sol_out(&self, token_mint: &Pubkey, amount: u64) -> Option<u64> {
+        let price = (self.sqrt_price as f64 / 2f64.powi(64)).powi(2); // B per A
+        let out = if self.sells_a_to_b(token_mint)? {
+            amount as f64 * price
+        } else {
+            amount as f64 / price
+        };
+        Some(out as u64)
+    }
+
+    /// Accounts for selling `token_mint` into the pool at `whirlpool`: the
+    /// current tick array and the next two in the swap's direction.
+    pub fn route(&self, whirlpool: &Pubkey, token_mint: &Pubkey) -> Option<SwapRoute> {
+        let a_to_b = self.sells_a_to_b(token_mint)?;
+        let ticks_per_array = TICK_ARRAY_SIZE * self.tick_spacing as i32;
+        let start = self.tick_current_index.div_euclid(ticks_per_array) * ticks_per_array;
+        let step = if a_to_b { -ticks_per_array } else { ticks_per_array };
+        let tick_arrays = [0, 1, 2].map(|i| pda::whirlpool_tick_array(whirlpool, start + i * step).0);
+        Some(SwapRoute::OrcaWhirlpool(WhirlpoolAccounts {
+            whirlpool: *whirlpool,
+            token_vault_a: self.token_vault_a,
+            token_vault_b: self.token_vault_b,
+            tick_arrays,
+            oracle: pda::whirlpool_oracle(whirlpool).0,
+        }))
+    }
+}
+
+/// Sells `amount` from `tax_vault` along `route` and pays the SOL to the
+/// jackpot; fails if the sale fetches less than `min_sol_out` lamports.
+/// `authority` must be the harvest config authority.
+pub fn process_harvest(
+    authority: &Pubkey,
+    tax_vault: &Pubkey,
+    token_mint: &Pubkey,
+    jackpot_sol_dest: &Pubkey,
+    amount: u64,
+    min_sol_out: u64,
+    route: &SwapRoute,
+) -> Instruction {
+    let vault_authority = pda::vault_authority().0;
+    let mut ix = build_ix(
+        program_ids::TAX_HARVEST,
+        accounts::ProcessHarvest {
+            config: pda::config().0,
+            tax_vault: *tax_vault,
+            vault_authority,
+            token_mint: *token_mint,
+            wsol_account: get_associated_token_address(&vault_authority, &native_mint::ID),
+            native_mint: native_mint::ID,
+            jackpot_sol_dest: *jackpot_sol_dest,
+            authority: *authority,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ProcessHarvest {
+            amount,
+            min_sol_out,
+            dex: route.dex(),
+        },
+    );
+    ix.accounts.extend(route.remaining_accounts());
+    ix
+}
@@ -0,0 +1,66 @@
+//! pepball-hook: the Token-2022 transfer hook of the fee mint.
+
+use crate::{build_ix, pda, program_ids};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::solana_program::system_program;
+use pepball_hook::{accounts, instruction};
+
+/// One-time setup after `initialize_token_2022`: without the account list
+/// every transfer of the mint fails.
+pub fn initialize_extra_account_meta_list(payer: &Pubkey, mint: &Pubkey, token_info: &Pubkey) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_HOOK,
+        accounts::InitializeExtraAccountMetaList {
+            payer: *payer,
+            extra_account_meta_list: pda::extra_account_metas(mint).0,
+            mint: *mint,
+            token_info: *token_info,
+            launch_guard: pda::hook_launch_guard(mint).0,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeExtraAccountMetaList {},
+    )
+}
+
+/// Records the balance of `token_account` (`owner`'s ATA of the mint) at the
+/// current slot; `payer` signs and funds the checkpoints on first use. From
+/// then on the hook updates them on every transfer.
+pub fn checkpoint_balance(mint: &Pubkey, owner: &Pubkey, token_account: &Pubkey, payer: &Pubkey) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_HOOK,
+        accounts::CheckpointBalance {
+            mint: *mint,
+            checkpoints: pda::hook_checkpoints(mint, owner).0,
+            owner: *owner,
+            token_account: *token_account,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::CheckpointBalance {},
+    )
+}
+
+/// Accounts to append to a Token-2022 `transfer_checked` of the mint so
+/// Token-2022 can run the hook: the resolved extras, the hook program and
+/// its account list, in the order the interface expects. `source_owner` and
+/// `destination_owner` own the two token accounts; the hook also reads the
+/// source owner's account.
+pub fn transfer_accounts(
+    mint: &Pubkey,
+    token_info: &Pubkey,
+    source_owner: &Pubkey,
+    destination_owner: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(*token_info, false),
+        AccountMeta::new_readonly(pda::exemption_list(token_info).0, false),
+        AccountMeta::new_readonly(pda::amm_pools(token_info).0, false),
+        AccountMeta::new(pda::hook_launch_guard(mint).0, false),
+        AccountMeta::new(pda::hook_checkpoints(mint, source_owner).0, false),
+        AccountMeta::new(pda::hook_checkpoints(mint, destination_owner).0, false),
+        AccountMeta::new_readonly(*source_owner, false),
+        AccountMeta::new_readonly(program_ids::PEPBALL_HOOK, false),
+        AccountMeta::new_readonly(pda::extra_account_metas(mint).0, false),
+    ]
+}
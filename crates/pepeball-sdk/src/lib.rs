@@ -0,0 +1,60 @@
+//! Typed client for the PEPEBALL programs.
+//! Builders wrap each program's Anchor `instruction`/`accounts` modules so
+//! account order and discriminators always match the deployed IDL — no more
+//! hand-rolled instruction bytes in `scripts/`.
+
+pub mod accounts;
+pub mod decode;
+pub mod error;
+pub mod harvest;
+pub mod hook;
+pub mod lottery;
+pub mod lp_manager;
+pub mod pda;
+pub mod registry;
+pub mod token;
+
+pub use anchor_lang;
+pub use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+pub use anchor_lang::solana_program::pubkey::Pubkey;
+pub use error::{PepeballError, SdkError};
+
+/// Program IDs (same on devnet, localnet and mainnet — see Anchor.toml).
+pub mod program_ids {
+    pub use ::game_registry::ID as GAME_REGISTRY;
+    pub use ::lottery::ID as LOTTERY;
+    pub use ::lp_manager::ID as LP_MANAGER;
+    pub use ::pepball_hook::ID as PEPBALL_HOOK;
+    pub use ::pepball_token::ID as PEPBALL_TOKEN;
+    pub use ::tax_harvest::ID as TAX_HARVEST;
+    /// SPL Token-2022, the program of transfer-fee mode mints
+    pub use ::anchor_spl::token_2022::ID as TOKEN_2022;
+    /// Orca Whirlpool, the DEX tax-harvest sells through
+    pub use ::tax_harvest::swap::WHIRLPOOL_PROGRAM_ID as ORCA_WHIRLPOOL;
+    /// Metaplex Token Metadata, which holds a legacy mint's metadata
+    pub use ::pepball_token::metaplex::TOKEN_METADATA_PROGRAM_ID as TOKEN_METADATA;
+}
+
+/// Program crates, re-exported for their account and argument types.
+pub mod programs {
+    pub use ::game_registry;
+    pub use ::lottery;
+    pub use ::lp_manager;
+    pub use ::pepball_hook;
+    pub use ::pepball_token;
+    pub use ::tax_harvest;
+}
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+
+pub(crate) fn build_ix(
+    program_id: Pubkey,
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
@@ -0,0 +1,357 @@
+//! Lottery instruction builders. The lottery PDA is derived, so callers only pass signers.
+
+use crate::{build_ix, pda, program_ids};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::solana_program::system_program;
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token;
+use lottery::{accounts, instruction};
+
+pub use lottery::PaidEntryBucket;
+
+pub fn initialize_lottery(
+    admin: &Pubkey,
+    jackpot_amount: u64,
+    entry_min_cents: u64,
+    tier2_min_cents: u64,
+    tier3_min_cents: u64,
+) -> Instruction {
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::InitializeLottery {
+            lottery: pda::lottery().0,
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeLottery {
+            jackpot_amount,
+            entry_min_cents,
+            tier2_min_cents,
+            tier3_min_cents,
+        },
+    )
+}
+
+pub fn set_tier_thresholds(
+    admin: &Pubkey,
+    entry_min_cents: u64,
+    tier2_min_cents: u64,
+    tier3_min_cents: u64,
+) -> Instruction {
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::UpdateTierThresholds {
+            lottery: pda::lottery().0,
+            admin: *admin,
+        },
+        instruction::SetTierThresholds {
+            entry_min_cents,
+            tier2_min_cents,
+            tier3_min_cents,
+        },
+    )
+}
+
+fn enter_accounts(participant: &Pubkey) -> accounts::EnterLottery {
+    let lottery = pda::lottery().0;
+    accounts::EnterLottery {
+        lottery,
+        participant_account: pda::participant(&lottery, participant).0,
+        participant: *participant,
+        system_program: system_program::ID,
+        paid_entry: pda::paid_entry(&lottery).0,
+        admin: None,
+    }
+}
+
+pub fn enter_lottery(participant: &Pubkey, ticket_count: u32) -> Instruction {
+    build_ix(
+        program_ids::LOTTERY,
+        enter_accounts(participant),
+        instruction::EnterLottery { ticket_count },
+    )
+}
+
+pub fn enter_lottery_with_usd_value(participant: &Pubkey, usd_value: u64) -> Instruction {
+    build_ix(
+        program_ids::LOTTERY,
+        enter_accounts(participant),
+        instruction::EnterLotteryWithUsdValue { usd_value },
+    )
+}
+
+pub fn update_participant_tickets(
+    participant: &Pubkey,
+    ticket_count: u32,
+    usd_value: u64,
+) -> Instruction {
+    let lottery = pda::lottery().0;
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::UpdateParticipant {
+            lottery,
+            participant_account: pda::participant(&lottery, participant).0,
+            participant: *participant,
+            paid_entry: pda::paid_entry(&lottery).0,
+            admin: None,
+        },
+        instruction::UpdateParticipantTickets {
+            ticket_count,
+            usd_value,
+        },
+    )
+}
+
+/// Adds the lottery admin as co-signer of a free entry (`enter_lottery`,
+/// `enter_lottery_with_usd_value`, `update_participant_tickets`), which the
+/// program requires once paid entry is set up.
+pub fn with_admin_cosign(mut ix: Instruction, admin: &Pubkey) -> Instruction {
+    let last = ix.accounts.last_mut().expect("free entry instruction has accounts");
+    *last = AccountMeta::new_readonly(*admin, true);
+    ix
+}
+
+/// `args.payment_mint = None` configures SOL entries.
+pub fn initialize_paid_entry(admin: &Pubkey, args: instruction::InitializePaidEntry) -> Instruction {
+    let lottery = pda::lottery().0;
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::InitializePaidEntry {
+            lottery,
+            paid_entry: pda::paid_entry(&lottery).0,
+            vault: pda::lottery_vault(&lottery).0,
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        args,
+    )
+}
+
+pub fn update_paid_entry(admin: &Pubkey, args: instruction::UpdatePaidEntry) -> Instruction {
+    let lottery = pda::lottery().0;
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::UpdatePaidEntry {
+            lottery,
+            paid_entry: pda::paid_entry(&lottery).0,
+            admin: *admin,
+        },
+        args,
+    )
+}
+
+pub fn enter_lottery_paid_sol(
+    participant: &Pubkey,
+    dev_wallet: &Pubkey,
+    ticket_count: u32,
+) -> Instruction {
+    let lottery = pda::lottery().0;
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::EnterLotteryPaidSol {
+            lottery,
+            paid_entry: pda::paid_entry(&lottery).0,
+            participant_account: pda::participant(&lottery, participant).0,
+            vault: pda::lottery_vault(&lottery).0,
+            dev_wallet: *dev_wallet,
+            participant: *participant,
+            system_program: system_program::ID,
+        },
+        instruction::EnterLotteryPaidSol { ticket_count },
+    )
+}
+
+/// Pays from the participant's ATA; dev share goes to `dev_wallet`'s ATA.
+pub fn enter_lottery_paid_token(
+    participant: &Pubkey,
+    payment_mint: &Pubkey,
+    dev_wallet: &Pubkey,
+    ticket_count: u32,
+) -> Instruction {
+    let lottery = pda::lottery().0;
+    let vault = pda::lottery_vault(&lottery).0;
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::EnterLotteryPaidToken {
+            lottery,
+            paid_entry: pda::paid_entry(&lottery).0,
+            participant_account: pda::participant(&lottery, participant).0,
+            vault,
+            payment_mint: *payment_mint,
+            participant_token_account: get_associated_token_address(participant, payment_mint),
+            vault_token_account: get_associated_token_address(&vault, payment_mint),
+            dev_token_account: get_associated_token_address(dev_wallet, payment_mint),
+            participant: *participant,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::EnterLotteryPaidToken { ticket_count },
+    )
+}
+
+pub fn withdraw_paid_entry_sol(
+    admin: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+    bucket: PaidEntryBucket,
+) -> Instruction {
+    let lottery = pda::lottery().0;
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::WithdrawPaidEntrySol {
+            lottery,
+            paid_entry: pda::paid_entry(&lottery).0,
+            vault: pda::lottery_vault(&lottery).0,
+            destination: *destination,
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        instruction::WithdrawPaidEntrySol { amount, bucket },
+    )
+}
+
+/// `destination` is a token account of `payment_mint`.
+pub fn withdraw_paid_entry_token(
+    admin: &Pubkey,
+    payment_mint: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+    bucket: PaidEntryBucket,
+) -> Instruction {
+    let lottery = pda::lottery().0;
+    let vault = pda::lottery_vault(&lottery).0;
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::WithdrawPaidEntryToken {
+            lottery,
+            paid_entry: pda::paid_entry(&lottery).0,
+            vault,
+            payment_mint: *payment_mint,
+            vault_token_account: get_associated_token_address(&vault, payment_mint),
+            destination: *destination,
+            admin: *admin,
+            token_program: token::ID,
+        },
+        instruction::WithdrawPaidEntryToken { amount, bucket },
+    )
+}
+
+pub fn take_snapshot(admin: &Pubkey) -> Instruction {
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::TakeSnapshot {
+            lottery: pda::lottery().0,
+            admin: *admin,
+        },
+        instruction::TakeSnapshot {},
+    )
+}
+
+pub fn set_winners(admin: &Pubkey, main_winner: Pubkey, minor_winners: Vec<Pubkey>) -> Instruction {
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::SetWinners {
+            lottery: pda::lottery().0,
+            admin: *admin,
+        },
+        instruction::SetWinners {
+            main_winner,
+            minor_winners,
+        },
+    )
+}
+
+/// `set_winners` with on-chain verification: `participant_accounts` must be every
+/// participant PDA of the round, sorted by wallet.
+pub fn set_winners_verified(
+    admin: &Pubkey,
+    main_winner: Pubkey,
+    minor_winners: Vec<Pubkey>,
+    participant_accounts: &[Pubkey],
+) -> Instruction {
+    let mut ix = set_winners(admin, main_winner, minor_winners);
+    ix.accounts.extend(
+        participant_accounts
+            .iter()
+            .map(|p| AccountMeta::new_readonly(*p, false)),
+    );
+    ix
+}
+
+pub fn payout_winners(admin: &Pubkey) -> Instruction {
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::PayoutWinners {
+            lottery: pda::lottery().0,
+            admin: *admin,
+        },
+        instruction::PayoutWinners {},
+    )
+}
+
+pub fn update_fees_collected(admin: &Pubkey, new_fees: u64) -> Instruction {
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::UpdateFeesCollected {
+            lottery: pda::lottery().0,
+            admin: *admin,
+        },
+        instruction::UpdateFeesCollected { new_fees },
+    )
+}
+
+/// Toggles: a second call resumes the lottery.
+pub fn emergency_pause_lottery(admin: &Pubkey) -> Instruction {
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::EmergencyPauseLottery {
+            lottery: pda::lottery().0,
+            admin: *admin,
+        },
+        instruction::EmergencyPauseLottery {},
+    )
+}
+
+pub fn configure_timing(
+    admin: &Pubkey,
+    base_snapshot_interval: u64,
+    fast_snapshot_interval: u64,
+    fast_mode_threshold: u64,
+) -> Instruction {
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::ConfigureTiming {
+            lottery: pda::lottery().0,
+            admin: *admin,
+        },
+        instruction::ConfigureTiming {
+            base_snapshot_interval,
+            fast_snapshot_interval,
+            fast_mode_threshold,
+        },
+    )
+}
+
+pub fn update_jackpot_amount(admin: &Pubkey, new_amount: u64) -> Instruction {
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::UpdateJackpotAmount {
+            lottery: pda::lottery().0,
+            admin: *admin,
+        },
+        instruction::UpdateJackpotAmount { new_amount },
+    )
+}
+
+pub fn close_lottery(admin: &Pubkey) -> Instruction {
+    build_ix(
+        program_ids::LOTTERY,
+        accounts::CloseLottery {
+            lottery: pda::lottery().0,
+            admin: *admin,
+        },
+        instruction::CloseLottery {},
+    )
+}
@@ -0,0 +1,166 @@
+//! lp-manager instruction builders. `lp_manager` is a keypair account.
+
+use crate::{build_ix, program_ids};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::solana_program::system_program;
+use anchor_spl::token;
+use lp_manager::{accounts, instruction};
+
+/// `lp_manager` must also sign (it is created with `init`).
+pub fn initialize_lp_manager(lp_manager: &Pubkey, admin: &Pubkey) -> Instruction {
+    build_ix(
+        program_ids::LP_MANAGER,
+        accounts::InitializeLPManager {
+            lp_manager: *lp_manager,
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeLpManager {},
+    )
+}
+
+pub fn fund_jackpot(
+    lp_manager: &Pubkey,
+    lp_pool: &Pubkey,
+    jackpot_pool: &Pubkey,
+    admin: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build_ix(
+        program_ids::LP_MANAGER,
+        accounts::FundJackpot {
+            lp_manager: *lp_manager,
+            lp_pool: *lp_pool,
+            jackpot_pool: *jackpot_pool,
+            admin: *admin,
+            token_program: token::ID,
+        },
+        instruction::FundJackpot { amount },
+    )
+}
+
+pub fn convert_fees_to_sol(lp_manager: &Pubkey, admin: &Pubkey, token_amount: u64) -> Instruction {
+    build_ix(
+        program_ids::LP_MANAGER,
+        accounts::ConvertFeesToSol {
+            lp_manager: *lp_manager,
+            admin: *admin,
+        },
+        instruction::ConvertFeesToSol { token_amount },
+    )
+}
+
+pub fn boost_jackpot(lp_manager: &Pubkey, admin: &Pubkey, sol_amount: u64) -> Instruction {
+    build_ix(
+        program_ids::LP_MANAGER,
+        accounts::BoostJackpot {
+            lp_manager: *lp_manager,
+            admin: *admin,
+        },
+        instruction::BoostJackpot { sol_amount },
+    )
+}
+
+pub fn emergency_withdraw(
+    lp_manager: &Pubkey,
+    lp_pool: &Pubkey,
+    admin_token_account: &Pubkey,
+    admin: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build_ix(
+        program_ids::LP_MANAGER,
+        accounts::EmergencyWithdraw {
+            lp_manager: *lp_manager,
+            lp_pool: *lp_pool,
+            admin_token_account: *admin_token_account,
+            admin: *admin,
+            token_program: token::ID,
+        },
+        instruction::EmergencyWithdraw { amount },
+    )
+}
+
+pub fn initialize_orca_position(
+    lp_manager: &Pubkey,
+    admin: &Pubkey,
+    tick_lower: i32,
+    tick_upper: i32,
+) -> Instruction {
+    build_ix(
+        program_ids::LP_MANAGER,
+        accounts::InitializeOrcaPosition {
+            lp_manager: *lp_manager,
+            admin: *admin,
+        },
+        instruction::InitializeOrcaPosition {
+            tick_lower,
+            tick_upper,
+        },
+    )
+}
+
+pub fn calculate_delta(lp_manager: &Pubkey, current_price: u64, liquidity_amount: u64) -> Instruction {
+    build_ix(
+        program_ids::LP_MANAGER,
+        accounts::CalculateDelta {
+            lp_manager: *lp_manager,
+        },
+        instruction::CalculateDelta {
+            current_price,
+            liquidity_amount,
+        },
+    )
+}
+
+pub fn auto_rebalance(
+    lp_manager: &Pubkey,
+    admin: &Pubkey,
+    current_price: u64,
+    current_delta: i64,
+    rebalance_threshold: i64,
+) -> Instruction {
+    build_ix(
+        program_ids::LP_MANAGER,
+        accounts::AutoRebalance {
+            lp_manager: *lp_manager,
+            admin: *admin,
+        },
+        instruction::AutoRebalance {
+            _current_price: current_price,
+            current_delta,
+            rebalance_threshold,
+        },
+    )
+}
+
+pub fn update_position_ticks(
+    lp_manager: &Pubkey,
+    admin: &Pubkey,
+    new_tick_lower: i32,
+    new_tick_upper: i32,
+) -> Instruction {
+    build_ix(
+        program_ids::LP_MANAGER,
+        accounts::UpdatePositionTicks {
+            lp_manager: *lp_manager,
+            admin: *admin,
+        },
+        instruction::UpdatePositionTicks {
+            new_tick_lower,
+            new_tick_upper,
+        },
+    )
+}
+
+pub fn set_target_delta(lp_manager: &Pubkey, admin: &Pubkey, target_delta: i64) -> Instruction {
+    build_ix(
+        program_ids::LP_MANAGER,
+        accounts::SetTargetDelta {
+            lp_manager: *lp_manager,
+            admin: *admin,
+        },
+        instruction::SetTargetDelta { target_delta },
+    )
+}
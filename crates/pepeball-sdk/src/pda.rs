@@ -0,0 +1,132 @@
+//! PDA derivation. Seeds mirror the `#[account(seeds = ...)]` constraints in `programs/`.
+
+use crate::program_ids;
+use anchor_lang::solana_program::pubkey::Pubkey;
+
+/// Lottery state: `["lottery"]`
+pub fn lottery() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"lottery"], &program_ids::LOTTERY)
+}
+
+/// Per-wallet entry: `["participant", lottery, wallet]`
+pub fn participant(lottery: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"participant", lottery.as_ref(), wallet.as_ref()],
+        &program_ids::LOTTERY,
+    )
+}
+
+/// Paid-entry config: `["paid_entry", lottery]`
+pub fn paid_entry(lottery: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"paid_entry", lottery.as_ref()], &program_ids::LOTTERY)
+}
+
+/// Paid-entry SOL vault and SPL vault authority: `["lottery_vault", lottery]`
+pub fn lottery_vault(lottery: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"lottery_vault", lottery.as_ref()], &program_ids::LOTTERY)
+}
+
+/// Tax-harvest config: `["config"]`
+pub fn config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &program_ids::TAX_HARVEST)
+}
+
+/// Tax-harvest vault signer: `["vault_authority"]`
+pub fn vault_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault_authority"], &program_ids::TAX_HARVEST)
+}
+
+/// Orca Whirlpool tick array starting at `start_tick_index`: `["tick_array", whirlpool, start as decimal]`
+pub fn whirlpool_tick_array(whirlpool: &Pubkey, start_tick_index: i32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"tick_array", whirlpool.as_ref(), start_tick_index.to_string().as_bytes()],
+        &program_ids::ORCA_WHIRLPOOL,
+    )
+}
+
+/// Orca Whirlpool oracle: `["oracle", whirlpool]`
+pub fn whirlpool_oracle(whirlpool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"oracle", whirlpool.as_ref()], &program_ids::ORCA_WHIRLPOOL)
+}
+
+/// Token config bound to its mint: `["token_info", mint]`
+pub fn token_info(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"token_info", mint.as_ref()], &program_ids::PEPBALL_TOKEN)
+}
+
+/// Metaplex metadata of a legacy mint: `["metadata", Token Metadata program, mint]`
+pub fn metaplex_metadata(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"metadata", program_ids::TOKEN_METADATA.as_ref(), mint.as_ref()],
+        &program_ids::TOKEN_METADATA,
+    )
+}
+
+/// Token-2022 fee config and withheld-fee authority: `["fee_authority", mint]`
+pub fn fee_authority(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"fee_authority", mint.as_ref()], &program_ids::PEPBALL_TOKEN)
+}
+
+/// Tax exemptions of a token: `["exemptions", token_info]`
+pub fn exemption_list(token_info: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"exemptions", token_info.as_ref()], &program_ids::PEPBALL_TOKEN)
+}
+
+/// Registered AMM pool token accounts: `["amm_pools", token_info]`
+pub fn amm_pools(token_info: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"amm_pools", token_info.as_ref()], &program_ids::PEPBALL_TOKEN)
+}
+
+/// Launch cooldowns of `transfer_with_tax`: `["launch_guard", token_info]`
+pub fn launch_guard(token_info: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"launch_guard", token_info.as_ref()], &program_ids::PEPBALL_TOKEN)
+}
+
+/// Vesting schedule of a beneficiary: `["vesting", token_info, beneficiary]`
+pub fn vesting(token_info: &Pubkey, beneficiary: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"vesting", token_info.as_ref(), beneficiary.as_ref()],
+        &program_ids::PEPBALL_TOKEN,
+    )
+}
+
+/// Balance history of a wallet's ATA: `["checkpoints", token_info, owner]`
+pub fn checkpoints(token_info: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"checkpoints", token_info.as_ref(), owner.as_ref()],
+        &program_ids::PEPBALL_TOKEN,
+    )
+}
+
+/// Delegate holders approve for signed permits: `["permit_authority", token_info]`
+pub fn permit_authority(token_info: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"permit_authority", token_info.as_ref()], &program_ids::PEPBALL_TOKEN)
+}
+
+/// Next permit nonce of an owner: `["permit_nonce", token_info, owner]`
+pub fn permit_nonce(token_info: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"permit_nonce", token_info.as_ref(), owner.as_ref()],
+        &program_ids::PEPBALL_TOKEN,
+    )
+}
+
+/// Launch cooldowns of Token-2022 transfers: `["launch_guard", mint]` under pepball-hook
+pub fn hook_launch_guard(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"launch_guard", mint.as_ref()], &program_ids::PEPBALL_HOOK)
+}
+
+/// Balance history of Token-2022 transfers: `["checkpoints", mint, owner]` under pepball-hook
+pub fn hook_checkpoints(mint: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"checkpoints", mint.as_ref(), owner.as_ref()], &program_ids::PEPBALL_HOOK)
+}
+
+/// Transfer-hook account list: `["extra-account-metas", mint]` under pepball-hook
+pub fn extra_account_metas(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], &program_ids::PEPBALL_HOOK)
+}
+
+/// Launch registry: `["game_registry"]`
+pub fn game_registry() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"game_registry"], &program_ids::GAME_REGISTRY)
+}
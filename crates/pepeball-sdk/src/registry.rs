@@ -0,0 +1,42 @@
+//! game-registry instruction builders.
+
+use crate::{build_ix, pda, program_ids};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::solana_program::system_program;
+use game_registry::{accounts, instruction};
+
+pub fn initialize_registry(admin: &Pubkey, args: instruction::InitializeRegistry) -> Instruction {
+    build_ix(
+        program_ids::GAME_REGISTRY,
+        accounts::InitializeRegistry {
+            registry: pda::game_registry().0,
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        args,
+    )
+}
+
+pub fn register_mints(admin: &Pubkey, args: instruction::RegisterMints) -> Instruction {
+    build_ix(
+        program_ids::GAME_REGISTRY,
+        accounts::UpdateRegistry {
+            registry: pda::game_registry().0,
+            admin: *admin,
+        },
+        args,
+    )
+}
+
+/// Irreversible.
+pub fn seal_registry(admin: &Pubkey) -> Instruction {
+    build_ix(
+        program_ids::GAME_REGISTRY,
+        accounts::UpdateRegistry {
+            registry: pda::game_registry().0,
+            admin: *admin,
+        },
+        instruction::SealRegistry {},
+    )
+}
@@ -0,0 +1,642 @@
+//! pepball-token instruction builders. `token_info` is the PDA of its mint.
+
+use crate::{build_ix, pda, program_ids};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::solana_program::{ed25519_program, sysvar};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use anchor_spl::{token, token_2022};
+use pepball_token::{accounts, instruction, Capability, TaxDirection};
+pub use pepball_token::permit_message;
+use serde::Deserialize;
+
+/// Where `pump-fun/metadata.json` is served from; the mint's metadata URI.
+pub const METADATA_URI: &str = "https://raw.githubusercontent.com/preseteth/pepeball/main/pump-fun/metadata.json";
+
+/// Name, symbol and URI written at initialization.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    #[serde(skip)]
+    pub uri: String,
+}
+
+/// Name and symbol from `pump-fun/metadata.json`, with [`METADATA_URI`].
+pub fn launch_metadata() -> TokenMetadata {
+    let json = include_str!("../../../pump-fun/metadata.json");
+    TokenMetadata {
+        uri: METADATA_URI.to_string(),
+        ..serde_json::from_str(json).expect("pump-fun/metadata.json")
+    }
+}
+
+/// Creates `mint` (a fresh keypair, must sign) under SPL Token with its
+/// Metaplex metadata, mints the full supply to `distribution_owner`'s ATA
+/// and revokes the mint authority.
+pub fn initialize_token(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    distribution_owner: &Pubkey,
+    metadata: TokenMetadata,
+    decimals: u8,
+    creator_fund_address: Pubkey,
+) -> Instruction {
+    initialize(token::ID, mint, admin, distribution_owner, metadata, decimals, creator_fund_address)
+}
+
+/// Token-2022 mode: as [`initialize_token`], with the TransferFee extension
+/// set to the token tax, pepball-hook as the transfer hook and `metadata` in
+/// the mint's token metadata. Follow with
+/// [`crate::hook::initialize_extra_account_meta_list`].
+pub fn initialize_token_2022(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    distribution_owner: &Pubkey,
+    metadata: TokenMetadata,
+    decimals: u8,
+    creator_fund_address: Pubkey,
+) -> Instruction {
+    initialize(token_2022::ID, mint, admin, distribution_owner, metadata, decimals, creator_fund_address)
+}
+
+fn initialize(
+    token_program: Pubkey,
+    mint: &Pubkey,
+    admin: &Pubkey,
+    distribution_owner: &Pubkey,
+    metadata: TokenMetadata,
+    decimals: u8,
+    creator_fund_address: Pubkey,
+) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::InitializeToken {
+            token_info: pda::token_info(mint).0,
+            exemption_list: pda::exemption_list(&pda::token_info(mint).0).0,
+            amm_pools: pda::amm_pools(&pda::token_info(mint).0).0,
+            launch_guard: pda::launch_guard(&pda::token_info(mint).0).0,
+            admin: *admin,
+            mint: *mint,
+            distribution_owner: *distribution_owner,
+            distribution: get_associated_token_address_with_program_id(distribution_owner, mint, &token_program),
+            token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            metadata: metaplex_metadata(mint, &token_program),
+            token_metadata_program: metaplex_program(&token_program),
+        },
+        instruction::InitializeToken {
+            name: metadata.name,
+            symbol: metadata.symbol,
+            uri: metadata.uri,
+            decimals,
+            creator_fund_address,
+        },
+    )
+}
+
+/// Token accounts for a taxed transfer.
+#[derive(Clone, Copy, Debug)]
+pub struct TaxedTransferAccounts {
+    pub token_info: Pubkey,
+    /// Burn share comes off this mint's supply
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub creator_fund: Pubkey,
+    pub jackpot_pool: Pubkey,
+    /// Owner of `from`; signs and pays for checkpoints created on first use
+    pub authority: Pubkey,
+    /// Owner of `to`
+    pub recipient: Pubkey,
+}
+
+pub fn transfer_with_tax(accts: &TaxedTransferAccounts, amount: u64) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::TransferWithTax {
+            token_info: accts.token_info,
+            exemption_list: pda::exemption_list(&accts.token_info).0,
+            amm_pools: pda::amm_pools(&accts.token_info).0,
+            launch_guard: pda::launch_guard(&accts.token_info).0,
+            mint: accts.mint,
+            from: accts.from,
+            to: accts.to,
+            from_checkpoints: pda::checkpoints(&accts.token_info, &accts.authority).0,
+            to_checkpoints: pda::checkpoints(&accts.token_info, &accts.recipient).0,
+            creator_fund: accts.creator_fund,
+            jackpot_pool: accts.jackpot_pool,
+            authority: accts.authority,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::TransferWithTax { amount },
+    )
+}
+
+/// Lets `permit_authority` move up to `amount` out of `from` (owned by
+/// `owner`, who signs); needed once before relayed permits.
+pub fn approve_permits(token_info: &Pubkey, from: &Pubkey, owner: &Pubkey, amount: u64) -> Instruction {
+    token::spl_token::instruction::approve(
+        &token::ID,
+        from,
+        &pda::permit_authority(token_info).0,
+        owner,
+        &[],
+        amount,
+    )
+    .expect("spl-token approve")
+}
+
+/// The ed25519 precompile check of `owner`'s `signature` over `message`
+/// (from [`permit_message`]); it must directly precede
+/// [`transfer_with_tax_permit`] in the transaction.
+pub fn permit_signature_check(owner: &Pubkey, signature: &[u8; 64], message: &[u8]) -> Instruction {
+    // One signature, all inline: count, padding, offsets, key, signature, message.
+    const DATA_START: u16 = 16;
+    let offsets = [
+        DATA_START + 32,
+        u16::MAX,
+        DATA_START,
+        u16::MAX,
+        DATA_START + 32 + 64,
+        message.len() as u16,
+        u16::MAX,
+    ];
+    let mut data = vec![1, 0];
+    data.extend(offsets.iter().flat_map(|offset| offset.to_le_bytes()));
+    data.extend_from_slice(owner.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+/// `transfer_with_tax` signed off-chain: `accts.authority` is the owner of
+/// `accts.from` who signed [`permit_message`]; `relayer` signs the
+/// transaction and pays for the owner's nonce account and checkpoints on
+/// first use. Send it right after [`permit_signature_check`].
+pub fn transfer_with_tax_permit(
+    accts: &TaxedTransferAccounts,
+    relayer: &Pubkey,
+    amount: u64,
+    nonce: u64,
+    expiry: i64,
+) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::TransferWithTaxPermit {
+            token_info: accts.token_info,
+            exemption_list: pda::exemption_list(&accts.token_info).0,
+            amm_pools: pda::amm_pools(&accts.token_info).0,
+            launch_guard: pda::launch_guard(&accts.token_info).0,
+            mint: accts.mint,
+            from: accts.from,
+            to: accts.to,
+            from_checkpoints: pda::checkpoints(&accts.token_info, &accts.authority).0,
+            to_checkpoints: pda::checkpoints(&accts.token_info, &accts.recipient).0,
+            creator_fund: accts.creator_fund,
+            jackpot_pool: accts.jackpot_pool,
+            permit_authority: pda::permit_authority(&accts.token_info).0,
+            permit_nonce: pda::permit_nonce(&accts.token_info, &accts.authority).0,
+            relayer: *relayer,
+            instructions: sysvar::instructions::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::TransferWithTaxPermit { amount, nonce, expiry },
+    )
+}
+
+/// Pays each `(wallet, amount)` to the wallet's associated token account
+/// from `accts.from`, with one tax quote over the batch; `accts.to` is
+/// unused. `accts.authority` also pays for checkpoints created on the way.
+pub fn batch_transfer_with_tax(accts: &TaxedTransferAccounts, payments: &[(Pubkey, u64)]) -> Instruction {
+    let mut ix = build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::BatchTransferWithTax {
+            token_info: accts.token_info,
+            exemption_list: pda::exemption_list(&accts.token_info).0,
+            amm_pools: pda::amm_pools(&accts.token_info).0,
+            launch_guard: pda::launch_guard(&accts.token_info).0,
+            mint: accts.mint,
+            from: accts.from,
+            from_checkpoints: pda::checkpoints(&accts.token_info, &accts.authority).0,
+            creator_fund: accts.creator_fund,
+            jackpot_pool: accts.jackpot_pool,
+            authority: accts.authority,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::BatchTransferWithTax {
+            amounts: payments.iter().map(|(_, amount)| *amount).collect(),
+        },
+    );
+    ix.accounts.extend(payments.iter().flat_map(|(wallet, _)| {
+        [
+            AccountMeta::new(get_associated_token_address_with_program_id(wallet, &accts.mint, &token::ID), false),
+            AccountMeta::new(pda::checkpoints(&accts.token_info, wallet).0, false),
+        ]
+    }));
+    ix
+}
+
+/// Read-only; simulate it and read the `TransferQuote` with
+/// [`crate::decode::transfer_quote`]. `from`/`to` are token accounts of `mint`.
+pub fn quote_transfer(token_info: &Pubkey, mint: &Pubkey, from: &Pubkey, to: &Pubkey, amount: u64) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::QuoteTransfer {
+            token_info: *token_info,
+            exemption_list: pda::exemption_list(token_info).0,
+            amm_pools: pda::amm_pools(token_info).0,
+            mint: *mint,
+            from: *from,
+            to: *to,
+        },
+        instruction::QuoteTransfer { amount },
+    )
+}
+
+/// Read-only; simulate it and read the supply with
+/// [`crate::decode::circulating_supply`].
+pub fn circulating_supply(token_info: &Pubkey) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::CirculatingSupply { token_info: *token_info },
+        instruction::CirculatingSupply {},
+    )
+}
+
+/// `owner`'s associated token account under the mint's `token_program`.
+pub fn ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+/// Token-2022 associated token account of `owner`.
+pub fn token_2022_ata(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, &token_2022::ID)
+}
+
+/// Fee vault the withheld fees are staged in: the fee authority's Token-2022 ATA.
+pub fn fee_vault(mint: &Pubkey) -> Pubkey {
+    token_2022_ata(&pda::fee_authority(mint).0, mint)
+}
+
+/// Withdraws withheld Token-2022 fees to the jackpot/creator destinations,
+/// harvesting from `sources` (token accounts holding withheld fees) first.
+/// `jackpot_owner` and `creator_owner` are the token info's
+/// `jackpot_vault_owner` and `creator_fund_address`.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_withheld_fees(
+    token_info: &Pubkey,
+    mint: &Pubkey,
+    jackpot_pool: &Pubkey,
+    jackpot_owner: &Pubkey,
+    creator_fund: &Pubkey,
+    creator_owner: &Pubkey,
+    payer: &Pubkey,
+    sources: &[Pubkey],
+) -> Instruction {
+    let fee_authority = pda::fee_authority(mint).0;
+    let mut ix = build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::WithdrawWithheldFees {
+            token_info: *token_info,
+            exemption_list: pda::exemption_list(token_info).0,
+            amm_pools: pda::amm_pools(token_info).0,
+            hook_launch_guard: pda::hook_launch_guard(mint).0,
+            fee_authority_checkpoints: pda::hook_checkpoints(mint, &fee_authority).0,
+            jackpot_checkpoints: pda::hook_checkpoints(mint, jackpot_owner).0,
+            creator_checkpoints: pda::hook_checkpoints(mint, creator_owner).0,
+            mint: *mint,
+            fee_authority,
+            fee_vault: fee_vault(mint),
+            jackpot_pool: *jackpot_pool,
+            creator_fund: *creator_fund,
+            hook_program: program_ids::PEPBALL_HOOK,
+            extra_account_meta_list: pda::extra_account_metas(mint).0,
+            payer: *payer,
+            token_program: token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::WithdrawWithheldFees {},
+    );
+    ix.accounts.extend(sources.iter().map(|s| AccountMeta::new(*s, false)));
+    ix
+}
+
+/// Admin-only until renounce: jackpot share goes to accounts owned by `owner`.
+pub fn set_jackpot_vault_owner(token_info: &Pubkey, admin: &Pubkey, owner: Pubkey) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::SetJackpotVaultOwner {
+            token_info: *token_info,
+            admin: *admin,
+        },
+        instruction::SetJackpotVaultOwner { owner },
+    )
+}
+
+/// Admin-only until renounce: `address` (a token account owner) skips the tax
+/// when sending and/or receiving. Re-adding an address replaces its flags.
+pub fn add_exemption(
+    token_info: &Pubkey,
+    admin: &Pubkey,
+    address: Pubkey,
+    exempt_sender: bool,
+    exempt_recipient: bool,
+) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::UpdateExemptions {
+            token_info: *token_info,
+            exemption_list: pda::exemption_list(token_info).0,
+            admin: *admin,
+        },
+        instruction::AddExemption {
+            address,
+            exempt_sender,
+            exempt_recipient,
+        },
+    )
+}
+
+pub fn remove_exemption(token_info: &Pubkey, admin: &Pubkey, address: Pubkey) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::UpdateExemptions {
+            token_info: *token_info,
+            exemption_list: pda::exemption_list(token_info).0,
+            admin: *admin,
+        },
+        instruction::RemoveExemption { address },
+    )
+}
+
+/// Admin-only until renounce; the total is capped at `MAX_TAX_RATE_BPS`.
+pub fn set_tax_rates(
+    token_info: &Pubkey,
+    admin: &Pubkey,
+    direction: TaxDirection,
+    creator_fund_rate: u16,
+    jackpot_rate: u16,
+    burn_rate: u16,
+) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::SetTaxRates {
+            token_info: *token_info,
+            admin: *admin,
+        },
+        instruction::SetTaxRates {
+            direction,
+            creator_fund_rate,
+            jackpot_rate,
+            burn_rate,
+        },
+    )
+}
+
+/// Admin-only until renounce: `pool` is an AMM pool's token account of the mint.
+pub fn register_amm_pool(token_info: &Pubkey, admin: &Pubkey, pool: Pubkey) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::UpdateAmmPools {
+            token_info: *token_info,
+            amm_pools: pda::amm_pools(token_info).0,
+            admin: *admin,
+        },
+        instruction::RegisterAmmPool { pool },
+    )
+}
+
+pub fn unregister_amm_pool(token_info: &Pubkey, admin: &Pubkey, pool: Pubkey) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::UpdateAmmPools {
+            token_info: *token_info,
+            amm_pools: pda::amm_pools(token_info).0,
+            admin: *admin,
+        },
+        instruction::UnregisterAmmPool { pool },
+    )
+}
+
+/// Admin-only until renounce: anti-bot limits for `window_slots` from
+/// `start_slot`. Zero disables a limit.
+pub fn configure_launch_protection(
+    token_info: &Pubkey,
+    admin: &Pubkey,
+    args: instruction::ConfigureLaunchProtection,
+) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::ConfigureLaunchProtection {
+            token_info: *token_info,
+            admin: *admin,
+        },
+        args,
+    )
+}
+
+/// Escrow of `beneficiary`'s vesting: the schedule PDA's associated token
+/// account under the mint's `token_program`.
+pub fn vesting_escrow(mint: &Pubkey, token_program: &Pubkey, beneficiary: &Pubkey) -> Pubkey {
+    let vesting = pda::vesting(&pda::token_info(mint).0, beneficiary).0;
+    get_associated_token_address_with_program_id(&vesting, mint, token_program)
+}
+
+/// Token-2022 vesting transfers run the hook; a legacy mint needs nothing.
+/// The owners are those of the transfer's source and destination.
+fn with_hook_accounts(
+    mut ix: Instruction,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    source_owner: &Pubkey,
+    destination_owner: &Pubkey,
+) -> Instruction {
+    if *token_program == token_2022::ID {
+        let token_info = pda::token_info(mint).0;
+        ix.accounts.extend(crate::hook::transfer_accounts(mint, &token_info, source_owner, destination_owner));
+    }
+    ix
+}
+
+/// Locks `args.amount` from `source` (owned by `funder`) for `beneficiary`.
+pub fn create_vesting(
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    admin: &Pubkey,
+    funder: &Pubkey,
+    source: &Pubkey,
+    beneficiary: &Pubkey,
+    args: instruction::CreateVesting,
+) -> Instruction {
+    let token_info = pda::token_info(mint).0;
+    let vesting = pda::vesting(&token_info, beneficiary).0;
+    let ix = build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::CreateVesting {
+            token_info,
+            vesting,
+            beneficiary: *beneficiary,
+            escrow: vesting_escrow(mint, token_program, beneficiary),
+            source: *source,
+            funder: *funder,
+            admin: *admin,
+            mint: *mint,
+            token_program: *token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        args,
+    );
+    with_hook_accounts(ix, mint, token_program, funder, &vesting)
+}
+
+/// Signed by `beneficiary`; pays out to any `destination` of the mint,
+/// owned by `destination_owner`.
+pub fn claim_vested(
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    beneficiary: &Pubkey,
+    destination: &Pubkey,
+    destination_owner: &Pubkey,
+) -> Instruction {
+    let token_info = pda::token_info(mint).0;
+    let vesting = pda::vesting(&token_info, beneficiary).0;
+    let ix = build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::ClaimVested {
+            token_info,
+            vesting,
+            beneficiary: *beneficiary,
+            escrow: vesting_escrow(mint, token_program, beneficiary),
+            destination: *destination,
+            mint: *mint,
+            token_program: *token_program,
+        },
+        instruction::ClaimVested {},
+    );
+    with_hook_accounts(ix, mint, token_program, &vesting, destination_owner)
+}
+
+/// Sends the unvested rest of `beneficiary`'s schedule to `refund`, owned
+/// by `refund_owner`.
+pub fn revoke_vesting(
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    admin: &Pubkey,
+    beneficiary: &Pubkey,
+    refund: &Pubkey,
+    refund_owner: &Pubkey,
+) -> Instruction {
+    let token_info = pda::token_info(mint).0;
+    let vesting = pda::vesting(&token_info, beneficiary).0;
+    let ix = build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::RevokeVesting {
+            token_info,
+            vesting,
+            admin: *admin,
+            escrow: vesting_escrow(mint, token_program, beneficiary),
+            refund: *refund,
+            mint: *mint,
+            token_program: *token_program,
+        },
+        instruction::RevokeVesting {},
+    );
+    with_hook_accounts(ix, mint, token_program, &vesting, refund_owner)
+}
+
+/// `token_program` is the mint's (SPL Token or Token-2022).
+pub fn update_metadata_uri(token_info: &Pubkey, admin: &Pubkey, mint: &Pubkey, token_program: &Pubkey, uri: String) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::UpdateMetadataUri {
+            token_info: *token_info,
+            admin: *admin,
+            mint: *mint,
+            token_program: *token_program,
+            system_program: system_program::ID,
+            metadata: metaplex_metadata(mint, token_program),
+            token_metadata_program: metaplex_program(token_program),
+        },
+        instruction::UpdateMetadataUri { uri },
+    )
+}
+
+/// A legacy mint's Metaplex metadata; Token-2022 mints keep theirs in the mint.
+fn metaplex_metadata(mint: &Pubkey, token_program: &Pubkey) -> Option<Pubkey> {
+    (*token_program == token::ID).then(|| pda::metaplex_metadata(mint).0)
+}
+
+fn metaplex_program(token_program: &Pubkey) -> Option<Pubkey> {
+    (*token_program == token::ID).then_some(program_ids::TOKEN_METADATA)
+}
+
+/// Starts a handoff; `new_admin` completes it with [`accept_admin`].
+pub fn propose_admin(token_info: &Pubkey, admin: &Pubkey, new_admin: Pubkey) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::ProposeAdmin {
+            token_info: *token_info,
+            admin: *admin,
+        },
+        instruction::ProposeAdmin { new_admin },
+    )
+}
+
+pub fn accept_admin(token_info: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::AcceptAdmin {
+            token_info: *token_info,
+            new_admin: *new_admin,
+        },
+        instruction::AcceptAdmin {},
+    )
+}
+
+/// Gives up one admin capability for good.
+pub fn renounce_capability(token_info: &Pubkey, admin: &Pubkey, capability: Capability) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::RenounceCapability {
+            token_info: *token_info,
+            admin: *admin,
+        },
+        instruction::RenounceCapability { capability },
+    )
+}
+
+/// Fails while paused.
+pub fn renounce_admin(token_info: &Pubkey, admin: &Pubkey) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::RenounceAdmin {
+            token_info: *token_info,
+            admin: *admin,
+        },
+        instruction::RenounceAdmin {},
+    )
+}
+
+/// Toggles: a second call lifts the pause.
+pub fn emergency_pause(token_info: &Pubkey, admin: &Pubkey) -> Instruction {
+    build_ix(
+        program_ids::PEPBALL_TOKEN,
+        accounts::EmergencyPause {
+            token_info: *token_info,
+            admin: *admin,
+        },
+        instruction::EmergencyPause {},
+    )
+}
@@ -0,0 +1,240 @@
+//! Economics model for PEPEBALL: trading volume -> transfer tax -> jackpot,
+//! snapshots on the on-chain cadence, ODD payouts and EVEN rollovers.
+//!
+//! Every rate and interval comes from the programs (`pepball_token`,
+//! `lottery`) or `pepeball-draw`, so the model moves when they do. What is
+//! assumed rather than on chain: all jackpot tax is harvested to SOL at a flat
+//! SOL price, `fees_collected` tracks that tax, every draw has enough
+//! participants, and the dev share of each payout funds the treasury.
+
+use pepeball_draw::Parity;
+use pepeball_sdk::programs::{lottery, pepball_token};
+use rand::Rng;
+
+const BPS: f64 = 10_000.0;
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+const DAY_SECS: i64 = 24 * 3600;
+/// Volume is accrued and the draw timer checked once per simulated hour.
+const STEP_SECS: i64 = 3600;
+
+/// A trading-volume scenario: `daily_volume_usd` on day 0, compounding by
+/// `daily_growth` (e.g. `-0.02` for a 2%/day fade).
+#[derive(Clone, Debug)]
+pub struct Scenario {
+    pub name: String,
+    pub daily_volume_usd: f64,
+    pub daily_growth: f64,
+}
+
+impl std::str::FromStr for Scenario {
+    type Err = String;
+
+    /// `name:daily_volume_usd[:daily_growth]`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        let number = |v: &str| v.parse::<f64>().map_err(|e| format!("{v}: {e}"));
+        match parts.as_slice() {
+            [name, volume] => Ok(Self {
+                name: name.to_string(),
+                daily_volume_usd: number(volume)?,
+                daily_growth: 0.0,
+            }),
+            [name, volume, growth] => Ok(Self {
+                name: name.to_string(),
+                daily_volume_usd: number(volume)?,
+                daily_growth: number(growth)?,
+            }),
+            _ => Err(format!("expected name:daily_volume_usd[:daily_growth], got {s}")),
+        }
+    }
+}
+
+impl std::fmt::Display for Scenario {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.name, self.daily_volume_usd, self.daily_growth)
+    }
+}
+
+/// Model inputs that are not on chain.
+#[derive(Clone, Debug)]
+pub struct Params {
+    pub days: u32,
+    pub sol_price_usd: f64,
+    /// Log-normal sigma of daily volume around the scenario mean.
+    pub volume_sigma: f64,
+    pub seed_jackpot_sol: f64,
+    pub treasury_sol: f64,
+    pub monthly_burn_usd: f64,
+}
+
+/// Snapshot cadence and rates, defaulting to what the programs ship with.
+#[derive(Clone, Debug)]
+pub struct Rules {
+    pub jackpot_rate_bps: u16,
+    pub creator_fund_rate_bps: u16,
+    pub base_snapshot_interval: u64,
+    pub fast_snapshot_interval: u64,
+    pub fast_mode_threshold: u64,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            jackpot_rate_bps: pepball_token::JACKPOT_RATE_BPS,
+            creator_fund_rate_bps: pepball_token::CREATOR_FUND_RATE_BPS,
+            base_snapshot_interval: lottery::DEFAULT_BASE_SNAPSHOT_INTERVAL,
+            fast_snapshot_interval: lottery::DEFAULT_FAST_SNAPSHOT_INTERVAL,
+            fast_mode_threshold: lottery::DEFAULT_FAST_MODE_THRESHOLD,
+        }
+    }
+}
+
+/// One ODD draw.
+#[derive(Clone, Debug)]
+pub struct Payout {
+    pub day: u32,
+    pub jackpot_sol: f64,
+    /// EVEN draws since the previous payout.
+    pub rollovers: u8,
+}
+
+impl Payout {
+    pub fn main_prize_sol(&self) -> f64 {
+        self.jackpot_sol * pepeball_draw::MAIN_PRIZE_BPS as f64 / BPS
+    }
+
+    /// Per minor winner.
+    pub fn minor_prize_sol(&self) -> f64 {
+        self.jackpot_sol * pepeball_draw::MINOR_PRIZE_BPS as f64 / BPS
+    }
+}
+
+/// Outcome of one simulated run.
+#[derive(Clone, Debug, Default)]
+pub struct Run {
+    pub total_volume_usd: f64,
+    pub jackpot_tax_sol: f64,
+    pub creator_fund_sol: f64,
+    pub draws: u32,
+    pub payouts: Vec<Payout>,
+    pub longest_rollover_streak: u8,
+    pub max_jackpot_sol: f64,
+    /// First day `fees_collected` reached the fast-mode threshold.
+    pub fast_mode_day: Option<u32>,
+    pub final_jackpot_sol: f64,
+    pub treasury_sol: f64,
+    /// First day the treasury could not cover the burn.
+    pub runway_days: Option<u32>,
+}
+
+/// Simulates one run of `params.days` days.
+pub fn simulate(scenario: &Scenario, params: &Params, rules: &Rules, rng: &mut impl Rng) -> Run {
+    let mut run = Run {
+        treasury_sol: params.treasury_sol,
+        ..Run::default()
+    };
+    let mut jackpot = params.seed_jackpot_sol;
+    let mut fees_collected = 0u64;
+    let mut last_snapshot = 0i64;
+    let mut rollover_count = 0u8;
+    let daily_burn_sol = params.monthly_burn_usd / 30.0 / params.sol_price_usd;
+    let steps_per_day = DAY_SECS / STEP_SECS;
+
+    for day in 0..params.days {
+        let mean = scenario.daily_volume_usd * (1.0 + scenario.daily_growth).powi(day as i32);
+        let volume = lognormal(rng, mean, params.volume_sigma);
+        run.total_volume_usd += volume;
+        let step_volume_sol = volume / params.sol_price_usd / steps_per_day as f64;
+
+        for step in 1..=steps_per_day {
+            let now = day as i64 * DAY_SECS + step * STEP_SECS;
+            let tax = step_volume_sol * rules.jackpot_rate_bps as f64 / BPS;
+            jackpot += tax;
+            run.jackpot_tax_sol += tax;
+            run.creator_fund_sol += step_volume_sol * rules.creator_fund_rate_bps as f64 / BPS;
+            fees_collected = fees_collected.saturating_add((tax * LAMPORTS_PER_SOL) as u64);
+            run.max_jackpot_sol = run.max_jackpot_sol.max(jackpot);
+
+            let fast = fees_collected >= rules.fast_mode_threshold;
+            if fast && run.fast_mode_day.is_none() {
+                run.fast_mode_day = Some(day);
+            }
+            let interval = if fast {
+                rules.fast_snapshot_interval
+            } else {
+                rules.base_snapshot_interval
+            };
+            if now - last_snapshot < interval as i64 {
+                continue;
+            }
+
+            // Same mapping as take_snapshot; the seed itself is modelled as uniform.
+            run.draws += 1;
+            let balls = pepeball_draw::ball_count(rng.gen());
+            if pepeball_draw::parity(balls) == Parity::Odd {
+                run.payouts.push(Payout {
+                    day,
+                    jackpot_sol: jackpot,
+                    rollovers: rollover_count,
+                });
+                run.treasury_sol += jackpot * pepeball_draw::DEV_BPS as f64 / BPS;
+                jackpot *= pepeball_draw::ROLLOVER_RESERVE_BPS as f64 / BPS;
+                rollover_count = 0;
+                last_snapshot = now;
+            } else {
+                rollover_count = rollover_count.saturating_add(1);
+                run.longest_rollover_streak = run.longest_rollover_streak.max(rollover_count);
+                last_snapshot = now + pepeball_draw::rollover_extension_secs(rollover_count);
+            }
+        }
+
+        run.treasury_sol -= daily_burn_sol;
+        if run.treasury_sol < 0.0 && run.runway_days.is_none() {
+            run.runway_days = Some(day);
+        }
+    }
+
+    run.final_jackpot_sol = jackpot;
+    run
+}
+
+/// Log-normal sample with the given mean (sigma = 0 returns the mean).
+fn lognormal(rng: &mut impl Rng, mean: f64, sigma: f64) -> f64 {
+    if sigma <= 0.0 {
+        return mean;
+    }
+    // Box-Muller
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+    mean * (sigma * z - sigma * sigma / 2.0).exp()
+}
+
+/// Mean and nearest-rank percentiles of a sample.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Distribution {
+    pub count: usize,
+    pub mean: f64,
+    pub p10: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub max: f64,
+}
+
+impl Distribution {
+    pub fn of(mut values: Vec<f64>) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        values.sort_by(f64::total_cmp);
+        let at = |p: f64| values[((values.len() - 1) as f64 * p).round() as usize];
+        Self {
+            count: values.len(),
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            p10: at(0.10),
+            p50: at(0.50),
+            p90: at(0.90),
+            max: values[values.len() - 1],
+        }
+    }
+}
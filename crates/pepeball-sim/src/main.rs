@@ -0,0 +1,164 @@
+//! `pepeball-sim` — Monte Carlo over trading-volume scenarios.
+//!
+//! Prints a summary CSV (jackpot size, rollover streak and runway
+//! distributions per scenario) to stdout; `--out DIR` also writes `runs.csv`
+//! (one row per run) and `payouts.csv` (one row per ODD draw).
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use pepeball_sim::{simulate, Distribution, Params, Rules, Run, Scenario};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "pepeball-sim", version, about = "Simulate PEPEBALL jackpot growth and rollovers")]
+struct Cli {
+    /// `name:daily_volume_usd[:daily_growth]`, repeatable
+    #[arg(long = "scenario", default_values_t = default_scenarios())]
+    scenarios: Vec<Scenario>,
+    /// Runs per scenario
+    #[arg(long, default_value_t = 1_000)]
+    runs: u32,
+    #[arg(long, default_value_t = 180)]
+    days: u32,
+    #[arg(long, default_value_t = 150.0)]
+    sol_price_usd: f64,
+    /// Day-to-day volume noise (log-normal sigma, 0 = flat)
+    #[arg(long, default_value_t = 0.5)]
+    volume_sigma: f64,
+    /// Jackpot in SOL before any tax arrives
+    #[arg(long, default_value_t = 0.0)]
+    seed_jackpot_sol: f64,
+    /// Treasury balance at launch, in SOL
+    #[arg(long, default_value_t = 100.0)]
+    treasury_sol: f64,
+    /// Operating cost paid from the treasury
+    #[arg(long, default_value_t = 5_000.0)]
+    monthly_burn_usd: f64,
+    /// RNG seed, for reproducible output
+    #[arg(long, default_value_t = 42)]
+    seed: u64,
+    /// Directory for runs.csv and payouts.csv
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+fn default_scenarios() -> Vec<Scenario> {
+    ["quiet:50000", "base:500000", "viral:5000000:-0.01"]
+        .iter()
+        .map(|s| s.parse().expect("valid default scenario"))
+        .collect()
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let params = Params {
+        days: cli.days,
+        sol_price_usd: cli.sol_price_usd,
+        volume_sigma: cli.volume_sigma,
+        seed_jackpot_sol: cli.seed_jackpot_sol,
+        treasury_sol: cli.treasury_sol,
+        monthly_burn_usd: cli.monthly_burn_usd,
+    };
+    let rules = Rules::default();
+    let mut rng = StdRng::seed_from_u64(cli.seed);
+
+    let mut files = match &cli.out {
+        Some(dir) => {
+            std::fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+            let mut runs = csv_file(dir, "runs.csv")?;
+            writeln!(
+                runs,
+                "scenario,run,total_volume_usd,jackpot_tax_sol,creator_fund_sol,draws,payouts,\
+                 longest_rollover_streak,max_jackpot_sol,fast_mode_day,final_jackpot_sol,treasury_sol,runway_days"
+            )?;
+            let mut payouts = csv_file(dir, "payouts.csv")?;
+            writeln!(payouts, "scenario,run,day,jackpot_sol,main_prize_sol,minor_prize_sol,rollovers")?;
+            Some((runs, payouts))
+        }
+        None => None,
+    };
+
+    let mut summary = io::stdout().lock();
+    writeln!(summary, "scenario,metric,count,mean,p10,p50,p90,max")?;
+    for scenario in &cli.scenarios {
+        let runs: Vec<Run> = (0..cli.runs)
+            .map(|_| simulate(scenario, &params, &rules, &mut rng))
+            .collect();
+        if let Some((runs_csv, payouts_csv)) = files.as_mut() {
+            write_runs(runs_csv, payouts_csv, &scenario.name, &runs)?;
+        }
+
+        let payouts = || runs.iter().flat_map(|r| &r.payouts);
+        let metrics = [
+            ("payout_jackpot_sol", Distribution::of(payouts().map(|p| p.jackpot_sol).collect())),
+            ("main_prize_sol", Distribution::of(payouts().map(|p| p.main_prize_sol()).collect())),
+            ("rollovers_before_payout", Distribution::of(payouts().map(|p| p.rollovers as f64).collect())),
+            ("longest_rollover_streak", per_run(&runs, |r| r.longest_rollover_streak as f64)),
+            ("max_jackpot_sol", per_run(&runs, |r| r.max_jackpot_sol)),
+            ("payouts_per_run", per_run(&runs, |r| r.payouts.len() as f64)),
+            ("fast_mode_day", Distribution::of(runs.iter().filter_map(|r| r.fast_mode_day.map(f64::from)).collect())),
+            // Runs that never run dry count as the full horizon.
+            ("runway_days", per_run(&runs, |r| r.runway_days.unwrap_or(cli.days) as f64)),
+            ("treasury_sol", per_run(&runs, |r| r.treasury_sol)),
+        ];
+        for (metric, d) in metrics {
+            writeln!(
+                summary,
+                "{},{metric},{},{:.4},{:.4},{:.4},{:.4},{:.4}",
+                scenario.name, d.count, d.mean, d.p10, d.p50, d.p90, d.max
+            )?;
+        }
+    }
+    if let Some((mut runs_csv, mut payouts_csv)) = files {
+        runs_csv.flush()?;
+        payouts_csv.flush()?;
+    }
+    Ok(())
+}
+
+fn per_run(runs: &[Run], f: impl Fn(&Run) -> f64) -> Distribution {
+    Distribution::of(runs.iter().map(f).collect())
+}
+
+fn csv_file(dir: &std::path::Path, name: &str) -> Result<BufWriter<File>> {
+    let path = dir.join(name);
+    let file = File::create(&path).with_context(|| format!("create {}", path.display()))?;
+    Ok(BufWriter::new(file))
+}
+
+fn write_runs(runs_csv: &mut impl Write, payouts_csv: &mut impl Write, scenario: &str, runs: &[Run]) -> Result<()> {
+    let opt = |v: Option<u32>| v.map(|d| d.to_string()).unwrap_or_default();
+    for (i, r) in runs.iter().enumerate() {
+        writeln!(
+            runs_csv,
+            "{scenario},{i},{:.2},{:.4},{:.4},{},{},{},{:.4},{},{:.4},{:.4},{}",
+            r.total_volume_usd,
+            r.jackpot_tax_sol,
+            r.creator_fund_sol,
+            r.draws,
+            r.payouts.len(),
+            r.longest_rollover_streak,
+            r.max_jackpot_sol,
+            opt(r.fast_mode_day),
+            r.final_jackpot_sol,
+            r.treasury_sol,
+            opt(r.runway_days),
+        )?;
+        for p in &r.payouts {
+            writeln!(
+                payouts_csv,
+                "{scenario},{i},{},{:.4},{:.4},{:.4},{}",
+                p.day,
+                p.jackpot_sol,
+                p.main_prize_sol(),
+                p.minor_prize_sol(),
+                p.rollovers
+            )?;
+        }
+    }
+    Ok(())
+}
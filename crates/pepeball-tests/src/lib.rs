@@ -0,0 +1,816 @@
+//! In-process test bank for the PEPEBALL programs.
+//!
+//! `solana-program-test` 1.18.0 pins `solana-program =1.18.0`, which anchor-spl
+//! 0.30.1's SPL crates (>= 1.18.11) rule out, so the programs run natively here
+//! instead: each instruction calls the program's Anchor `entry` with real
+//! `AccountInfo`s, and `solana_program`'s syscall stubs provide the clock, rent,
+//! logs, return data and CPI. CPI targets are the native System, SPL Token,
+//! Token-2022 and Associated Token processors plus the workspace programs.
+//! CPI re-entry (A -> B -> A) is rejected as on chain. Ed25519 precompile
+//! instructions are verified before the transaction runs, and the
+//! instructions sysvar is served to every instruction that lists it.
+//!
+//! The runtime's account-modification rules are enforced per invocation, at
+//! every CPI boundary and when a program returns: only the owner may debit
+//! lamports, change data or reassign (zeroed accounts only), read-only
+//! accounts may not change at all, and lamports must balance.
+//!
+//! Not emulated: fees, compute units and rent collection.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE, SUCCESS};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::program_stubs::{self, SyscallStubs};
+use anchor_lang::solana_program::system_instruction::SystemInstruction;
+use anchor_lang::solana_program::sysvar::instructions::{
+    self as sysvar_instructions, construct_instructions_data, store_current_index, BorrowedAccountMeta,
+    BorrowedInstruction,
+};
+use anchor_lang::solana_program::{bpf_loader_upgradeable, ed25519_program, system_program, sysvar};
+use anchor_spl::associated_token::{get_associated_token_address, spl_associated_token_account};
+use anchor_spl::token::spl_token;
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::StateWithExtensions;
+use base64::Engine;
+use solana_sdk::ed25519_instruction;
+use solana_sdk::feature_set::FeatureSet;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Once;
+
+pub use pepeball_sdk;
+
+pub mod metaplex;
+pub mod whirlpool;
+
+/// Lamports given to every wallet created with [`Bank::wallet`].
+pub const WALLET_LAMPORTS: u64 = 100 * 1_000_000_000;
+
+/// Account as stored between instructions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StoredAccount {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+/// A failed transaction: the instruction that failed, its error and the logs so far.
+#[derive(Debug)]
+pub struct TxError {
+    pub index: usize,
+    pub error: ProgramError,
+    pub logs: Vec<String>,
+}
+
+impl TxError {
+    /// Custom error code (Anchor errors are `6000 + variant`).
+    pub fn custom_code(&self) -> Option<u32> {
+        match self.error {
+            ProgramError::Custom(code) => Some(code),
+            _ => None,
+        }
+    }
+}
+
+pub type TxResult = std::result::Result<Vec<String>, TxError>;
+
+/// Asserts that `result` failed with the Anchor/custom error `code`.
+#[track_caller]
+pub fn assert_error(result: TxResult, code: impl Into<u32>) {
+    let code = code.into();
+    match result {
+        Ok(logs) => panic!("expected error {code}, transaction succeeded:\n{}", logs.join("\n")),
+        Err(e) => assert_eq!(
+            e.custom_code(),
+            Some(code),
+            "wrong error {:?}:\n{}",
+            e.error,
+            e.logs.join("\n")
+        ),
+    }
+}
+
+/// Accounts, clock and program registry for one test.
+pub struct Bank {
+    accounts: HashMap<Pubkey, StoredAccount>,
+    clock: Clock,
+}
+
+impl Default for Bank {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bank {
+    pub fn new() -> Self {
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            program_stubs::set_syscall_stubs(Box::new(Stubs));
+        });
+
+        let mut bank = Self {
+            accounts: HashMap::new(),
+            clock: Clock {
+                slot: 1,
+                unix_timestamp: 1_700_000_000,
+                ..Clock::default()
+            },
+        };
+        // Loader ownership is never checked, so every program sits under the upgradeable loader.
+        for (id, _) in PROGRAMS {
+            bank.set_account(
+                *id,
+                StoredAccount {
+                    lamports: 1,
+                    data: Vec::new(),
+                    owner: bpf_loader_upgradeable::ID,
+                    executable: true,
+                },
+            );
+        }
+        let wsol = spl_token::state::Mint {
+            mint_authority: COption::None,
+            supply: 0,
+            decimals: native_mint::DECIMALS,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        bank.set_packed(native_mint::ID, spl_token::ID, wsol);
+        bank
+    }
+
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    /// Moves the clock to `slot` / `unix_timestamp`.
+    pub fn warp(&mut self, slot: u64, unix_timestamp: i64) {
+        self.clock.slot = slot;
+        self.clock.unix_timestamp = unix_timestamp;
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<&StoredAccount> {
+        self.accounts.get(key)
+    }
+
+    pub fn set_account(&mut self, key: Pubkey, account: StoredAccount) {
+        self.accounts.insert(key, account);
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.accounts.get(key).map_or(0, |a| a.lamports)
+    }
+
+    pub fn airdrop(&mut self, key: &Pubkey, lamports: u64) {
+        let account = self.accounts.entry(*key).or_insert_with(|| StoredAccount {
+            owner: system_program::ID,
+            ..StoredAccount::default()
+        });
+        account.lamports += lamports;
+    }
+
+    /// Fresh funded system account.
+    pub fn wallet(&mut self) -> Pubkey {
+        let key = Pubkey::new_unique();
+        self.airdrop(&key, WALLET_LAMPORTS);
+        key
+    }
+
+    /// Deserializes an Anchor account (discriminator checked).
+    pub fn anchor_account<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        let account = self.accounts.get(key).unwrap_or_else(|| panic!("account {key} not found"));
+        T::try_deserialize(&mut account.data.as_slice()).expect("anchor account")
+    }
+
+    pub fn create_mint(&mut self, authority: &Pubkey, decimals: u8) -> Pubkey {
+        let key = Pubkey::new_unique();
+        let mint = spl_token::state::Mint {
+            mint_authority: COption::Some(*authority),
+            supply: 0,
+            decimals,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        self.set_packed(key, spl_token::ID, mint);
+        key
+    }
+
+    /// Token account at `key` (keypair-style address). Wrapped SOL accounts
+    /// hold `amount` lamports on top of their rent, like the real ones.
+    pub fn create_token_account(&mut self, key: Pubkey, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let rent = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+        let native = *mint == native_mint::ID;
+        let account = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            is_native: if native { COption::Some(rent) } else { COption::None },
+            ..spl_token::state::Account::default()
+        };
+        self.set_packed(key, spl_token::ID, account);
+        if native {
+            self.accounts.get_mut(&key).unwrap().lamports += amount;
+        } else {
+            self.adjust_supply(mint, amount);
+        }
+        key
+    }
+
+    /// Associated token account of `owner` holding `amount`.
+    pub fn create_ata(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        self.create_token_account(get_associated_token_address(owner, mint), mint, owner, amount)
+    }
+
+    /// Balance of an SPL Token or Token-2022 account.
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        let account = self.accounts.get(key).unwrap_or_else(|| panic!("token account {key} not found"));
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .expect("token account")
+            .base
+            .amount
+    }
+
+    fn set_packed<T: Pack>(&mut self, key: Pubkey, owner: Pubkey, state: T) {
+        let mut data = vec![0; T::LEN];
+        state.pack_into_slice(&mut data);
+        self.set_account(
+            key,
+            StoredAccount {
+                lamports: Rent::default().minimum_balance(T::LEN),
+                data,
+                owner,
+                executable: false,
+            },
+        );
+    }
+
+    fn adjust_supply(&mut self, mint: &Pubkey, amount: u64) {
+        if let Some(account) = self.accounts.get_mut(mint) {
+            let mut state = spl_token::state::Mint::unpack(&account.data).expect("mint");
+            state.supply += amount;
+            state.pack_into_slice(&mut account.data);
+        }
+    }
+
+    /// Runs `instructions` atomically. `signers` are the transaction signatures.
+    pub fn process(&mut self, instructions: &[Instruction], signers: &[Pubkey]) -> TxResult {
+        with_runtime(|rt| {
+            rt.clock = self.clock.clone();
+            rt.logs.clear();
+            rt.return_data = None;
+        });
+        let snapshot = self.accounts.clone();
+        let datas: Vec<&[u8]> = instructions.iter().map(|ix| ix.data.as_slice()).collect();
+        for (index, ix) in instructions.iter().enumerate() {
+            if ix.program_id == ed25519_program::ID {
+                if let Err(e) = ed25519_instruction::verify(&ix.data, &datas, &FeatureSet::all_enabled()) {
+                    return Err(TxError {
+                        index,
+                        error: ProgramError::Custom(e as u32),
+                        logs: Vec::new(),
+                    });
+                }
+            }
+        }
+        let mut sysvar = instructions_sysvar(instructions);
+        for (index, ix) in instructions.iter().enumerate() {
+            if ix.program_id == ed25519_program::ID {
+                continue;
+            }
+            store_current_index(&mut sysvar, index as u16);
+            self.set_account(
+                sysvar_instructions::ID,
+                StoredAccount {
+                    lamports: 1,
+                    data: sysvar.clone(),
+                    owner: sysvar::ID,
+                    executable: false,
+                },
+            );
+            if let Err(error) = self.process_instruction(ix, signers) {
+                self.accounts = snapshot;
+                let logs = with_runtime(|rt| std::mem::take(&mut rt.logs));
+                return Err(TxError { index, error, logs });
+            }
+        }
+        self.accounts.remove(&sysvar_instructions::ID);
+        Ok(with_runtime(|rt| std::mem::take(&mut rt.logs)))
+    }
+
+    /// Shorthand for a single instruction.
+    pub fn send(&mut self, ix: Instruction, signers: &[Pubkey]) -> TxResult {
+        self.process(&[ix], signers)
+    }
+
+    fn process_instruction(&mut self, ix: &Instruction, signers: &[Pubkey]) -> ProgramResult {
+        let entry = processor(&ix.program_id).ok_or(ProgramError::IncorrectProgramId)?;
+        for meta in &ix.accounts {
+            if meta.is_signer && !signers.contains(&meta.pubkey) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+        }
+
+        // One slot per distinct key so duplicate metas share lamports/data.
+        let mut keys: Vec<Pubkey> = Vec::new();
+        for meta in &ix.accounts {
+            if !keys.contains(&meta.pubkey) {
+                keys.push(meta.pubkey);
+            }
+        }
+        let mut slots: Vec<Box<Slot>> = keys
+            .iter()
+            .map(|key| {
+                let stored = self.accounts.get(key).cloned().unwrap_or_else(|| StoredAccount {
+                    owner: system_program::ID,
+                    ..StoredAccount::default()
+                });
+                Box::new(Slot {
+                    original_data_len: stored.data.len() as u32,
+                    key: *key,
+                    owner: stored.owner,
+                    lamports: stored.lamports,
+                    executable: stored.executable,
+                    data: stored.data,
+                })
+            })
+            .collect();
+
+        let shared: Vec<SharedSlot> = slots
+            .iter_mut()
+            .map(|slot| {
+                let data = arena_data(&slot.data);
+                // SAFETY: the slots outlive every AccountInfo built from them
+                // (`infos` and `shared` are dropped before `slots` below).
+                let lamports: &'static mut u64 = unsafe { &mut *std::ptr::addr_of_mut!(slot.lamports) };
+                (Rc::new(RefCell::new(lamports)), Rc::new(RefCell::new(data)))
+            })
+            .collect();
+
+        let infos: Vec<AccountInfo<'static>> = ix
+            .accounts
+            .iter()
+            .map(|meta| {
+                let i = keys.iter().position(|k| *k == meta.pubkey).unwrap();
+                let slot: *mut Slot = std::ptr::addr_of_mut!(*slots[i]);
+                // SAFETY: as above; `owner` is only ever read back through `owner_of`.
+                let (key, owner) = unsafe { (&*std::ptr::addr_of!((*slot).key), &*std::ptr::addr_of!((*slot).owner)) };
+                AccountInfo {
+                    key,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                    lamports: shared[i].0.clone(),
+                    data: shared[i].1.clone(),
+                    owner,
+                    executable: slots[i].executable,
+                    rent_epoch: u64::MAX,
+                }
+            })
+            .collect();
+
+        let result = run(&ix.program_id, entry, &infos, &ix.data);
+        drop(infos);
+
+        if result.is_ok() {
+            for (i, slot) in slots.iter().enumerate() {
+                let lamports = **shared[i].0.borrow();
+                let data = shared[i].1.borrow().to_vec();
+                let owner = owner_of(&slot.owner);
+                if lamports == 0 && !slot.executable {
+                    self.accounts.remove(&slot.key);
+                } else {
+                    self.accounts.insert(
+                        slot.key,
+                        StoredAccount {
+                            lamports,
+                            data,
+                            owner,
+                            executable: slot.executable,
+                        },
+                    );
+                }
+            }
+        }
+        drop(shared);
+        drop(slots);
+        with_runtime(|rt| rt.arena.clear());
+        result
+    }
+}
+
+/// Instructions sysvar data for `instructions`; the current index is stored
+/// per instruction.
+fn instructions_sysvar(instructions: &[Instruction]) -> Vec<u8> {
+    let borrowed: Vec<BorrowedInstruction> = instructions
+        .iter()
+        .map(|ix| BorrowedInstruction {
+            program_id: &ix.program_id,
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|meta| BorrowedAccountMeta {
+                    pubkey: &meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: &ix.data,
+        })
+        .collect();
+    construct_instructions_data(&borrowed)
+}
+
+/// Backing memory for one `AccountInfo`. `original_data_len` sits right before
+/// `key`, where `AccountInfo::original_data_len` reads it.
+#[repr(C)]
+struct Slot {
+    original_data_len: u32,
+    key: Pubkey,
+    owner: Pubkey,
+    lamports: u64,
+    executable: bool,
+    data: Vec<u8>,
+}
+
+#[derive(Default)]
+struct Runtime {
+    clock: Clock,
+    stack: Vec<Pubkey>,
+    logs: Vec<String>,
+    return_data: Option<(Pubkey, Vec<u8>)>,
+    /// Account data buffers for the current top-level instruction.
+    arena: Vec<Box<[u64]>>,
+}
+
+thread_local! {
+    static RUNTIME: RefCell<Runtime> = RefCell::new(Runtime::default());
+    /// Account baselines of the invocations on the stack, innermost last.
+    static FRAMES: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+fn with_runtime<R>(f: impl FnOnce(&mut Runtime) -> R) -> R {
+    RUNTIME.with(|rt| f(&mut rt.borrow_mut()))
+}
+
+/// Copies `bytes` into an arena buffer laid out like the runtime's input:
+/// an 8-byte length header in front and `MAX_PERMITTED_DATA_INCREASE` spare
+/// bytes behind, so `AccountInfo::realloc` stays in bounds.
+fn arena_data(bytes: &[u8]) -> &'static mut [u8] {
+    let words = (8 + bytes.len() + MAX_PERMITTED_DATA_INCREASE).div_ceil(8);
+    let mut buffer = vec![0u64; words].into_boxed_slice();
+    buffer[0] = bytes.len() as u64;
+    let base = buffer.as_mut_ptr() as *mut u8;
+    with_runtime(|rt| rt.arena.push(buffer));
+    // SAFETY: the boxed buffer lives in the arena until the top-level instruction ends.
+    let data = unsafe { std::slice::from_raw_parts_mut(base.add(8), bytes.len()) };
+    data.copy_from_slice(bytes);
+    data
+}
+
+type Entrypoint = fn(&Pubkey, &[AccountInfo], &[u8]) -> ProgramResult;
+
+/// Lamports and data cells shared by every `AccountInfo` for one account.
+type SharedSlot = (Rc<RefCell<&'static mut u64>>, Rc<RefCell<&'static mut [u8]>>);
+
+macro_rules! anchor_entry {
+    ($name:ident, $program:ident) => {
+        fn $name<'a, 'b>(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>], data: &[u8]) -> ProgramResult {
+            // Anchor ties the slice lifetime to the account lifetime.
+            let accounts = unsafe {
+                std::mem::transmute::<&'a [AccountInfo<'b>], &'b [AccountInfo<'b>]>(accounts)
+            };
+            $program::entry(program_id, accounts, data)
+        }
+    };
+}
+
+anchor_entry!(lottery_entry, lottery);
+anchor_entry!(pepball_token_entry, pepball_token);
+anchor_entry!(pepball_hook_entry, pepball_hook);
+anchor_entry!(tax_harvest_entry, tax_harvest);
+anchor_entry!(lp_manager_entry, lp_manager);
+anchor_entry!(game_registry_entry, game_registry);
+
+const PROGRAMS: &[(Pubkey, Entrypoint)] = &[
+    (system_program::ID, system_processor),
+    (spl_token::ID, spl_token::processor::Processor::process),
+    (spl_token_2022::ID, spl_token_2022::processor::Processor::process),
+    (spl_associated_token_account::ID, spl_associated_token_account::processor::process_instruction),
+    (lottery::ID, lottery_entry),
+    (pepball_token::ID, pepball_token_entry),
+    (pepball_hook::ID, pepball_hook_entry),
+    (tax_harvest::ID, tax_harvest_entry),
+    (lp_manager::ID, lp_manager_entry),
+    (game_registry::ID, game_registry_entry),
+    (tax_harvest::swap::WHIRLPOOL_PROGRAM_ID, whirlpool::process),
+    (pepball_token::metaplex::TOKEN_METADATA_PROGRAM_ID, metaplex::process),
+];
+
+fn processor(program_id: &Pubkey) -> Option<Entrypoint> {
+    PROGRAMS.iter().find(|(id, _)| id == program_id).map(|(_, entry)| *entry)
+}
+
+fn run(program_id: &Pubkey, entry: Entrypoint, infos: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let depth = with_runtime(|rt| {
+        rt.stack.push(*program_id);
+        rt.stack.len()
+    });
+    with_runtime(|rt| rt.logs.push(format!("Program {program_id} invoke [{depth}]")));
+    FRAMES.with(|f| f.borrow_mut().push(Frame::new(*program_id, infos)));
+    let mut result = entry(program_id, infos, data);
+    let frame = FRAMES.with(|f| f.borrow_mut().pop().expect("invocation frame"));
+    if result.is_ok() {
+        result = frame.verify(infos);
+    }
+    with_runtime(|rt| {
+        rt.stack.pop();
+        rt.logs.push(match &result {
+            Ok(()) => format!("Program {program_id} success"),
+            Err(e) => format!("Program {program_id} failed: {e}"),
+        });
+    });
+    result
+}
+
+/// CPI: caller privileges plus PDA signatures derived from the caller's program id.
+fn invoke_signed(ix: &Instruction, account_infos: &[AccountInfo], signers_seeds: &[&[&[u8]]]) -> ProgramResult {
+    let caller = with_runtime(|rt| *rt.stack.last().expect("CPI outside an instruction"));
+    let pda_signers = signers_seeds
+        .iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|_| ProgramError::InvalidSeeds)?;
+
+    let mut infos = Vec::with_capacity(ix.accounts.len());
+    for meta in &ix.accounts {
+        let info = account_infos
+            .iter()
+            .find(|info| *info.key == meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if meta.is_signer && !info.is_signer && !pda_signers.contains(&meta.pubkey) {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if meta.is_writable && !info.is_writable {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let mut info = info.clone();
+        info.is_signer = meta.is_signer;
+        info.is_writable = meta.is_writable;
+        infos.push(info);
+    }
+
+    let reentry = with_runtime(|rt| {
+        rt.stack.contains(&ix.program_id) && rt.stack.last() != Some(&ix.program_id)
+    });
+    if reentry {
+        with_runtime(|rt| rt.logs.push("Cross-program invocation reentrancy not allowed for this instruction".into()));
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let entry = processor(&ix.program_id).ok_or(ProgramError::IncorrectProgramId)?;
+    // The caller's changes so far are checked before the callee sees them, and
+    // whatever the callee leaves becomes the caller's new baseline.
+    with_caller_frame(|frame| frame.sync(&infos, true))?;
+    run(&ix.program_id, entry, &infos, &ix.data)?;
+    with_caller_frame(|frame| frame.sync(&infos, false))
+}
+
+/// One invocation's view of its accounts as it received them (or as the last
+/// CPI left them), to attribute every later change to this program.
+struct Frame {
+    program_id: Pubkey,
+    accounts: Vec<PreAccount>,
+}
+
+struct PreAccount {
+    key: Pubkey,
+    is_writable: bool,
+    lamports: u64,
+    data: Vec<u8>,
+    owner: Pubkey,
+}
+
+impl Frame {
+    fn new(program_id: Pubkey, infos: &[AccountInfo]) -> Self {
+        let mut accounts: Vec<PreAccount> = Vec::with_capacity(infos.len());
+        for info in infos {
+            match accounts.iter_mut().find(|pre| pre.key == *info.key) {
+                Some(pre) => pre.is_writable |= info.is_writable,
+                None => accounts.push(PreAccount {
+                    key: *info.key,
+                    is_writable: info.is_writable,
+                    lamports: info.lamports(),
+                    data: info.data.borrow().to_vec(),
+                    owner: owner_of(info.owner),
+                }),
+            }
+        }
+        Self { program_id, accounts }
+    }
+
+    /// Checks every change since the frame began, plus the lamport balance.
+    fn verify(&self, infos: &[AccountInfo]) -> ProgramResult {
+        let (mut before, mut after) = (0u128, 0u128);
+        for pre in &self.accounts {
+            let info = infos.iter().find(|info| *info.key == pre.key).expect("frame account");
+            pre.verify(&self.program_id, info)?;
+            before += pre.lamports as u128;
+            after += info.lamports() as u128;
+        }
+        if before != after {
+            return Err(violation(&self.program_id, "unbalanced lamports", ProgramError::InvalidArgument));
+        }
+        Ok(())
+    }
+
+    /// Around a CPI: `check` the caller's changes to the accounts it passes
+    /// (before the call), then take their current state as the new baseline.
+    fn sync(&mut self, infos: &[AccountInfo], check: bool) -> ProgramResult {
+        for info in infos {
+            if let Some(pre) = self.accounts.iter_mut().find(|pre| pre.key == *info.key) {
+                if check {
+                    pre.verify(&self.program_id, info)?;
+                }
+                pre.lamports = info.lamports();
+                pre.data = info.data.borrow().to_vec();
+                pre.owner = owner_of(info.owner);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PreAccount {
+    /// The runtime's rules for what `program_id` may have done to this account.
+    fn verify(&self, program_id: &Pubkey, info: &AccountInfo) -> ProgramResult {
+        let lamports = info.lamports();
+        let owner = owner_of(info.owner);
+        let data = info.data.borrow();
+        let owned = *program_id == self.owner;
+        let data_changed = **data != self.data[..];
+        if !self.is_writable && (lamports != self.lamports || data_changed || owner != self.owner) {
+            return Err(violation(program_id, &format!("modified read-only account {}", self.key), ProgramError::InvalidAccountData));
+        }
+        if owner != self.owner && !(owned && data.iter().all(|b| *b == 0)) {
+            return Err(violation(program_id, &format!("reassigned account {}", self.key), ProgramError::IllegalOwner));
+        }
+        if lamports < self.lamports && !owned {
+            return Err(violation(
+                program_id,
+                &format!("debited account {} it does not own", self.key),
+                ProgramError::InvalidAccountOwner,
+            ));
+        }
+        if data_changed && !owned {
+            return Err(violation(
+                program_id,
+                &format!("modified data of account {} it does not own", self.key),
+                ProgramError::InvalidAccountOwner,
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn violation(program_id: &Pubkey, what: &str, error: ProgramError) -> ProgramError {
+    with_runtime(|rt| rt.logs.push(format!("Program {program_id}: instruction {what}")));
+    error
+}
+
+fn with_caller_frame(f: impl FnOnce(&mut Frame) -> ProgramResult) -> ProgramResult {
+    FRAMES.with(|frames| f(frames.borrow_mut().last_mut().expect("CPI outside an instruction")))
+}
+
+/// Reads an owner that `AccountInfo::assign` may have rewritten in place; it
+/// writes with `write_volatile`, so the read must be volatile too.
+fn owner_of(owner: *const Pubkey) -> Pubkey {
+    // SAFETY: `owner` points into a live `Slot` (see `process_instruction`).
+    unsafe { std::ptr::read_volatile(owner) }
+}
+
+/// The System program instructions Anchor and the ATA program use.
+fn system_processor(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let ix: SystemInstruction = bincode::deserialize(data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    let signed = |info: &AccountInfo| {
+        if info.is_signer {
+            Ok(())
+        } else {
+            Err(ProgramError::MissingRequiredSignature)
+        }
+    };
+    let account = |i: usize| accounts.get(i).ok_or(ProgramError::NotEnoughAccountKeys);
+
+    match ix {
+        SystemInstruction::CreateAccount { lamports, space, owner } => {
+            let (from, to) = (account(0)?, account(1)?);
+            signed(from)?;
+            signed(to)?;
+            if to.lamports() > 0 || !to.data_is_empty() || *to.owner != system_program::ID {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            transfer(from, to, lamports)?;
+            allocate(to, space)?;
+            to.assign(&owner);
+        }
+        SystemInstruction::Transfer { lamports } => {
+            let (from, to) = (account(0)?, account(1)?);
+            signed(from)?;
+            if *from.owner != system_program::ID || !from.data_is_empty() {
+                return Err(ProgramError::InvalidArgument);
+            }
+            transfer(from, to, lamports)?;
+        }
+        SystemInstruction::Allocate { space } => {
+            let to = account(0)?;
+            signed(to)?;
+            allocate(to, space)?;
+        }
+        SystemInstruction::Assign { owner } => {
+            let to = account(0)?;
+            signed(to)?;
+            to.assign(&owner);
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+    Ok(())
+}
+
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    let remaining = from.lamports().checked_sub(lamports).ok_or(ProgramError::InsufficientFunds)?;
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+fn allocate(info: &AccountInfo, space: u64) -> ProgramResult {
+    if !info.data_is_empty() || *info.owner != system_program::ID {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    *info.try_borrow_mut_data()? = arena_data(&vec![0; space as usize]);
+    Ok(())
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        with_runtime(|rt| rt.logs.push(format!("Program log: {message}")));
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let encoded: Vec<String> = fields
+            .iter()
+            .map(|f| base64::engine::general_purpose::STANDARD.encode(f))
+            .collect();
+        with_runtime(|rt| rt.logs.push(format!("Program data: {}", encoded.join(" "))));
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        invoke_signed(instruction, account_infos, signers_seeds)
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = with_runtime(|rt| rt.clock.clone());
+        // SAFETY: `Clock::get` passes a pointer to a `Clock`.
+        unsafe { std::ptr::write(var_addr as *mut Clock, clock) };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        // SAFETY: `Rent::get` passes a pointer to a `Rent`.
+        unsafe { std::ptr::write(var_addr as *mut Rent, Rent::default()) };
+        SUCCESS
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        with_runtime(|rt| rt.return_data.clone())
+    }
+
+    /// Also logs `Program return: <program> <base64>` like the runtime does.
+    fn sol_set_return_data(&self, data: &[u8]) {
+        with_runtime(|rt| {
+            let program_id = *rt.stack.last().expect("return data outside an instruction");
+            rt.logs.push(format!(
+                "Program return: {} {}",
+                program_id,
+                base64::engine::general_purpose::STANDARD.encode(data)
+            ));
+            rt.return_data = Some((program_id, data.to_vec()));
+        });
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        with_runtime(|rt| rt.stack.len() as u64)
+    }
+}
@@ -0,0 +1,166 @@
+//! Stand-in for Metaplex Token Metadata: `CreateMetadataAccountV3` and
+//! `UpdateMetadataAccountV2` with the checks the real program makes on a
+//! fungible mint (metadata PDA, mint authority, update authority), writing
+//! the real `Metadata` layout. Enough for pepball-token's legacy metadata.
+
+use crate::Bank;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::system_instruction;
+use anchor_spl::token::spl_token;
+use pepball_token::metaplex::{METADATA_SEED, TOKEN_METADATA_PROGRAM_ID};
+
+const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+const UPDATE_METADATA_ACCOUNT_V2: u8 = 15;
+/// `MAX_METADATA_LEN`
+const METADATA_LEN: usize = 679;
+const KEY_METADATA_V1: u8 = 4;
+const TOKEN_STANDARD_FUNGIBLE: u8 = 2;
+// Fields are padded to these lengths, as Metaplex does.
+const NAME_LEN: usize = 32;
+const SYMBOL_LEN: usize = 10;
+const URI_LEN: usize = 200;
+
+/// The parts of a Metaplex `Metadata` account the tests look at.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MetaplexMetadata {
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub is_mutable: bool,
+}
+
+impl Bank {
+    /// `mint`'s Metaplex metadata, if it has any.
+    pub fn metaplex_metadata(&self, mint: &Pubkey) -> Option<MetaplexMetadata> {
+        let data = &self.account(&metadata_address(mint).0)?.data;
+        let key = |offset: usize| Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap());
+        let mut offset = 65;
+        let mut string = || {
+            let len = u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as usize;
+            let value = String::from_utf8(data[offset + 4..offset + 4 + len].to_vec()).unwrap();
+            offset += 4 + len;
+            value.trim_end_matches('\0').to_string()
+        };
+        let (name, symbol, uri) = (string(), string(), string());
+        Some(MetaplexMetadata {
+            update_authority: key(1),
+            mint: key(33),
+            name,
+            symbol,
+            uri,
+            is_mutable: data[offset + 4] == 1,
+        })
+    }
+}
+
+fn metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[METADATA_SEED, TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+}
+
+/// Reads a `DataV2` with no creators, collection or uses, padding the
+/// strings; returns the fields and the rest of the input.
+fn read_data_v2(mut data: &[u8]) -> std::result::Result<(Vec<u8>, &[u8]), ProgramError> {
+    let mut out = Vec::new();
+    for max in [NAME_LEN, SYMBOL_LEN, URI_LEN] {
+        let len = u32::from_le_bytes(data.get(..4).ok_or(ProgramError::InvalidInstructionData)?.try_into().unwrap());
+        let value = data.get(4..4 + len as usize).ok_or(ProgramError::InvalidInstructionData)?;
+        if value.len() > max {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        out.extend_from_slice(&(max as u32).to_le_bytes());
+        out.extend_from_slice(value);
+        out.resize(out.len() + max - value.len(), 0);
+        data = &data[4 + len as usize..];
+    }
+    // seller_fee_basis_points, creators, collection, uses
+    if data.len() < 5 || data[2..5] != [0, 0, 0] {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    out.extend_from_slice(&data[..2]);
+    out.push(0); // creators: None
+    Ok((out, &data[5..]))
+}
+
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    match data.split_first() {
+        Some((&CREATE_METADATA_ACCOUNT_V3, args)) => create(program_id, accounts, args),
+        Some((&UPDATE_METADATA_ACCOUNT_V2, args)) => update(program_id, accounts, args),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn create(program_id: &Pubkey, accounts: &[AccountInfo], args: &[u8]) -> ProgramResult {
+    let [metadata, mint, mint_authority, payer, update_authority, system_program, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (fields, rest) = read_data_v2(args)?;
+    let [is_mutable, 0] = rest else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let (address, bump) = metadata_address(mint.key);
+    if *metadata.key != address || metadata.lamports() > 0 {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let mint_state = spl_token::state::Mint::unpack(&mint.try_borrow_data()?)?;
+    if !mint_authority.is_signer || mint_state.mint_authority != Some(*mint_authority.key).into() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            metadata.key,
+            Rent::get()?.minimum_balance(METADATA_LEN),
+            METADATA_LEN as u64,
+            program_id,
+        ),
+        &[payer.clone(), metadata.clone(), system_program.clone()],
+        &[&[METADATA_SEED, program_id.as_ref(), mint.key.as_ref(), &[bump]]],
+    )?;
+    let mut account = vec![KEY_METADATA_V1];
+    account.extend_from_slice(update_authority.key.as_ref());
+    account.extend_from_slice(mint.key.as_ref());
+    account.extend_from_slice(&fields);
+    account.push(0); // primary_sale_happened
+    account.push(*is_mutable);
+    account.push(0); // edition_nonce: None
+    account.extend_from_slice(&[1, TOKEN_STANDARD_FUNGIBLE]);
+    metadata.try_borrow_mut_data()?[..account.len()].copy_from_slice(&account);
+    Ok(())
+}
+
+fn update(program_id: &Pubkey, accounts: &[AccountInfo], args: &[u8]) -> ProgramResult {
+    let [metadata, update_authority, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if metadata.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    // Only a new `data` is supported; authority, primary sale and mutability stay.
+    let Some((1, args)) = args.split_first() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let (fields, rest) = read_data_v2(args)?;
+    if rest != [0, 0, 0] {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let mut data = metadata.try_borrow_mut_data()?;
+    let is_mutable = data[65 + fields.len() + 1] == 1;
+    if !update_authority.is_signer || data[1..33] != update_authority.key.to_bytes() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !is_mutable {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    data[65..65 + fields.len()].copy_from_slice(&fields);
+    Ok(())
+}
@@ -0,0 +1,137 @@
+//! Stand-in for the Orca Whirlpool program: `swap` fills the whole amount at
+//! the pool's current `sqrt_price` (no fees, ticks or liquidity limits) and
+//! pays out of the pool vault with the whirlpool PDA's signature, the way the
+//! real program does. Enough to drive tax-harvest's adapter end to end.
+
+use crate::Bank;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_spl::token::spl_token;
+use pepeball_sdk::harvest::{SwapRoute, WhirlpoolPool};
+use tax_harvest::swap::{OrcaWhirlpool, WHIRLPOOL_PROGRAM_ID};
+
+/// Orca's `AmountOutBelowMinimum`.
+pub const AMOUNT_OUT_BELOW_MINIMUM: u32 = 6036;
+
+const WHIRLPOOL_LEN: usize = 653;
+const BUMP: usize = 40;
+const TICK_SPACING_SEED: usize = 43;
+
+/// A pool made by [`Bank::create_whirlpool`].
+pub struct TestWhirlpool {
+    pub address: Pubkey,
+    pub pool: WhirlpoolPool,
+}
+
+impl TestWhirlpool {
+    /// Accounts for selling `token_mint` into this pool.
+    pub fn route(&self, token_mint: &Pubkey) -> SwapRoute {
+        self.pool.route(&self.address, token_mint).expect("pool does not trade this mint for SOL")
+    }
+}
+
+impl Bank {
+    /// Whirlpool for `mint_x` / `mint_y` (ordered A < B as Orca requires) at
+    /// `sqrt_price` (Q64.64 of price B per A), each vault funded with `reserve`.
+    pub fn create_whirlpool(&mut self, mint_x: &Pubkey, mint_y: &Pubkey, sqrt_price: u128, reserve: u64) -> TestWhirlpool {
+        let (mint_a, mint_b) = if mint_x < mint_y { (mint_x, mint_y) } else { (mint_y, mint_x) };
+        let config = Pubkey::new_unique();
+        let tick_spacing: u16 = 64;
+        let (address, bump) = Pubkey::find_program_address(
+            &[b"whirlpool", config.as_ref(), mint_a.as_ref(), mint_b.as_ref(), &tick_spacing.to_le_bytes()],
+            &WHIRLPOOL_PROGRAM_ID,
+        );
+        let vault_a = self.create_token_account(Pubkey::new_unique(), mint_a, &address, reserve);
+        let vault_b = self.create_token_account(Pubkey::new_unique(), mint_b, &address, reserve);
+        let price = (sqrt_price as f64 / 2f64.powi(64)).powi(2);
+        let tick_current_index = (price.ln() / 1.0001f64.ln()).floor() as i32;
+
+        let mut data = vec![0; WHIRLPOOL_LEN];
+        let mut put = |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        put(0, &OrcaWhirlpool::ACCOUNT_DISCRIMINATOR);
+        put(8, config.as_ref());
+        put(BUMP, &[bump]);
+        put(OrcaWhirlpool::TICK_SPACING, &tick_spacing.to_le_bytes());
+        put(TICK_SPACING_SEED, &tick_spacing.to_le_bytes());
+        put(OrcaWhirlpool::SQRT_PRICE, &sqrt_price.to_le_bytes());
+        put(OrcaWhirlpool::TICK_CURRENT_INDEX, &tick_current_index.to_le_bytes());
+        put(OrcaWhirlpool::TOKEN_MINT_A, mint_a.as_ref());
+        put(OrcaWhirlpool::TOKEN_VAULT_A, vault_a.as_ref());
+        put(OrcaWhirlpool::TOKEN_MINT_B, mint_b.as_ref());
+        put(OrcaWhirlpool::TOKEN_VAULT_B, vault_b.as_ref());
+        self.set_account(
+            address,
+            crate::StoredAccount {
+                lamports: Rent::default().minimum_balance(WHIRLPOOL_LEN),
+                data,
+                owner: WHIRLPOOL_PROGRAM_ID,
+                executable: false,
+            },
+        );
+        let pool = WhirlpoolPool::decode(&self.account(&address).unwrap().data).unwrap();
+        TestWhirlpool { address, pool }
+    }
+}
+
+/// `swap` with `amount_specified_is_input`; every other Whirlpool instruction is rejected.
+pub(crate) fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    if data.len() != 42 || data[..8] != OrcaWhirlpool::SWAP_DISCRIMINATOR || data[40] != 1 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let amount = u64::from_le_bytes(data[8..16].try_into().unwrap());
+    let threshold = u64::from_le_bytes(data[16..24].try_into().unwrap());
+    let a_to_b = data[41] == 1;
+    let [token_program, authority, whirlpool, owner_a, vault_a, owner_b, vault_b, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if whirlpool.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let pool = WhirlpoolPool::decode(&whirlpool.try_borrow_data()?).map_err(|_| ProgramError::InvalidAccountData)?;
+    if (*vault_a.key, *vault_b.key) != (pool.token_vault_a, pool.token_vault_b) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let price_x64 = pool.sqrt_price.checked_mul(pool.sqrt_price).ok_or(ProgramError::ArithmeticOverflow)? >> 64;
+    let out = if a_to_b {
+        (amount as u128 * price_x64) >> 64
+    } else {
+        ((amount as u128) << 64) / price_x64
+    };
+    let out = u64::try_from(out).map_err(|_| ProgramError::ArithmeticOverflow)?;
+    if out < threshold {
+        return Err(ProgramError::Custom(AMOUNT_OUT_BELOW_MINIMUM));
+    }
+
+    let (user_in, vault_in, vault_out, user_out) = if a_to_b {
+        (owner_a, vault_a, vault_b, owner_b)
+    } else {
+        (owner_b, vault_b, vault_a, owner_a)
+    };
+    invoke(
+        &spl_token::instruction::transfer(token_program.key, user_in.key, vault_in.key, authority.key, &[], amount)?,
+        &[user_in.clone(), vault_in.clone(), authority.clone()],
+    )?;
+
+    let (config, tick_spacing_seed, bump) = {
+        let data = whirlpool.try_borrow_data()?;
+        (
+            Pubkey::new_from_array(data[8..BUMP].try_into().unwrap()),
+            [data[TICK_SPACING_SEED], data[TICK_SPACING_SEED + 1]],
+            data[BUMP],
+        )
+    };
+    invoke_signed(
+        &spl_token::instruction::transfer(token_program.key, vault_out.key, user_out.key, whirlpool.key, &[], out)?,
+        &[vault_out.clone(), user_out.clone(), whirlpool.clone()],
+        &[&[
+            b"whirlpool",
+            config.as_ref(),
+            pool.token_mint_a.as_ref(),
+            pool.token_mint_b.as_ref(),
+            &tick_spacing_seed,
+            &[bump],
+        ]],
+    )
+}
@@ -0,0 +1,91 @@
+//! Lottery fixtures shared by the integration tests.
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::Pubkey;
+use lottery::Lottery;
+use pepeball_draw::Parity;
+use pepeball_sdk::programs::lottery::instruction as lottery_ix;
+use pepeball_sdk::{lottery as ix, pda};
+use pepeball_tests::Bank;
+
+pub const JACKPOT: u64 = 10 * 1_000_000_000;
+/// Production tiers: $20 / $100 / $500 in cents.
+pub const TIERS: (u64, u64, u64) = (2_000, 10_000, 50_000);
+pub const TICKET_PRICE: u64 = 100_000_000;
+
+pub fn lottery_key() -> Pubkey {
+    pda::lottery().0
+}
+
+pub fn lottery(bank: &Bank) -> Lottery {
+    bank.anchor_account(&lottery_key())
+}
+
+/// Initialized lottery with production tiers; returns the admin.
+pub fn init_lottery(bank: &mut Bank) -> Pubkey {
+    let admin = bank.wallet();
+    bank.send(ix::initialize_lottery(&admin, JACKPOT, TIERS.0, TIERS.1, TIERS.2), &[admin])
+        .expect("initialize_lottery");
+    admin
+}
+
+/// `count` free entries with 1..=3 tickets each, wallets in ascending order.
+pub fn enter_participants(bank: &mut Bank, count: usize) -> Vec<Pubkey> {
+    (0..count)
+        .map(|i| {
+            let wallet = bank.wallet();
+            bank.send(ix::enter_lottery(&wallet, 1 + (i % 3) as u32), &[wallet])
+                .expect("enter_lottery");
+            wallet
+        })
+        .collect()
+}
+
+/// Warps to the first moment the next snapshot is allowed whose seed draws a
+/// Pepe ball count of the wanted parity. Returns the seed.
+pub fn warp_to_draw(bank: &mut Bank, want: Parity) -> u64 {
+    let l = lottery(bank);
+    let interval = if l.fees_collected >= l.fast_mode_threshold {
+        l.fast_snapshot_interval
+    } else {
+        l.base_snapshot_interval
+    };
+    let next_slot = bank.clock().slot + 1;
+    let mut unix_timestamp = l.last_snapshot + interval as i64;
+    loop {
+        // An even slot or timestamp pins the product's parity, so try two slots.
+        for slot in [next_slot, next_slot + 1] {
+            let seed = pepeball_draw::derive_seed(slot, unix_timestamp, l.total_participants, l.total_snapshots);
+            if seed != 0 && pepeball_draw::parity(pepeball_draw::ball_count(seed)) == want {
+                bank.warp(slot, unix_timestamp);
+                return seed;
+            }
+        }
+        unix_timestamp += 1;
+    }
+}
+
+/// Participant PDAs and ticket counts sorted by wallet, the order `set_winners` verifies in.
+pub fn participant_set(bank: &Bank, wallets: &[Pubkey]) -> (Vec<Pubkey>, Vec<Pubkey>, Vec<u32>) {
+    let mut wallets = wallets.to_vec();
+    wallets.sort();
+    let accounts: Vec<Pubkey> = wallets.iter().map(|w| pda::participant(&lottery_key(), w).0).collect();
+    let counts = accounts
+        .iter()
+        .map(|a| bank.anchor_account::<lottery::ParticipantAccount>(a).ticket_count)
+        .collect();
+    (wallets, accounts, counts)
+}
+
+pub fn paid_entry_args(payment_mint: Option<Pubkey>, dev_wallet: Pubkey, max_tickets: u32) -> lottery_ix::InitializePaidEntry {
+    lottery_ix::InitializePaidEntry {
+        payment_mint,
+        ticket_price: TICKET_PRICE,
+        max_tickets_per_wallet: max_tickets,
+        prize_bps: 8_000,
+        reserve_bps: 1_500,
+        dev_bps: 500,
+        dev_wallet,
+    }
+}
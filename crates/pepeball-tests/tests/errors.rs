@@ -0,0 +1,1132 @@
+//! One negative case per custom error of the lottery, pepball-token, game-registry
+//! and tax-harvest programs.
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountSerialize;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token::native_mint;
+use common::*;
+use game_registry::RegistryError;
+use lottery::ErrorCode as LotteryError;
+use lp_manager::ErrorCode as LpManagerError;
+use pepball_token::{Capability, ErrorCode as TokenError, TaxDirection, VestingCategory};
+use pepeball_draw::Parity;
+use pepeball_sdk::lottery::{self as ix, PaidEntryBucket};
+use pepeball_sdk::programs::game_registry::instruction as registry_ix;
+use pepeball_sdk::programs::lottery::instruction as lottery_ix;
+use pepeball_sdk::programs::pepball_token::instruction as token_ix;
+use pepeball_sdk::harvest::SwapRoute;
+use pepeball_sdk::{harvest, lp_manager as lp_manager_sdk, pda, registry, token};
+use pepeball_tests::{assert_error, Bank, StoredAccount};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::signature::{Keypair, Signer};
+use tax_harvest::HarvestError;
+
+fn update_args(dev_wallet: Pubkey, is_enabled: bool) -> lottery_ix::UpdatePaidEntry {
+    lottery_ix::UpdatePaidEntry {
+        ticket_price: TICKET_PRICE,
+        max_tickets_per_wallet: 5,
+        prize_bps: 8_000,
+        reserve_bps: 1_500,
+        dev_bps: 500,
+        dev_wallet,
+        is_enabled,
+    }
+}
+
+// ---- lottery ----
+
+#[test]
+fn lottery_inactive() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    bank.send(ix::emergency_pause_lottery(&admin), &[admin]).unwrap();
+    let wallet = bank.wallet();
+    assert_error(bank.send(ix::enter_lottery(&wallet, 1), &[wallet]), LotteryError::LotteryInactive);
+}
+
+#[test]
+fn draw_too_early() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    enter_participants(&mut bank, 9);
+    assert_error(bank.send(ix::take_snapshot(&admin), &[admin]), LotteryError::DrawTooEarly);
+}
+
+#[test]
+fn no_winners() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    let wallet = bank.wallet();
+    assert_error(bank.send(ix::set_winners(&admin, wallet, vec![]), &[admin]), LotteryError::NoWinners);
+    assert_error(bank.send(ix::payout_winners(&admin), &[admin]), LotteryError::NoWinners);
+}
+
+#[test]
+fn not_enough_participants() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    enter_participants(&mut bank, pepeball_draw::MIN_PARTICIPANTS as usize - 1);
+    warp_to_draw(&mut bank, Parity::Odd);
+    assert_error(bank.send(ix::take_snapshot(&admin), &[admin]), LotteryError::NotEnoughParticipants);
+}
+
+#[test]
+fn lottery_unauthorized() {
+    let mut bank = Bank::new();
+    init_lottery(&mut bank);
+    let intruder = bank.wallet();
+    assert_error(bank.send(ix::take_snapshot(&intruder), &[intruder]), LotteryError::Unauthorized);
+    assert_error(
+        bank.send(ix::update_jackpot_amount(&intruder, 1), &[intruder]),
+        LotteryError::Unauthorized,
+    );
+    assert_error(bank.send(ix::close_lottery(&intruder), &[intruder]), LotteryError::Unauthorized);
+}
+
+#[test]
+fn insufficient_value() {
+    let mut bank = Bank::new();
+    init_lottery(&mut bank);
+    let wallet = bank.wallet();
+    assert_error(
+        bank.send(ix::enter_lottery_with_usd_value(&wallet, TIERS.0 - 1), &[wallet]),
+        LotteryError::InsufficientValue,
+    );
+}
+
+#[test]
+fn invalid_config() {
+    let mut bank = Bank::new();
+    let admin = bank.wallet();
+    assert_error(
+        bank.send(ix::initialize_lottery(&admin, 0, TIERS.0, TIERS.1, TIERS.2), &[admin]),
+        LotteryError::InvalidConfig,
+    );
+    assert_error(
+        bank.send(ix::initialize_lottery(&admin, JACKPOT, TIERS.1, TIERS.0, TIERS.2), &[admin]),
+        LotteryError::InvalidConfig,
+    );
+    bank.send(ix::initialize_lottery(&admin, JACKPOT, TIERS.0, TIERS.1, TIERS.2), &[admin])
+        .unwrap();
+    assert_error(bank.send(ix::configure_timing(&admin, 0, 300, 1), &[admin]), LotteryError::InvalidConfig);
+}
+
+#[test]
+fn paid_entry_disabled() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    let dev = bank.wallet();
+    bank.send(ix::initialize_paid_entry(&admin, paid_entry_args(None, dev, 5)), &[admin])
+        .unwrap();
+    bank.send(ix::update_paid_entry(&admin, update_args(dev, false)), &[admin]).unwrap();
+    let player = bank.wallet();
+    assert_error(
+        bank.send(ix::enter_lottery_paid_sol(&player, &dev, 1), &[player]),
+        LotteryError::PaidEntryDisabled,
+    );
+}
+
+#[test]
+fn ticket_cap_exceeded() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    let dev = bank.wallet();
+    bank.send(ix::initialize_paid_entry(&admin, paid_entry_args(None, dev, 5)), &[admin])
+        .unwrap();
+    let player = bank.wallet();
+    bank.send(ix::enter_lottery_paid_sol(&player, &dev, 4), &[player]).unwrap();
+    assert_error(
+        bank.send(ix::enter_lottery_paid_sol(&player, &dev, 2), &[player]),
+        LotteryError::TicketCapExceeded,
+    );
+}
+
+#[test]
+fn invalid_payment_account() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    let dev = bank.wallet();
+    bank.send(ix::initialize_paid_entry(&admin, paid_entry_args(None, dev, 5)), &[admin])
+        .unwrap();
+    let player = bank.wallet();
+    let not_dev = bank.wallet();
+    assert_error(
+        bank.send(ix::enter_lottery_paid_sol(&player, &not_dev, 1), &[player]),
+        LotteryError::InvalidPaymentAccount,
+    );
+}
+
+#[test]
+fn insufficient_pool_balance() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    let dev = bank.wallet();
+    bank.send(ix::initialize_paid_entry(&admin, paid_entry_args(None, dev, 5)), &[admin])
+        .unwrap();
+    let player = bank.wallet();
+    bank.send(ix::enter_lottery_paid_sol(&player, &dev, 1), &[player]).unwrap();
+    let reserve = TICKET_PRICE * 1_500 / 10_000;
+    assert_error(
+        bank.send(ix::withdraw_paid_entry_sol(&admin, &admin, reserve + 1, PaidEntryBucket::Reserve), &[admin]),
+        LotteryError::InsufficientPoolBalance,
+    );
+}
+
+#[test]
+fn math_overflow() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    let dev = bank.wallet();
+    bank.send(ix::initialize_paid_entry(&admin, paid_entry_args(None, dev, 5)), &[admin])
+        .unwrap();
+    let player = bank.wallet();
+    bank.send(ix::enter_lottery_paid_sol(&player, &dev, 1), &[player]).unwrap();
+    assert_error(
+        bank.send(ix::enter_lottery_paid_sol(&player, &dev, u32::MAX), &[player]),
+        LotteryError::MathOverflow,
+    );
+}
+
+#[test]
+fn winners_mismatch() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    let wallets = enter_participants(&mut bank, 10);
+    let seed = warp_to_draw(&mut bank, Parity::Odd);
+    bank.send(ix::take_snapshot(&admin), &[admin]).unwrap();
+
+    let (sorted, accounts, counts) = participant_set(&bank, &wallets);
+    let selection = pepeball_draw::select_winners(seed, &counts).unwrap();
+    let minors: Vec<_> = selection.minors.iter().map(|i| sorted[*i]).collect();
+    let wrong_main = sorted[(selection.main + 1) % sorted.len()];
+    assert_error(
+        bank.send(ix::set_winners_verified(&admin, wrong_main, minors.clone(), &accounts), &[admin]),
+        LotteryError::WinnersMismatch,
+    );
+
+    // Leaving a participant out is caught before any winner is compared.
+    let main = sorted[selection.main];
+    assert_error(
+        bank.send(ix::set_winners_verified(&admin, main, minors, &accounts[1..]), &[admin]),
+        LotteryError::WinnersMismatch,
+    );
+}
+
+#[test]
+fn paid_entry_required() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    let dev = bank.wallet();
+    bank.send(ix::initialize_paid_entry(&admin, paid_entry_args(None, dev, 5)), &[admin])
+        .unwrap();
+    let wallet = bank.wallet();
+    assert_error(bank.send(ix::enter_lottery(&wallet, 1), &[wallet]), LotteryError::PaidEntryRequired);
+}
+
+// ---- pepball-token ----
+
+struct Token {
+    info: Pubkey,
+    mint: Pubkey,
+    admin: Pubkey,
+    creator: Pubkey,
+    accts: token::TaxedTransferAccounts,
+}
+
+fn token_setup(bank: &mut Bank) -> Token {
+    let admin = bank.wallet();
+    let mint = Pubkey::new_unique();
+    let creator = bank.wallet();
+    let treasury = bank.wallet();
+    bank.send(
+        token::initialize_token(&mint, &admin, &treasury, token::launch_metadata(), 6, creator),
+        &[mint, admin],
+    )
+    .unwrap();
+    let info = pda::token_info(&mint).0;
+    let holder = bank.wallet();
+    let recipient = bank.wallet();
+    let accts = token::TaxedTransferAccounts {
+        token_info: info,
+        mint,
+        from: bank.create_ata(&mint, &holder, 1_000_000_000),
+        to: bank.create_ata(&mint, &recipient, 0),
+        creator_fund: bank.create_ata(&mint, &creator, 0),
+        jackpot_pool: bank.create_ata(&mint, &pda::vault_authority().0, 0),
+        authority: holder,
+        recipient,
+    };
+    Token {
+        info,
+        mint,
+        admin,
+        creator,
+        accts,
+    }
+}
+
+#[test]
+fn token_already_renounced() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    bank.send(token::renounce_admin(&t.info, &t.admin), &[t.admin]).unwrap();
+    assert_error(
+        bank.send(token::renounce_admin(&t.info, &t.admin), &[t.admin]),
+        TokenError::AlreadyRenounced,
+    );
+    assert_error(
+        bank.send(token::emergency_pause(&t.info, &t.admin), &[t.admin]),
+        TokenError::AlreadyRenounced,
+    );
+}
+
+#[test]
+fn token_unauthorized() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let intruder = bank.wallet();
+    assert_error(
+        bank.send(token::emergency_pause(&t.info, &intruder), &[intruder]),
+        TokenError::Unauthorized,
+    );
+    assert_error(
+        bank.send(token::renounce_admin(&t.info, &intruder), &[intruder]),
+        TokenError::Unauthorized,
+    );
+}
+
+#[test]
+fn transfers_paused() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    bank.send(token::emergency_pause(&t.info, &t.admin), &[t.admin]).unwrap();
+    assert_error(
+        bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[t.accts.authority]),
+        TokenError::TransfersPaused,
+    );
+}
+
+#[test]
+fn amount_too_small() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    assert_error(
+        bank.send(token::transfer_with_tax(&t.accts, 999), &[t.accts.authority]),
+        TokenError::AmountTooSmall,
+    );
+}
+
+/// A batch whose amounts overflow a `u64` is rejected before anything moves.
+#[test]
+fn invalid_tax_calculation() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let (first, second) = (bank.wallet(), bank.wallet());
+    let first_ata = bank.create_ata(&t.mint, &first, 0);
+    bank.create_ata(&t.mint, &second, 0);
+    assert_error(
+        bank.send(
+            token::batch_transfer_with_tax(&t.accts, &[(first, u64::MAX), (second, 1_000)]),
+            &[t.accts.authority],
+        ),
+        TokenError::InvalidTaxCalculation,
+    );
+    assert_eq!(bank.token_balance(&first_ata), 0);
+}
+
+/// Sent with the legacy token program, as a caller on a legacy mint would.
+#[test]
+fn transfer_fee_mode_required() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let cranker = bank.wallet();
+    let mut ix = token::withdraw_withheld_fees(
+        &t.info,
+        &t.mint,
+        &t.accts.jackpot_pool,
+        &pda::vault_authority().0,
+        &t.accts.creator_fund,
+        &t.creator,
+        &cranker,
+        &[],
+    );
+    let legacy_vault = get_associated_token_address(&pda::fee_authority(&t.mint).0, &t.mint);
+    for meta in &mut ix.accounts {
+        if meta.pubkey == anchor_spl::token_2022::ID {
+            meta.pubkey = anchor_spl::token::ID;
+        } else if meta.pubkey == token::fee_vault(&t.mint) {
+            meta.pubkey = legacy_vault;
+        }
+    }
+    assert_error(bank.send(ix, &[cranker]), TokenError::TransferFeeModeRequired);
+}
+
+#[test]
+fn token_invalid_tax_destination() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let thief = bank.wallet();
+    let own = bank.create_ata(&t.mint, &thief, 0);
+    for accts in [
+        token::TaxedTransferAccounts {
+            creator_fund: own,
+            ..t.accts
+        },
+        token::TaxedTransferAccounts {
+            jackpot_pool: own,
+            ..t.accts
+        },
+    ] {
+        assert_error(
+            bank.send(token::transfer_with_tax(&accts, 1_000_000), &[t.accts.authority]),
+            TokenError::InvalidTaxDestination,
+        );
+    }
+}
+
+#[test]
+fn token_mint_mismatch() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let other = bank.create_mint(&t.admin, 6);
+    let recipient = bank.wallet();
+    let accts = token::TaxedTransferAccounts {
+        to: bank.create_ata(&other, &recipient, 0),
+        ..t.accts
+    };
+    assert_error(
+        bank.send(token::transfer_with_tax(&accts, 1_000_000), &[t.accts.authority]),
+        TokenError::MintMismatch,
+    );
+}
+
+#[test]
+fn token_invalid_decimals() {
+    let mut bank = Bank::new();
+    let admin = bank.wallet();
+    let mint = Pubkey::new_unique();
+    assert_error(
+        bank.send(
+            token::initialize_token(&mint, &admin, &admin, token::launch_metadata(), 11, admin),
+            &[mint, admin],
+        ),
+        TokenError::InvalidDecimals,
+    );
+}
+
+#[test]
+fn token_invalid_exemption() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    assert_error(
+        bank.send(token::add_exemption(&t.info, &t.admin, t.admin, false, false), &[t.admin]),
+        TokenError::InvalidExemption,
+    );
+}
+
+#[test]
+fn token_exemption_list_full() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    for _ in 0..pepball_token::MAX_EXEMPTIONS {
+        let address = Pubkey::new_unique();
+        bank.send(token::add_exemption(&t.info, &t.admin, address, true, false), &[t.admin]).unwrap();
+    }
+    assert_error(
+        bank.send(token::add_exemption(&t.info, &t.admin, Pubkey::new_unique(), true, false), &[t.admin]),
+        TokenError::ExemptionListFull,
+    );
+}
+
+#[test]
+fn token_exemption_not_found() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    assert_error(
+        bank.send(token::remove_exemption(&t.info, &t.admin, t.admin), &[t.admin]),
+        TokenError::ExemptionNotFound,
+    );
+}
+
+#[test]
+fn token_tax_rate_too_high() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let cap = pepball_token::MAX_TAX_RATE_BPS;
+    assert_error(
+        bank.send(token::set_tax_rates(&t.info, &t.admin, TaxDirection::Sell, 1, cap, 0), &[t.admin]),
+        TokenError::TaxRateTooHigh,
+    );
+    // The burn share counts toward the cap.
+    assert_error(
+        bank.send(token::set_tax_rates(&t.info, &t.admin, TaxDirection::Buy, 0, cap, 1), &[t.admin]),
+        TokenError::TaxRateTooHigh,
+    );
+}
+
+#[test]
+fn token_amm_pool_list_full() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    for _ in 0..pepball_token::MAX_AMM_POOLS {
+        bank.send(token::register_amm_pool(&t.info, &t.admin, Pubkey::new_unique()), &[t.admin]).unwrap();
+    }
+    assert_error(
+        bank.send(token::register_amm_pool(&t.info, &t.admin, Pubkey::new_unique()), &[t.admin]),
+        TokenError::AmmPoolListFull,
+    );
+}
+
+#[test]
+fn token_amm_pool_not_found() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    assert_error(
+        bank.send(token::unregister_amm_pool(&t.info, &t.admin, t.accts.to), &[t.admin]),
+        TokenError::AmmPoolNotFound,
+    );
+}
+
+#[test]
+fn token_invalid_batch() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let wallets: Vec<Pubkey> = (0..=pepball_token::MAX_BATCH_RECIPIENTS).map(|_| bank.wallet()).collect();
+    for wallet in &wallets {
+        bank.create_ata(&t.mint, wallet, 0);
+    }
+    let too_many: Vec<_> = wallets.iter().map(|wallet| (*wallet, 1_000)).collect();
+    let duplicate = [(wallets[0], 1_000), (wallets[1], 1_000), (wallets[0], 1_000)];
+    for payments in [&[][..], &too_many[..], &duplicate[..]] {
+        assert_error(
+            bank.send(token::batch_transfer_with_tax(&t.accts, payments), &[t.accts.authority]),
+            TokenError::InvalidBatch,
+        );
+    }
+    // One amount per recipient and its checkpoints.
+    let mut ix = token::batch_transfer_with_tax(&t.accts, &[(wallets[0], 1_000)]);
+    ix.accounts.pop();
+    assert_error(bank.send(ix, &[t.accts.authority]), TokenError::InvalidBatch);
+}
+
+#[test]
+fn token_batch_to_amm_pool() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let pool_owner = bank.wallet();
+    let pool = bank.create_ata(&t.mint, &pool_owner, 0);
+    bank.send(token::register_amm_pool(&t.info, &t.admin, pool), &[t.admin]).unwrap();
+    assert_error(
+        bank.send(token::batch_transfer_with_tax(&t.accts, &[(pool_owner, 1_000)]), &[t.accts.authority]),
+        TokenError::BatchToAmmPool,
+    );
+}
+
+/// The checkpoints paired with a recipient must be its owner's.
+#[test]
+fn token_invalid_batch_checkpoints() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let wallet = bank.wallet();
+    bank.create_ata(&t.mint, &wallet, 0);
+    let mut ix = token::batch_transfer_with_tax(&t.accts, &[(wallet, 1_000)]);
+    let last = ix.accounts.len() - 1;
+    ix.accounts[last].pubkey = pda::checkpoints(&t.info, &t.admin).0;
+    assert_error(bank.send(ix, &[t.accts.authority]), TokenError::InvalidCheckpointAccount);
+}
+
+fn launch_args(start_slot: u64) -> token_ix::ConfigureLaunchProtection {
+    token_ix::ConfigureLaunchProtection {
+        start_slot,
+        window_slots: 100,
+        max_tx_amount: 2_000_000,
+        max_wallet_balance: 3_000_000,
+        cooldown_slots: 10,
+    }
+}
+
+#[test]
+fn token_invalid_launch_window() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let args = token_ix::ConfigureLaunchProtection {
+        window_slots: 0,
+        ..launch_args(1)
+    };
+    assert_error(
+        bank.send(token::configure_launch_protection(&t.info, &t.admin, args), &[t.admin]),
+        TokenError::InvalidLaunchWindow,
+    );
+    let too_long = token_ix::ConfigureLaunchProtection {
+        window_slots: pepball_token::MAX_LAUNCH_WINDOW_SLOTS + 1,
+        ..launch_args(1)
+    };
+    assert_error(
+        bank.send(token::configure_launch_protection(&t.info, &t.admin, too_long), &[t.admin]),
+        TokenError::InvalidLaunchWindow,
+    );
+    // The bank starts at slot 1.
+    let too_far = launch_args(1 + pepball_token::MAX_LAUNCH_LEAD_SLOTS + 1);
+    assert_error(
+        bank.send(token::configure_launch_protection(&t.info, &t.admin, too_far), &[t.admin]),
+        TokenError::InvalidLaunchWindow,
+    );
+}
+
+#[test]
+fn token_launch_window_started() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    // Before the start the admin can still move it.
+    bank.send(token::configure_launch_protection(&t.info, &t.admin, launch_args(50)), &[t.admin]).unwrap();
+    bank.send(token::configure_launch_protection(&t.info, &t.admin, launch_args(60)), &[t.admin]).unwrap();
+    bank.warp(60, 0);
+    assert_error(
+        bank.send(token::configure_launch_protection(&t.info, &t.admin, launch_args(100)), &[t.admin]),
+        TokenError::LaunchWindowStarted,
+    );
+    // Still fixed after the window has ended and switched off.
+    bank.warp(160, 0);
+    bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[t.accts.authority]).unwrap();
+    assert_error(
+        bank.send(token::configure_launch_protection(&t.info, &t.admin, launch_args(200)), &[t.admin]),
+        TokenError::LaunchWindowStarted,
+    );
+}
+
+#[test]
+fn token_launch_not_started() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    bank.send(token::configure_launch_protection(&t.info, &t.admin, launch_args(50)), &[t.admin]).unwrap();
+    assert_error(
+        bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[t.accts.authority]),
+        TokenError::LaunchNotStarted,
+    );
+}
+
+#[test]
+fn token_max_transaction_exceeded() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    bank.send(token::configure_launch_protection(&t.info, &t.admin, launch_args(1)), &[t.admin]).unwrap();
+    assert_error(
+        bank.send(token::transfer_with_tax(&t.accts, 2_000_001), &[t.accts.authority]),
+        TokenError::MaxTransactionExceeded,
+    );
+}
+
+#[test]
+fn token_max_wallet_exceeded() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let args = token_ix::ConfigureLaunchProtection {
+        cooldown_slots: 0,
+        ..launch_args(1)
+    };
+    bank.send(token::configure_launch_protection(&t.info, &t.admin, args), &[t.admin]).unwrap();
+    bank.send(token::transfer_with_tax(&t.accts, 2_000_000), &[t.accts.authority]).unwrap();
+    assert_error(
+        bank.send(token::transfer_with_tax(&t.accts, 2_000_000), &[t.accts.authority]),
+        TokenError::MaxWalletExceeded,
+    );
+}
+
+#[test]
+fn token_wallet_cooldown() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    bank.send(token::configure_launch_protection(&t.info, &t.admin, launch_args(1)), &[t.admin]).unwrap();
+    bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[t.accts.authority]).unwrap();
+    assert_error(
+        bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[t.accts.authority]),
+        TokenError::WalletCooldown,
+    );
+}
+
+/// Cycling through more wallets than the table holds cannot evict a
+/// wallet's cooldown: new recipients wait until an entry expires.
+#[test]
+fn token_cooldown_table_full() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    bank.send(token::configure_launch_protection(&t.info, &t.admin, launch_args(1)), &[t.admin]).unwrap();
+    let send_to_new_wallet = |bank: &mut Bank| {
+        let wallet = bank.wallet();
+        let accts = token::TaxedTransferAccounts {
+            to: bank.create_ata(&t.mint, &wallet, 0),
+            recipient: wallet,
+            ..t.accts
+        };
+        bank.send(token::transfer_with_tax(&accts, 1_000), &[t.accts.authority])
+    };
+    for _ in 0..pepball_token::MAX_COOLDOWN_WALLETS {
+        send_to_new_wallet(&mut bank).unwrap();
+    }
+    assert_error(send_to_new_wallet(&mut bank), TokenError::CooldownTableFull);
+    bank.warp(11, 0);
+    send_to_new_wallet(&mut bank).unwrap();
+}
+
+// ---- game-registry ----
+
+#[test]
+fn token_renounce_while_paused() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    bank.send(token::emergency_pause(&t.info, &t.admin), &[t.admin]).unwrap();
+    assert_error(
+        bank.send(token::renounce_admin(&t.info, &t.admin), &[t.admin]),
+        TokenError::RenounceWhilePaused,
+    );
+    assert_error(
+        bank.send(token::renounce_capability(&t.info, &t.admin, Capability::Pause), &[t.admin]),
+        TokenError::RenounceWhilePaused,
+    );
+}
+
+#[test]
+fn token_capability_renounced() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    bank.send(token::renounce_capability(&t.info, &t.admin, Capability::Rates), &[t.admin]).unwrap();
+    assert_error(
+        bank.send(token::renounce_capability(&t.info, &t.admin, Capability::Rates), &[t.admin]),
+        TokenError::CapabilityRenounced,
+    );
+    assert_error(
+        bank.send(token::set_tax_rates(&t.info, &t.admin, TaxDirection::Sell, 1, 1, 0), &[t.admin]),
+        TokenError::CapabilityRenounced,
+    );
+    let keeper = bank.wallet();
+    assert_error(
+        bank.send(token::set_jackpot_vault_owner(&t.info, &t.admin, keeper), &[t.admin]),
+        TokenError::CapabilityRenounced,
+    );
+
+    bank.send(token::renounce_capability(&t.info, &t.admin, Capability::Pause), &[t.admin]).unwrap();
+    assert_error(
+        bank.send(token::configure_launch_protection(&t.info, &t.admin, launch_args(50)), &[t.admin]),
+        TokenError::CapabilityRenounced,
+    );
+}
+
+#[test]
+fn token_metadata_too_long() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let uri = "x".repeat(pepball_token::MAX_URI_LEN + 1);
+    assert_error(
+        bank.send(token::update_metadata_uri(&t.info, &t.admin, &t.mint, &anchor_spl::token::ID, uri), &[t.admin]),
+        TokenError::MetadataTooLong,
+    );
+}
+
+#[test]
+fn token_metadata_accounts_required() {
+    let mut bank = Bank::new();
+    let admin = bank.wallet();
+    let mint = Pubkey::new_unique();
+    let mut ix = token::initialize_token(&mint, &admin, &admin, token::launch_metadata(), 6, admin);
+    // Leave out the Metaplex accounts, as a Token-2022 launch would.
+    let len = ix.accounts.len();
+    for meta in &mut ix.accounts[len - 2..] {
+        *meta = AccountMeta::new_readonly(pepball_token::ID, false);
+    }
+    assert_error(bank.send(ix, &[mint, admin]), TokenError::MetadataAccountsRequired);
+}
+
+/// Team vesting of 1_000_000 funded by the admin, clock at its start.
+fn vesting_setup(bank: &mut Bank, t: &Token, revocable: bool, end_ts: i64) -> Pubkey {
+    let start_ts = bank.clock().unix_timestamp;
+    let source = bank.create_ata(&t.mint, &t.admin, 1_000_000);
+    let beneficiary = bank.wallet();
+    let args = token_ix::CreateVesting {
+        category: VestingCategory::Team,
+        amount: 1_000_000,
+        start_ts,
+        cliff_ts: start_ts,
+        end_ts,
+        revocable,
+    };
+    let ix = token::create_vesting(&t.mint, &anchor_spl::token::ID, &t.admin, &t.admin, &source, &beneficiary, args);
+    bank.send(ix, &[t.admin]).unwrap();
+    beneficiary
+}
+
+#[test]
+fn token_invalid_vesting_schedule() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let source = bank.create_ata(&t.mint, &t.admin, 1_000_000);
+    let args = token_ix::CreateVesting {
+        category: VestingCategory::Marketing,
+        amount: 1_000_000,
+        start_ts: 100,
+        cliff_ts: 300,
+        end_ts: 200,
+        revocable: false,
+    };
+    let ix = token::create_vesting(&t.mint, &anchor_spl::token::ID, &t.admin, &t.admin, &source, &t.admin, args);
+    assert_error(bank.send(ix, &[t.admin]), TokenError::InvalidVestingSchedule);
+}
+
+#[test]
+fn token_nothing_vested() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let end_ts = bank.clock().unix_timestamp + 1_000;
+    let beneficiary = vesting_setup(&mut bank, &t, false, end_ts);
+    let payout = bank.create_ata(&t.mint, &beneficiary, 0);
+    assert_error(
+        bank.send(token::claim_vested(&t.mint, &anchor_spl::token::ID, &beneficiary, &payout, &beneficiary), &[beneficiary]),
+        TokenError::NothingVested,
+    );
+}
+
+#[test]
+fn token_vesting_not_revocable() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let end_ts = bank.clock().unix_timestamp + 1_000;
+    let beneficiary = vesting_setup(&mut bank, &t, false, end_ts);
+    let refund = bank.create_ata(&t.mint, &t.admin, 0);
+    assert_error(
+        bank.send(token::revoke_vesting(&t.mint, &anchor_spl::token::ID, &t.admin, &beneficiary, &refund, &t.admin), &[t.admin]),
+        TokenError::VestingNotRevocable,
+    );
+}
+
+/// `t.accts.from` re-owned by a keypair that approved the permit delegate.
+fn permit_setup(bank: &mut Bank, t: &mut Token) -> Keypair {
+    let owner = Keypair::new();
+    t.accts.from = bank.create_ata(&t.mint, &owner.pubkey(), 1_000_000_000);
+    t.accts.authority = owner.pubkey();
+    bank.send(token::approve_permits(&t.info, &t.accts.from, &owner.pubkey(), u64::MAX), &[owner.pubkey()])
+        .unwrap();
+    owner
+}
+
+/// `signer`'s permit of 1_000_000 for `t.accts` and the relayed transfer.
+fn permit(t: &Token, signer: &Keypair, relayer: &Pubkey, nonce: u64, expiry: i64) -> [Instruction; 2] {
+    let message = token::permit_message(&t.accts.from, &t.accts.to, 1_000_000, nonce, expiry);
+    let signature: [u8; 64] = signer.sign_message(&message).into();
+    [
+        token::permit_signature_check(&signer.pubkey(), &signature, &message),
+        token::transfer_with_tax_permit(&t.accts, relayer, 1_000_000, nonce, expiry),
+    ]
+}
+
+#[test]
+fn token_invalid_permit() {
+    let mut bank = Bank::new();
+    let mut t = token_setup(&mut bank);
+    permit_setup(&mut bank, &mut t);
+    let relayer = bank.wallet();
+    let expiry = bank.clock().unix_timestamp + 60;
+    // Validly signed, but not by the owner of `from`.
+    let [check, transfer] = permit(&t, &Keypair::new(), &relayer, 0, expiry);
+    assert_error(bank.process(&[check, transfer.clone()], &[relayer]), TokenError::InvalidPermit);
+    // No signature check before the transfer.
+    assert_error(bank.send(transfer, &[relayer]), TokenError::InvalidPermit);
+}
+
+#[test]
+fn token_permit_expired() {
+    let mut bank = Bank::new();
+    let mut t = token_setup(&mut bank);
+    let owner = permit_setup(&mut bank, &mut t);
+    let relayer = bank.wallet();
+    let expiry = bank.clock().unix_timestamp - 1;
+    assert_error(bank.process(&permit(&t, &owner, &relayer, 0, expiry), &[relayer]), TokenError::PermitExpired);
+}
+
+#[test]
+fn token_invalid_permit_nonce() {
+    let mut bank = Bank::new();
+    let mut t = token_setup(&mut bank);
+    let owner = permit_setup(&mut bank, &mut t);
+    let relayer = bank.wallet();
+    let expiry = bank.clock().unix_timestamp + 60;
+    bank.process(&permit(&t, &owner, &relayer, 0, expiry), &[relayer]).unwrap();
+    // Replaying the same permit, or skipping ahead, is refused.
+    for nonce in [0, 2] {
+        assert_error(
+            bank.process(&permit(&t, &owner, &relayer, nonce, expiry), &[relayer]),
+            TokenError::InvalidPermitNonce,
+        );
+    }
+}
+
+#[test]
+fn token_invalid_checkpoint_account() {
+    let mut bank = Bank::new();
+    let t = token_setup(&mut bank);
+    let owner = bank.wallet();
+    // A transfer must carry the checkpoints of `to`'s actual owner.
+    let accts = token::TaxedTransferAccounts {
+        recipient: owner,
+        ..t.accts
+    };
+    assert_error(
+        bank.send(token::transfer_with_tax(&accts, 1_000_000), &[t.accts.authority]),
+        TokenError::InvalidCheckpointAccount,
+    );
+}
+
+fn registry_args(jackpot_sol_dest: Pubkey) -> registry_ix::InitializeRegistry {
+    registry_ix::InitializeRegistry {
+        jackpot_sol_dest,
+        lottery_program: lottery::ID,
+        pepball_token_program: pepball_token::ID,
+        tax_harvest_program: tax_harvest::ID,
+        lp_manager_program: lp_manager::ID,
+    }
+}
+
+fn mints(master: Pubkey) -> registry_ix::RegisterMints {
+    registry_ix::RegisterMints {
+        master_mint: master,
+        pump_shell_mint: Pubkey::new_unique(),
+        trix_yang_mint: Pubkey::new_unique(),
+        trix_yin_mint: master,
+        trix_bridge_mint: Pubkey::new_unique(),
+    }
+}
+
+fn registry_setup(bank: &mut Bank) -> Pubkey {
+    let admin = bank.wallet();
+    let dest = bank.wallet();
+    bank.send(registry::initialize_registry(&admin, registry_args(dest)), &[admin])
+        .unwrap();
+    admin
+}
+
+#[test]
+fn registry_already_sealed() {
+    let mut bank = Bank::new();
+    let admin = registry_setup(&mut bank);
+    let master = Pubkey::new_unique();
+    bank.send(registry::register_mints(&admin, mints(master)), &[admin]).unwrap();
+    bank.send(registry::seal_registry(&admin), &[admin]).unwrap();
+    assert_error(
+        bank.send(registry::register_mints(&admin, mints(master)), &[admin]),
+        RegistryError::AlreadySealed,
+    );
+    assert_error(bank.send(registry::seal_registry(&admin), &[admin]), RegistryError::AlreadySealed);
+}
+
+#[test]
+fn registry_unauthorized() {
+    let mut bank = Bank::new();
+    registry_setup(&mut bank);
+    let intruder = bank.wallet();
+    assert_error(
+        bank.send(registry::seal_registry(&intruder), &[intruder]),
+        RegistryError::Unauthorized,
+    );
+}
+
+#[test]
+fn registry_invalid_mint() {
+    let mut bank = Bank::new();
+    let admin = registry_setup(&mut bank);
+    let mut args = mints(Pubkey::new_unique());
+    args.pump_shell_mint = Pubkey::default();
+    assert_error(bank.send(registry::register_mints(&admin, args), &[admin]), RegistryError::InvalidMint);
+}
+
+#[test]
+fn registry_mints_not_registered() {
+    let mut bank = Bank::new();
+    let admin = registry_setup(&mut bank);
+    assert_error(
+        bank.send(registry::seal_registry(&admin), &[admin]),
+        RegistryError::MintsNotRegistered,
+    );
+}
+
+#[test]
+fn registry_yin_must_equal_master() {
+    let mut bank = Bank::new();
+    let admin = registry_setup(&mut bank);
+    let mut args = mints(Pubkey::new_unique());
+    args.trix_yin_mint = Pubkey::new_unique();
+    assert_error(
+        bank.send(registry::register_mints(&admin, args), &[admin]),
+        RegistryError::YinMustEqualMaster,
+    );
+}
+
+// ---- tax-harvest ----
+
+struct Harvest {
+    authority: Pubkey,
+    vault: Pubkey,
+    mint: Pubkey,
+    dest: Pubkey,
+    route: SwapRoute,
+}
+
+impl Harvest {
+    fn process(&self, amount: u64) -> Instruction {
+        harvest::process_harvest(&self.authority, &self.vault, &self.mint, &self.dest, amount, 0, &self.route)
+    }
+}
+
+fn harvest_setup(bank: &mut Bank, vault_owner: Option<Pubkey>) -> Harvest {
+    let authority = bank.wallet();
+    let dest = bank.wallet();
+    bank.send(harvest::initialize(&authority, dest), &[authority]).unwrap();
+    let mint = bank.create_mint(&authority, 6);
+    let owner = vault_owner.unwrap_or(pepeball_sdk::pda::vault_authority().0);
+    let vault = bank.create_token_account(Pubkey::new_unique(), &mint, &owner, 5_000);
+    let route = bank.create_whirlpool(&mint, &native_mint::ID, 1 << 64, 1_000_000).route(&mint);
+    Harvest { authority, vault, mint, dest, route }
+}
+
+#[test]
+fn harvest_invalid_amount() {
+    let mut bank = Bank::new();
+    let h = harvest_setup(&mut bank, None);
+    assert_error(bank.send(h.process(0), &[h.authority]), HarvestError::InvalidAmount);
+}
+
+#[test]
+fn harvest_insufficient_vault_balance() {
+    let mut bank = Bank::new();
+    let h = harvest_setup(&mut bank, None);
+    assert_error(bank.send(h.process(5_001), &[h.authority]), HarvestError::InsufficientVaultBalance);
+}
+
+#[test]
+fn harvest_vault_not_owned_by_program() {
+    let mut bank = Bank::new();
+    let stranger = bank.wallet();
+    let h = harvest_setup(&mut bank, Some(stranger));
+    assert_error(bank.send(h.process(1), &[h.authority]), HarvestError::VaultNotOwnedByProgram);
+}
+
+#[test]
+fn harvest_invalid_swap_accounts() {
+    let mut bank = Bank::new();
+    let mut h = harvest_setup(&mut bank, None);
+    // A SOL pool for some other token.
+    let other = bank.create_mint(&h.authority, 6);
+    h.route = bank.create_whirlpool(&other, &native_mint::ID, 1 << 64, 1_000_000).route(&other);
+    assert_error(bank.send(h.process(1), &[h.authority]), HarvestError::InvalidSwapAccounts);
+}
+
+// ---- lp-manager ----
+
+fn lp_manager_setup(bank: &mut Bank) -> (Pubkey, Pubkey) {
+    let lp = Pubkey::new_unique();
+    let admin = bank.wallet();
+    bank.send(lp_manager_sdk::initialize_lp_manager(&lp, &admin), &[lp, admin]).unwrap();
+    (lp, admin)
+}
+
+#[test]
+fn lp_manager_unauthorized() {
+    let mut bank = Bank::new();
+    let (lp, _) = lp_manager_setup(&mut bank);
+    let intruder = bank.wallet();
+    assert_error(
+        bank.send(lp_manager_sdk::initialize_orca_position(&lp, &intruder, -64, 64), &[intruder]),
+        LpManagerError::Unauthorized,
+    );
+    assert_error(
+        bank.send(lp_manager_sdk::set_target_delta(&lp, &intruder, 1), &[intruder]),
+        LpManagerError::Unauthorized,
+    );
+}
+
+/// `initialize_lp_manager` always sets the flag, so the state is written by hand.
+#[test]
+fn lp_manager_not_initialized() {
+    let mut bank = Bank::new();
+    let lp = Pubkey::new_unique();
+    let admin = bank.wallet();
+    let state = lp_manager::LPManager {
+        admin,
+        is_initialized: false,
+        total_funded: 0,
+        sol_converted: 0,
+        jackpot_boosts: 0,
+        fee_conversion_rate: 0,
+        jackpot_funding_rate: 0,
+        orca_position_initialized: false,
+        tick_lower: 0,
+        tick_upper: 0,
+        target_delta: 0,
+        current_delta: 0,
+        last_rebalance_amount: 0,
+        last_rebalance_direction: 0,
+        rebalance_count: 0,
+        last_rebalance_time: 0,
+        orca_whirlpool: None,
+        orca_position_nft: None,
+    };
+    let mut data = Vec::new();
+    state.try_serialize(&mut data).unwrap();
+    bank.set_account(
+        lp,
+        StoredAccount {
+            lamports: 1_000_000_000,
+            data,
+            owner: lp_manager::ID,
+            executable: false,
+        },
+    );
+    assert_error(
+        bank.send(lp_manager_sdk::initialize_orca_position(&lp, &admin, -64, 64), &[admin]),
+        LpManagerError::NotInitialized,
+    );
+    assert_error(bank.send(lp_manager_sdk::set_target_delta(&lp, &admin, 1), &[admin]), LpManagerError::NotInitialized);
+}
+
+#[test]
+fn position_not_initialized() {
+    let mut bank = Bank::new();
+    let (lp, admin) = lp_manager_setup(&mut bank);
+    assert_error(
+        bank.send(lp_manager_sdk::auto_rebalance(&lp, &admin, 1_000, 500, 100), &[admin]),
+        LpManagerError::PositionNotInitialized,
+    );
+}
+
+#[test]
+fn invalid_tick_range() {
+    let mut bank = Bank::new();
+    let (lp, admin) = lp_manager_setup(&mut bank);
+    assert_error(
+        bank.send(lp_manager_sdk::initialize_orca_position(&lp, &admin, 64, 64), &[admin]),
+        LpManagerError::InvalidTickRange,
+    );
+    bank.send(lp_manager_sdk::initialize_orca_position(&lp, &admin, -64, 64), &[admin]).unwrap();
+    assert_error(
+        bank.send(lp_manager_sdk::update_position_ticks(&lp, &admin, 128, -128), &[admin]),
+        LpManagerError::InvalidTickRange,
+    );
+}
+
+#[test]
+fn lp_manager_insufficient_funds() {
+    let mut bank = Bank::new();
+    let (lp, admin) = lp_manager_setup(&mut bank);
+    let mint = bank.create_mint(&admin, 6);
+    let lp_pool = bank.create_ata(&mint, &admin, 1_000);
+    let owner = bank.wallet();
+    let jackpot = bank.create_ata(&mint, &owner, 0);
+    assert_error(
+        bank.send(lp_manager_sdk::fund_jackpot(&lp, &lp_pool, &jackpot, &admin, 1_001), &[admin]),
+        LpManagerError::InsufficientFunds,
+    );
+    assert_error(
+        bank.send(lp_manager_sdk::emergency_withdraw(&lp, &lp_pool, &jackpot, &admin, 1_001), &[admin]),
+        LpManagerError::InsufficientFunds,
+    );
+}
@@ -0,0 +1,829 @@
+//! Launch path outside the lottery: registry seal, taxed transfers and their destinations, pause/renounce, harvest.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::spl_token::native_mint;
+use game_registry::GameRegistry;
+use pepball_token::ErrorCode as TokenError;
+use pepball_token::{
+    AmmPools, BalanceCheckpoints, Capability, ExemptionList, LaunchGuard, PermitNonce, TaxDirection, TaxSplit, TokenInfo, VestingCategory,
+    VestingSchedule,
+};
+use pepeball_sdk::programs::game_registry::instruction as registry_ix;
+use pepeball_sdk::programs::pepball_token::instruction as token_ix;
+use pepeball_sdk::decode::{self, TokenEvent};
+use pepeball_sdk::{harvest, pda, registry, token};
+use pepeball_tests::whirlpool::AMOUNT_OUT_BELOW_MINIMUM;
+use pepeball_tests::{assert_error, Bank};
+use solana_sdk::signature::{Keypair, Signer};
+use tax_harvest::HarvestConfig;
+
+#[test]
+fn registry_init_register_seal() {
+    let mut bank = Bank::new();
+    let admin = bank.wallet();
+    let dest = bank.wallet();
+    let args = registry_ix::InitializeRegistry {
+        jackpot_sol_dest: dest,
+        lottery_program: lottery::ID,
+        pepball_token_program: pepball_token::ID,
+        tax_harvest_program: tax_harvest::ID,
+        lp_manager_program: lp_manager::ID,
+    };
+    bank.send(registry::initialize_registry(&admin, args), &[admin]).unwrap();
+
+    let master = Pubkey::new_unique();
+    let bridge = Pubkey::new_unique();
+    let mints = registry_ix::RegisterMints {
+        master_mint: master,
+        pump_shell_mint: Pubkey::new_unique(),
+        trix_yang_mint: Pubkey::new_unique(),
+        trix_yin_mint: master,
+        trix_bridge_mint: bridge,
+    };
+    bank.send(registry::register_mints(&admin, mints), &[admin]).unwrap();
+    bank.send(registry::seal_registry(&admin), &[admin]).unwrap();
+
+    let reg: GameRegistry = bank.anchor_account(&pda::game_registry().0);
+    assert!(reg.sealed);
+    assert!(reg.mints_registered);
+    assert_eq!(reg.admin, admin);
+    assert_eq!(reg.master_mint, master);
+    assert_eq!(reg.trix_yin_mint, master);
+    assert_eq!(reg.trix_bridge_mint, bridge);
+    assert_eq!(reg.jackpot_sol_dest, dest);
+    assert_eq!(reg.lottery_program, lottery::ID);
+    assert_eq!((reg.peg_numerator, reg.peg_denominator), (1, 1));
+}
+
+struct Launch {
+    info: Pubkey,
+    mint: Pubkey,
+    admin: Pubkey,
+    treasury: Pubkey,
+    accts: token::TaxedTransferAccounts,
+}
+
+fn launch_token(bank: &mut Bank, balance: u64) -> Launch {
+    let admin = bank.wallet();
+    let creator = bank.wallet();
+    let treasury = bank.wallet();
+    let mint = Pubkey::new_unique();
+    bank.send(
+        token::initialize_token(&mint, &admin, &treasury, token::launch_metadata(), 6, creator),
+        &[mint, admin],
+    )
+    .unwrap();
+    let info = pda::token_info(&mint).0;
+
+    let holder = bank.wallet();
+    let recipient = bank.wallet();
+    let accts = token::TaxedTransferAccounts {
+        token_info: info,
+        mint,
+        from: bank.create_ata(&mint, &holder, balance),
+        to: bank.create_ata(&mint, &recipient, 0),
+        creator_fund: bank.create_ata(&mint, &creator, 0),
+        jackpot_pool: bank.create_token_account(Pubkey::new_unique(), &mint, &pda::vault_authority().0, 0),
+        authority: holder,
+        recipient,
+    };
+    Launch {
+        info,
+        mint,
+        admin,
+        treasury,
+        accts,
+    }
+}
+
+#[test]
+fn initialize_token_sets_tax_rates() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 0);
+    let info: TokenInfo = bank.anchor_account(&t.info);
+    assert_eq!(info.admin, t.admin);
+    assert_eq!(info.total_supply, 1_000_000_000 * 1_000_000);
+    assert_eq!((info.tax_rate, info.creator_fund_rate, info.jackpot_rate), (250, 5, 245));
+    assert_eq!(info.min_transfer_amount, 1_000);
+    assert!(!info.is_paused && !info.is_renounced);
+}
+
+#[test]
+fn initialize_token_mints_the_supply_and_revokes_authority() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 0);
+    let info: TokenInfo = bank.anchor_account(&t.info);
+    assert_eq!(info.mint, t.mint);
+    assert_eq!(info.bump, pda::token_info(&t.mint).1);
+    assert!(!info.transfer_fee_mode);
+
+    let distribution = anchor_spl::associated_token::get_associated_token_address(&t.treasury, &t.mint);
+    assert_eq!(bank.token_balance(&distribution), info.total_supply);
+    let mint = bank.anchor_account::<anchor_spl::token::Mint>(&t.mint);
+    assert_eq!(mint.decimals, 6);
+    assert!(mint.mint_authority.is_none());
+    assert!(mint.freeze_authority.is_none());
+}
+
+#[test]
+fn transfer_with_tax_splits_creator_and_jackpot() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[t.accts.authority]).unwrap();
+
+    assert_eq!(bank.token_balance(&t.accts.from), 9_000_000);
+    assert_eq!(bank.token_balance(&t.accts.to), 975_000);
+    assert_eq!(bank.token_balance(&t.accts.creator_fund), 500);
+    assert_eq!(bank.token_balance(&t.accts.jackpot_pool), 24_500);
+}
+
+#[test]
+fn taxed_transfers_accumulate_stats_and_emit_events() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    let logs = bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[t.accts.authority]).unwrap();
+    let events: Vec<_> = decode::event_payloads(&logs)
+        .iter()
+        .filter_map(|p| decode::decode_token_event(p))
+        .collect();
+    match events.as_slice() {
+        [TokenEvent::TaxedTransfer(e)] => {
+            assert_eq!((e.token_info, e.from, e.to), (t.info, t.accts.from, t.accts.to));
+            assert_eq!(e.direction, TaxDirection::Transfer);
+            assert_eq!((e.amount, e.net_amount, e.creator_tax, e.jackpot_tax), (1_000_000, 975_000, 500, 24_500));
+        }
+        other => panic!("unexpected events {:?}", other.iter().map(|e| e.name()).collect::<Vec<_>>()),
+    }
+
+    bank.send(token::transfer_with_tax(&t.accts, 2_000_000), &[t.accts.authority]).unwrap();
+    let stats = bank.anchor_account::<TokenInfo>(&t.info).stats;
+    assert_eq!(stats.transfer_count, 2);
+    assert_eq!(stats.total_volume, 3_000_000);
+    assert_eq!(stats.total_creator_tax, bank.token_balance(&t.accts.creator_fund));
+    assert_eq!(stats.total_jackpot_tax, bank.token_balance(&t.accts.jackpot_pool));
+    assert_eq!(stats.total_jackpot_tax, 73_500);
+}
+
+#[test]
+fn pause_toggles_then_renounce_locks_admin() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    let holder = t.accts.authority;
+
+    bank.send(token::emergency_pause(&t.info, &t.admin), &[t.admin]).unwrap();
+    assert!(bank.anchor_account::<TokenInfo>(&t.info).is_paused);
+    assert!(bank.send(token::transfer_with_tax(&t.accts, 1_000), &[holder]).is_err());
+
+    bank.send(token::emergency_pause(&t.info, &t.admin), &[t.admin]).unwrap();
+    bank.send(token::transfer_with_tax(&t.accts, 1_000), &[holder]).unwrap();
+
+    bank.send(token::renounce_admin(&t.info, &t.admin), &[t.admin]).unwrap();
+    let info: TokenInfo = bank.anchor_account(&t.info);
+    assert!(info.is_renounced);
+    assert_eq!(info.admin, Pubkey::default());
+    assert!(bank.send(token::emergency_pause(&t.info, &t.admin), &[t.admin]).is_err());
+}
+
+const T0: i64 = 1_700_000_000;
+
+fn vesting_args(revocable: bool) -> token_ix::CreateVesting {
+    token_ix::CreateVesting {
+        category: VestingCategory::Team,
+        amount: 1_000_000,
+        start_ts: T0,
+        cliff_ts: T0 + 1_000,
+        end_ts: T0 + 10_000,
+        revocable,
+    }
+}
+
+#[test]
+fn vesting_cliff_linear_claim_and_revoke() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 0);
+    let program = anchor_spl::token::ID;
+    let treasury_ata = anchor_spl::associated_token::get_associated_token_address(&t.treasury, &t.mint);
+    let team = bank.wallet();
+    let payout = bank.create_ata(&t.mint, &team, 0);
+
+    let ix = token::create_vesting(&t.mint, &program, &t.admin, &t.treasury, &treasury_ata, &team, vesting_args(true));
+    let logs = bank.send(ix, &[t.admin, t.treasury]).unwrap();
+    let escrow = token::vesting_escrow(&t.mint, &program, &team);
+    assert_eq!(bank.token_balance(&escrow), 1_000_000);
+    let events: Vec<_> = decode::event_payloads(&logs)
+        .iter()
+        .filter_map(|p| decode::decode_token_event(p))
+        .collect();
+    match events.as_slice() {
+        [TokenEvent::VestingCreated(e)] => {
+            assert_eq!((e.beneficiary, e.category, e.amount), (team, VestingCategory::Team, 1_000_000));
+        }
+        other => panic!("unexpected events {:?}", other.iter().map(|e| e.name()).collect::<Vec<_>>()),
+    }
+
+    // Before the cliff nothing is claimable; after it, the linear share since start.
+    bank.warp(10, T0 + 999);
+    assert!(bank.send(token::claim_vested(&t.mint, &program, &team, &payout, &team), &[team]).is_err());
+    bank.warp(20, T0 + 5_000);
+    bank.send(token::claim_vested(&t.mint, &program, &team, &payout, &team), &[team]).unwrap();
+    assert_eq!(bank.token_balance(&payout), 500_000);
+
+    // Revoking keeps what has vested claimable and refunds the rest.
+    bank.warp(30, T0 + 7_500);
+    let ix = token::revoke_vesting(&t.mint, &program, &t.admin, &team, &treasury_ata, &t.treasury);
+    bank.send(ix, &[t.admin]).unwrap();
+    assert_eq!(bank.token_balance(&escrow), 250_000);
+    bank.warp(40, T0 + 20_000);
+    bank.send(token::claim_vested(&t.mint, &program, &team, &payout, &team), &[team]).unwrap();
+    assert_eq!(bank.token_balance(&payout), 750_000);
+    assert_eq!(bank.token_balance(&escrow), 0);
+
+    let schedule: VestingSchedule = bank.anchor_account(&pda::vesting(&t.info, &team).0);
+    assert!(schedule.revoked);
+    assert_eq!((schedule.total_amount, schedule.claimed), (750_000, 750_000));
+    // Only the beneficiary can claim.
+    let stranger = bank.wallet();
+    assert!(bank.send(token::claim_vested(&t.mint, &program, &stranger, &payout, &team), &[stranger]).is_err());
+}
+
+/// A legacy mint's name, symbol and URI live in Metaplex metadata that
+/// `token_info` updates.
+#[test]
+fn legacy_token_metadata_is_on_metaplex() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    let launch = token::launch_metadata();
+    let metadata = bank.metaplex_metadata(&t.mint).unwrap();
+    assert_eq!((&metadata.name, &metadata.symbol, metadata.uri.as_str()), (&launch.name, &launch.symbol, token::METADATA_URI));
+    assert_eq!((metadata.update_authority, metadata.mint, metadata.is_mutable), (t.info, t.mint, true));
+
+    let uri = "ipfs://pepeball".to_string();
+    let ix = token::update_metadata_uri(&t.info, &t.admin, &t.mint, &anchor_spl::token::ID, uri.clone());
+    bank.send(ix, &[t.admin]).unwrap();
+    let metadata = bank.metaplex_metadata(&t.mint).unwrap();
+    assert_eq!((metadata.name, metadata.symbol, &metadata.uri), (launch.name, launch.symbol, &uri));
+    assert_eq!(bank.anchor_account::<TokenInfo>(&t.info).uri, uri);
+
+    let stranger = bank.wallet();
+    let ix = token::update_metadata_uri(&t.info, &stranger, &t.mint, &anchor_spl::token::ID, "ipfs://x".into());
+    assert_error(bank.send(ix, &[stranger]), TokenError::Unauthorized);
+    assert_eq!(bank.metaplex_metadata(&t.mint).unwrap().uri, uri);
+}
+
+#[test]
+fn admin_handoff_needs_acceptance() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    let multisig = bank.wallet();
+
+    bank.send(token::propose_admin(&t.info, &t.admin, multisig), &[t.admin]).unwrap();
+    let info: TokenInfo = bank.anchor_account(&t.info);
+    assert_eq!((info.admin, info.pending_admin), (t.admin, multisig));
+    // The old admin keeps control until the handoff is accepted.
+    bank.send(token::emergency_pause(&t.info, &t.admin), &[t.admin]).unwrap();
+    let stranger = bank.wallet();
+    assert!(bank.send(token::accept_admin(&t.info, &stranger), &[stranger]).is_err());
+
+    bank.send(token::accept_admin(&t.info, &multisig), &[multisig]).unwrap();
+    let info: TokenInfo = bank.anchor_account(&t.info);
+    assert_eq!((info.admin, info.pending_admin), (multisig, Pubkey::default()));
+    assert!(bank.send(token::emergency_pause(&t.info, &t.admin), &[t.admin]).is_err());
+    bank.send(token::emergency_pause(&t.info, &multisig), &[multisig]).unwrap();
+    assert!(bank.send(token::accept_admin(&t.info, &multisig), &[multisig]).is_err());
+}
+
+#[test]
+fn renounced_capability_leaves_the_others() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    let pool = t.accts.to;
+
+    bank.send(token::renounce_capability(&t.info, &t.admin, Capability::Exemptions), &[t.admin]).unwrap();
+    assert!(bank.send(token::add_exemption(&t.info, &t.admin, pool, true, true), &[t.admin]).is_err());
+    bank.send(token::register_amm_pool(&t.info, &t.admin, pool), &[t.admin]).unwrap();
+
+    bank.send(token::renounce_capability(&t.info, &t.admin, Capability::Rates), &[t.admin]).unwrap();
+    assert!(bank.send(token::unregister_amm_pool(&t.info, &t.admin, pool), &[t.admin]).is_err());
+    assert!(bank
+        .send(token::set_tax_rates(&t.info, &t.admin, TaxDirection::Buy, 0, 0, 0), &[t.admin])
+        .is_err());
+    let keeper = bank.wallet();
+    assert!(bank.send(token::set_jackpot_vault_owner(&t.info, &t.admin, keeper), &[t.admin]).is_err());
+    let window = || token_ix::ConfigureLaunchProtection {
+        start_slot: 50,
+        window_slots: 100,
+        max_tx_amount: 0,
+        max_wallet_balance: 0,
+        cooldown_slots: 0,
+    };
+    bank.send(token::configure_launch_protection(&t.info, &t.admin, window()), &[t.admin]).unwrap();
+
+    bank.send(token::renounce_capability(&t.info, &t.admin, Capability::Pause), &[t.admin]).unwrap();
+    assert!(bank.send(token::emergency_pause(&t.info, &t.admin), &[t.admin]).is_err());
+    assert!(bank
+        .send(token::configure_launch_protection(&t.info, &t.admin, window()), &[t.admin])
+        .is_err());
+    // Non-scoped powers remain.
+    let multisig = bank.wallet();
+    bank.send(token::propose_admin(&t.info, &t.admin, multisig), &[t.admin]).unwrap();
+    let info: TokenInfo = bank.anchor_account(&t.info);
+    assert!(info.rates_renounced && info.exemptions_renounced && info.pause_renounced && !info.is_renounced);
+}
+
+#[test]
+fn jackpot_vault_owner_is_configurable_until_renounce() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    assert_eq!(bank.anchor_account::<TokenInfo>(&t.info).jackpot_vault_owner, pda::vault_authority().0);
+
+    let keeper = bank.wallet();
+    bank.send(token::set_jackpot_vault_owner(&t.info, &t.admin, keeper), &[t.admin]).unwrap();
+    let accts = token::TaxedTransferAccounts {
+        jackpot_pool: bank.create_ata(&t.mint, &keeper, 0),
+        ..t.accts
+    };
+    bank.send(token::transfer_with_tax(&accts, 1_000_000), &[accts.authority]).unwrap();
+    assert_eq!(bank.token_balance(&accts.jackpot_pool), 24_500);
+    // The old vault is no longer a valid destination.
+    assert!(bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[accts.authority]).is_err());
+
+    bank.send(token::renounce_admin(&t.info, &t.admin), &[t.admin]).unwrap();
+    assert!(bank
+        .send(token::set_jackpot_vault_owner(&t.info, &t.admin, t.admin), &[t.admin])
+        .is_err());
+}
+
+#[test]
+fn exempt_sender_or_recipient_moves_untaxed() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    let holder = t.accts.authority;
+    let recipient = bank.anchor_account::<anchor_spl::token::TokenAccount>(&t.accts.to).owner;
+
+    // A recipient-only exemption does not cover the holder's sends.
+    let logs = bank
+        .send(token::add_exemption(&t.info, &t.admin, holder, false, true), &[t.admin])
+        .unwrap();
+    let events: Vec<_> = decode::event_payloads(&logs)
+        .iter()
+        .filter_map(|p| decode::decode_token_event(p))
+        .collect();
+    match events.as_slice() {
+        [TokenEvent::ExemptionAdded(e)] => {
+            assert_eq!((e.token_info, e.address), (t.info, holder));
+            assert!(!e.exempt_sender && e.exempt_recipient);
+        }
+        other => panic!("unexpected events {:?}", other.iter().map(|e| e.name()).collect::<Vec<_>>()),
+    }
+    bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[holder]).unwrap();
+    assert_eq!(bank.token_balance(&t.accts.to), 975_000);
+
+    // Upsert to a sender exemption: full amount, no tax, below the minimum too.
+    bank.send(token::add_exemption(&t.info, &t.admin, holder, true, false), &[t.admin]).unwrap();
+    let list: ExemptionList = bank.anchor_account(&pda::exemption_list(&t.info).0);
+    assert_eq!(list.token_info, t.info);
+    assert_eq!(list.entries.len(), 1);
+    bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[holder]).unwrap();
+    bank.send(token::transfer_with_tax(&t.accts, 10), &[holder]).unwrap();
+    assert_eq!(bank.token_balance(&t.accts.to), 1_975_010);
+    assert_eq!(bank.token_balance(&t.accts.jackpot_pool), 24_500);
+
+    bank.send(token::remove_exemption(&t.info, &t.admin, holder), &[t.admin]).unwrap();
+    bank.send(token::add_exemption(&t.info, &t.admin, recipient, false, true), &[t.admin]).unwrap();
+    bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[holder]).unwrap();
+    assert_eq!(bank.token_balance(&t.accts.to), 2_975_010);
+
+    // Pause still applies to exempt transfers.
+    bank.send(token::emergency_pause(&t.info, &t.admin), &[t.admin]).unwrap();
+    assert!(bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[holder]).is_err());
+}
+
+#[test]
+fn exemptions_are_frozen_after_renounce() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    let holder = t.accts.authority;
+    bank.send(token::add_exemption(&t.info, &t.admin, holder, true, true), &[t.admin]).unwrap();
+    bank.send(token::renounce_admin(&t.info, &t.admin), &[t.admin]).unwrap();
+
+    assert!(bank.send(token::remove_exemption(&t.info, &t.admin, holder), &[t.admin]).is_err());
+    let other = bank.wallet();
+    assert!(bank
+        .send(token::add_exemption(&t.info, &t.admin, other, true, true), &[t.admin])
+        .is_err());
+    // The existing entry keeps working.
+    bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[holder]).unwrap();
+    assert_eq!(bank.token_balance(&t.accts.to), 1_000_000);
+}
+
+#[test]
+fn quote_transfer_matches_the_transfer() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    let holder = t.accts.authority;
+    let quote = |bank: &mut Bank, to: &Pubkey, amount: u64| {
+        let logs = bank
+            .send(token::quote_transfer(&t.info, &t.mint, &t.accts.from, to, amount), &[])
+            .unwrap();
+        decode::transfer_quote(&logs).unwrap()
+    };
+
+    // 1_234_567 * 250 bps = 30_864.175 -> 30_864; creator 617.28 -> 617.
+    let q = quote(&mut bank, &t.accts.to, 1_234_567);
+    assert_eq!(q.direction, TaxDirection::Transfer);
+    assert!(!q.exempt);
+    assert_eq!((q.net_amount, q.creator_tax, q.jackpot_tax), (1_203_703, 617, 30_247));
+    assert_eq!(q.net_amount + q.creator_tax + q.jackpot_tax, q.amount);
+    bank.send(token::transfer_with_tax(&t.accts, 1_234_567), &[holder]).unwrap();
+    assert_eq!(bank.token_balance(&t.accts.to), q.net_amount);
+    assert_eq!(bank.token_balance(&t.accts.creator_fund), q.creator_tax);
+    assert_eq!(bank.token_balance(&t.accts.jackpot_pool), q.jackpot_tax);
+
+    bank.send(token::register_amm_pool(&t.info, &t.admin, t.accts.to), &[t.admin]).unwrap();
+    bank.send(token::set_tax_rates(&t.info, &t.admin, TaxDirection::Sell, 0, 5, 0), &[t.admin]).unwrap();
+    let q = quote(&mut bank, &t.accts.to, 1_000);
+    assert_eq!(q.direction, TaxDirection::Sell);
+    assert_eq!((q.net_amount, q.creator_tax, q.jackpot_tax), (1_000, 0, 0));
+    // A rate that rounds to no tax at the minimum no longer rejects the transfer.
+    bank.send(token::transfer_with_tax(&t.accts, 1_000), &[holder]).unwrap();
+
+    assert!(bank
+        .send(token::quote_transfer(&t.info, &t.mint, &t.accts.from, &t.accts.to, 999), &[])
+        .is_err());
+    bank.send(token::add_exemption(&t.info, &t.admin, holder, true, false), &[t.admin]).unwrap();
+    let q = quote(&mut bank, &t.accts.to, 999);
+    assert!(q.exempt);
+    assert_eq!(q.net_amount, 999);
+}
+
+#[test]
+fn batch_transfer_splits_the_tax_once() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    let holder = t.accts.authority;
+    let wallets: Vec<Pubkey> = (0..3).map(|_| bank.wallet()).collect();
+    let recipients: Vec<Pubkey> = wallets.iter().map(|w| bank.create_ata(&t.mint, w, 0)).collect();
+    let payments = [(wallets[0], 1_000), (wallets[1], 1_000), (wallets[2], 998_000)];
+
+    let logs = bank.send(token::batch_transfer_with_tax(&t.accts, &payments), &[holder]).unwrap();
+    // 25 + 25 + 24_950 withheld; creator gets 5 bps of the 1_000_000 total.
+    assert_eq!(bank.token_balance(&recipients[0]), 975);
+    assert_eq!(bank.token_balance(&recipients[1]), 975);
+    assert_eq!(bank.token_balance(&recipients[2]), 973_050);
+    assert_eq!(bank.token_balance(&t.accts.creator_fund), 500);
+    assert_eq!(bank.token_balance(&t.accts.jackpot_pool), 24_500);
+    assert_eq!(bank.token_balance(&t.accts.from), 9_000_000);
+    let events: Vec<_> = decode::event_payloads(&logs)
+        .iter()
+        .filter_map(|p| decode::decode_token_event(p))
+        .collect();
+    match events.as_slice() {
+        [TokenEvent::BatchTaxedTransfer(e)] => {
+            assert_eq!((e.recipients, e.amount, e.net_amount), (3, 1_000_000, 975_000));
+            assert_eq!((e.creator_tax, e.jackpot_tax), (500, 24_500));
+        }
+        other => panic!("unexpected events {:?}", other.iter().map(|e| e.name()).collect::<Vec<_>>()),
+    }
+    let stats = bank.anchor_account::<TokenInfo>(&t.info).stats;
+    assert_eq!((stats.transfer_count, stats.total_volume), (3, 1_000_000));
+    // The sender's and every recipient's checkpoints hold the new balances.
+    let history = |owner: &Pubkey| bank.anchor_account::<BalanceCheckpoints>(&pda::checkpoints(&t.info, owner).0);
+    assert_eq!(history(&holder).history.checkpoints[0].balance, 9_000_000);
+    assert_eq!(history(&wallets[2]).history.checkpoints[0].balance, 973_050);
+
+    // Every amount must meet the minimum, even an exempt recipient's; those
+    // are paid in full.
+    let short = [(wallets[0], 1_000), (wallets[1], 999)];
+    assert!(bank.send(token::batch_transfer_with_tax(&t.accts, &short), &[holder]).is_err());
+    bank.send(token::add_exemption(&t.info, &t.admin, wallets[1], false, true), &[t.admin]).unwrap();
+    assert!(bank.send(token::batch_transfer_with_tax(&t.accts, &short), &[holder]).is_err());
+    bank.send(token::batch_transfer_with_tax(&t.accts, &[(wallets[0], 1_000), (wallets[1], 1_000)]), &[holder])
+        .unwrap();
+    assert_eq!(bank.token_balance(&recipients[1]), 1_975);
+    assert_eq!(bank.token_balance(&t.accts.jackpot_pool), 24_525);
+
+    // The batch is quoted as one transfer of 2_078: 51 units of tax where two
+    // transfers of 1_039 would floor to 25 each. The nets share the rest.
+    let (first, second) = (bank.wallet(), bank.wallet());
+    let first_ata = bank.create_ata(&t.mint, &first, 0);
+    let second_ata = bank.create_ata(&t.mint, &second, 0);
+    bank.send(token::batch_transfer_with_tax(&t.accts, &[(first, 1_039), (second, 1_039)]), &[holder]).unwrap();
+    assert_eq!(bank.token_balance(&first_ata), 1_013);
+    assert_eq!(bank.token_balance(&second_ata), 1_014);
+    assert_eq!(bank.token_balance(&t.accts.creator_fund), 501);
+    assert_eq!(bank.token_balance(&t.accts.jackpot_pool), 24_575);
+
+    // Sells go through transfer_with_tax at the sell rate, not a batch.
+    bank.send(token::register_amm_pool(&t.info, &t.admin, recipients[2]), &[t.admin]).unwrap();
+    assert!(bank
+        .send(token::batch_transfer_with_tax(&t.accts, &[(wallets[0], 1_000), (wallets[2], 1_000)]), &[holder])
+        .is_err());
+}
+
+#[test]
+fn permit_lets_a_relayer_move_taxed_tokens() {
+    let mut bank = Bank::new();
+    let mut t = launch_token(&mut bank, 0);
+    let owner = Keypair::new();
+    t.accts.from = bank.create_ata(&t.mint, &owner.pubkey(), 10_000_000);
+    t.accts.authority = owner.pubkey();
+    bank.send(token::approve_permits(&t.info, &t.accts.from, &owner.pubkey(), 2_000_000), &[owner.pubkey()])
+        .unwrap();
+
+    // The owner signs off-chain; only the relayer signs the transaction.
+    let relayer = bank.wallet();
+    let expiry = bank.clock().unix_timestamp + 60;
+    let permit = |nonce: u64| {
+        let message = token::permit_message(&t.accts.from, &t.accts.to, 1_000_000, nonce, expiry);
+        let signature: [u8; 64] = owner.sign_message(&message).into();
+        [
+            token::permit_signature_check(&owner.pubkey(), &signature, &message),
+            token::transfer_with_tax_permit(&t.accts, &relayer, 1_000_000, nonce, expiry),
+        ]
+    };
+    bank.process(&permit(0), &[relayer]).unwrap();
+    assert_eq!(bank.token_balance(&t.accts.from), 9_000_000);
+    assert_eq!(bank.token_balance(&t.accts.to), 975_000);
+    assert_eq!(bank.token_balance(&t.accts.creator_fund), 500);
+    assert_eq!(bank.token_balance(&t.accts.jackpot_pool), 24_500);
+    let nonce: PermitNonce = bank.anchor_account(&pda::permit_nonce(&t.info, &owner.pubkey()).0);
+    assert_eq!(nonce.nonce, 1);
+
+    // A permit is good once; the next one carries the next nonce.
+    assert!(bank.process(&permit(0), &[relayer]).is_err());
+    bank.process(&permit(1), &[relayer]).unwrap();
+    assert_eq!(bank.token_balance(&t.accts.from), 8_000_000);
+    // The approval is spent, so a third permit fails in the token program.
+    assert!(bank.process(&permit(2), &[relayer]).is_err());
+}
+
+/// Taxed transfers keep both sides' checkpoints, one per interval.
+#[test]
+fn checkpoints_record_balances_by_interval() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    let (holder, recipient) = (t.accts.authority, t.accts.recipient);
+    let interval = pepball_token::CHECKPOINT_INTERVAL_SLOTS;
+    // Slot 1: the first taxed transfer creates both sides' checkpoints.
+    bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[holder]).unwrap();
+    bank.warp(interval + 10, T0 + 4);
+    bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[holder]).unwrap();
+
+    let history = |bank: &mut Bank, owner: &Pubkey| {
+        bank.anchor_account::<BalanceCheckpoints>(&pda::checkpoints(&t.info, owner).0).history
+    };
+    let (held, received) = (history(&mut bank, &holder), history(&mut bank, &recipient));
+    assert_eq!(held.balance_at(interval).unwrap(), 9_000_000);
+    assert_eq!(held.balance_at(2 * interval).unwrap(), 8_000_000);
+    assert_eq!(received.balance_at(2 * interval).unwrap(), 1_950_000);
+    // Before the kept history the balance is unknown, not zero.
+    assert!(held.balance_at(0).is_err());
+    assert!(received.min_balance(0, 2 * interval).is_err());
+    assert_eq!(held.min_balance(1, 2 * interval).unwrap(), 8_000_000);
+    assert_eq!(received.min_balance(1, 2 * interval).unwrap(), 975_000);
+
+    // Within an interval the records merge and the lowest balance counts
+    // until the interval ends.
+    bank.warp(2 * interval + 5, T0 + 8);
+    bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[holder]).unwrap();
+    let back = token::TaxedTransferAccounts {
+        from: t.accts.to,
+        to: t.accts.from,
+        authority: recipient,
+        recipient: holder,
+        ..t.accts
+    };
+    bank.warp(2 * interval + 6, T0 + 9);
+    bank.send(token::transfer_with_tax(&back, 1_000_000), &[recipient]).unwrap();
+    let held = history(&mut bank, &holder);
+    assert_eq!(held.checkpoints.len(), 3);
+    assert_eq!(held.balance_at(2 * interval + 7).unwrap(), 7_000_000);
+    assert_eq!(held.balance_at(3 * interval).unwrap(), 7_975_000);
+    assert_eq!(held.min_balance(1, 3 * interval).unwrap(), 7_000_000);
+
+    // Transferring every slot cannot push out the history.
+    for slot in 0..pepball_token::MAX_CHECKPOINTS as u64 + 4 {
+        bank.warp(2 * interval + 10 + slot, T0 + 10);
+        bank.send(token::transfer_with_tax(&t.accts, 1_000), &[holder]).unwrap();
+    }
+    let held = history(&mut bank, &holder);
+    assert_eq!(held.checkpoints.len(), 3);
+    assert_eq!(held.balance_at(interval).unwrap(), 9_000_000);
+}
+
+#[test]
+fn buy_sell_and_transfer_use_their_own_rates() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    let holder = t.accts.authority;
+    let pool_owner = bank.wallet();
+    let pool = bank.create_ata(&t.mint, &pool_owner, 10_000_000);
+
+    bank.send(token::register_amm_pool(&t.info, &t.admin, pool), &[t.admin]).unwrap();
+    bank.send(token::register_amm_pool(&t.info, &t.admin, pool), &[t.admin]).unwrap();
+    let pools: AmmPools = bank.anchor_account(&pda::amm_pools(&t.info).0);
+    assert_eq!(pools.pools, vec![pool]);
+
+    // Launch week: sells taxed at 10%, buys untaxed.
+    bank.send(token::set_tax_rates(&t.info, &t.admin, TaxDirection::Sell, 100, 900, 0), &[t.admin]).unwrap();
+    bank.send(token::set_tax_rates(&t.info, &t.admin, TaxDirection::Buy, 0, 0, 0), &[t.admin]).unwrap();
+    let info: TokenInfo = bank.anchor_account(&t.info);
+    assert_eq!(info.sell_tax, TaxSplit { creator_fund_rate: 100, jackpot_rate: 900, burn_rate: 0 });
+    assert_eq!(info.tax_split(TaxDirection::Transfer), TaxSplit::launch());
+
+    let sell = token::TaxedTransferAccounts {
+        to: pool,
+        recipient: pool_owner,
+        ..t.accts
+    };
+    bank.send(token::transfer_with_tax(&sell, 1_000_000), &[holder]).unwrap();
+    assert_eq!(bank.token_balance(&pool), 10_900_000);
+    assert_eq!(bank.token_balance(&t.accts.creator_fund), 10_000);
+    assert_eq!(bank.token_balance(&t.accts.jackpot_pool), 90_000);
+
+    let buy = token::TaxedTransferAccounts {
+        from: pool,
+        authority: pool_owner,
+        ..t.accts
+    };
+    bank.send(token::transfer_with_tax(&buy, 1_000_000), &[pool_owner]).unwrap();
+    assert_eq!(bank.token_balance(&t.accts.to), 1_000_000);
+
+    bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[holder]).unwrap();
+    assert_eq!(bank.token_balance(&t.accts.to), 1_975_000);
+
+    // Unregistered, the pool is just another wallet.
+    bank.send(token::unregister_amm_pool(&t.info, &t.admin, pool), &[t.admin]).unwrap();
+    bank.send(token::transfer_with_tax(&sell, 1_000_000), &[holder]).unwrap();
+    assert_eq!(bank.token_balance(&pool), 10_875_000);
+}
+
+#[test]
+fn tax_rates_are_frozen_after_renounce() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    bank.send(token::set_tax_rates(&t.info, &t.admin, TaxDirection::Transfer, 10, 90, 0), &[t.admin]).unwrap();
+    let info: TokenInfo = bank.anchor_account(&t.info);
+    assert_eq!((info.tax_rate, info.creator_fund_rate, info.jackpot_rate), (100, 10, 90));
+
+    bank.send(token::renounce_admin(&t.info, &t.admin), &[t.admin]).unwrap();
+    assert!(bank
+        .send(token::set_tax_rates(&t.info, &t.admin, TaxDirection::Transfer, 5, 245, 0), &[t.admin])
+        .is_err());
+    assert!(bank
+        .send(token::register_amm_pool(&t.info, &t.admin, t.accts.to), &[t.admin])
+        .is_err());
+}
+
+#[test]
+fn burn_share_reduces_the_supply() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    let holder = t.accts.authority;
+    bank.send(token::set_tax_rates(&t.info, &t.admin, TaxDirection::Transfer, 5, 145, 100), &[t.admin]).unwrap();
+
+    let ix = token::quote_transfer(&t.info, &t.mint, &t.accts.from, &t.accts.to, 1_000_000);
+    let q = decode::transfer_quote(&bank.send(ix, &[]).unwrap()).unwrap();
+    assert_eq!((q.net_amount, q.creator_tax, q.jackpot_tax, q.burn_tax), (975_000, 500, 14_500, 10_000));
+
+    let logs = bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[holder]).unwrap();
+    assert_eq!(bank.token_balance(&t.accts.to), 975_000);
+    assert_eq!(bank.token_balance(&t.accts.jackpot_pool), 14_500);
+    match decode::event_payloads(&logs).iter().filter_map(|p| decode::decode_token_event(p)).next() {
+        Some(TokenEvent::TaxedTransfer(e)) => assert_eq!(e.burn_tax, 10_000),
+        _ => panic!("no TaxedTransfer event"),
+    }
+
+    let recipient = bank.wallet();
+    bank.create_ata(&t.mint, &recipient, 0);
+    bank.send(token::batch_transfer_with_tax(&t.accts, &[(recipient, 1_000_000)]), &[holder]).unwrap();
+    assert_eq!(bank.token_balance(&t.accts.from), 8_000_000);
+    let info: TokenInfo = bank.anchor_account(&t.info);
+    assert_eq!(info.stats.total_burned, 20_000);
+    assert_eq!(info.circulating_supply(), info.total_supply - 20_000);
+    let logs = bank.send(token::circulating_supply(&t.info), &[]).unwrap();
+    assert_eq!(decode::circulating_supply(&logs), Some(info.total_supply - 20_000));
+    // Burned through the token program, so the mint's supply matches (plus
+    // the holder balance the fixture seeds outside the minted supply).
+    let mint = bank.anchor_account::<anchor_spl::token::Mint>(&t.mint);
+    assert_eq!(mint.supply, info.circulating_supply() + 10_000_000);
+}
+
+#[test]
+fn launch_protection_limits_the_window_then_switches_off() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 100_000_000);
+    let holder = t.accts.authority;
+    let pool_owner = bank.wallet();
+    let pool = bank.create_ata(&t.mint, &pool_owner, 0);
+    bank.send(token::register_amm_pool(&t.info, &t.admin, pool), &[t.admin]).unwrap();
+    let args = || token_ix::ConfigureLaunchProtection {
+        start_slot: 10,
+        window_slots: 100,
+        max_tx_amount: 5_000_000,
+        max_wallet_balance: 6_000_000,
+        cooldown_slots: 20,
+    };
+    bank.send(token::configure_launch_protection(&t.info, &t.admin, args()), &[t.admin]).unwrap();
+
+    // Held until the start slot, except for exempt owners.
+    assert!(bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[holder]).is_err());
+    let seeder = bank.wallet();
+    let seeded = token::TaxedTransferAccounts {
+        to: bank.create_ata(&t.mint, &seeder, 0),
+        recipient: seeder,
+        ..t.accts
+    };
+    bank.send(token::add_exemption(&t.info, &t.admin, seeder, false, true), &[t.admin]).unwrap();
+    bank.send(token::transfer_with_tax(&seeded, 50_000_000), &[holder]).unwrap();
+
+    bank.warp(10, 0);
+    assert!(bank.send(token::transfer_with_tax(&t.accts, 5_000_001), &[holder]).is_err());
+    bank.send(token::transfer_with_tax(&t.accts, 5_000_000), &[holder]).unwrap();
+    let guard: LaunchGuard = bank.anchor_account(&pda::launch_guard(&t.info).0);
+    assert_eq!(guard.cooldowns.entries.len(), 1);
+    assert_eq!(guard.cooldowns.entries[0].last_slot, 10);
+
+    // Cooldown, then the wallet cap (4_875_000 held).
+    bank.warp(29, 0);
+    assert!(bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[holder]).is_err());
+    bank.warp(30, 0);
+    assert!(bank.send(token::transfer_with_tax(&t.accts, 2_000_000), &[holder]).is_err());
+    bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[holder]).unwrap();
+
+    // Sells into a registered pool are held to the max tx only.
+    let sell = token::TaxedTransferAccounts {
+        to: pool,
+        recipient: pool_owner,
+        ..t.accts
+    };
+    bank.send(token::transfer_with_tax(&sell, 5_000_000), &[holder]).unwrap();
+    bank.send(token::transfer_with_tax(&sell, 5_000_000), &[holder]).unwrap();
+
+    // A batch is one transaction: its total is held to the max tx.
+    let wallets = [bank.wallet(), bank.wallet()];
+    for wallet in &wallets {
+        bank.create_ata(&t.mint, wallet, 0);
+    }
+    let split = |each: u64| wallets.map(|wallet| (wallet, each));
+    assert!(bank.send(token::batch_transfer_with_tax(&t.accts, &split(3_000_000)), &[holder]).is_err());
+    bank.send(token::batch_transfer_with_tax(&t.accts, &split(2_500_000)), &[holder]).unwrap();
+
+    // After the window the limits are gone and the config switches off.
+    bank.warp(110, 0);
+    bank.send(token::transfer_with_tax(&t.accts, 20_000_000), &[holder]).unwrap();
+    assert!(!bank.anchor_account::<TokenInfo>(&t.info).launch_protection.enabled);
+
+    bank.send(token::renounce_admin(&t.info, &t.admin), &[t.admin]).unwrap();
+    assert!(bank
+        .send(token::configure_launch_protection(&t.info, &t.admin, args()), &[t.admin])
+        .is_err());
+}
+
+#[test]
+fn harvest_accepts_the_program_owned_vault() {
+    let mut bank = Bank::new();
+    let t = launch_token(&mut bank, 10_000_000);
+    bank.send(token::transfer_with_tax(&t.accts, 1_000_000), &[t.accts.authority]).unwrap();
+
+    let authority = bank.wallet();
+    let dest = bank.wallet();
+    bank.send(harvest::initialize(&authority, dest), &[authority]).unwrap();
+    let config: HarvestConfig = bank.anchor_account(&pda::config().0);
+    assert_eq!(config.jackpot_sol_dest, dest);
+    assert_eq!(config.authority, authority);
+    assert_eq!(config.bump, pda::config().1);
+
+    let vault = t.accts.jackpot_pool;
+    let mint = bank.anchor_account::<anchor_spl::token::TokenAccount>(&vault).mint;
+    // sqrt_price 2^63: 1 raw token = 1/4 lamport.
+    let pool = bank.create_whirlpool(&mint, &native_mint::ID, 1 << 63, 1_000_000_000);
+    let route = pool.route(&mint);
+    let harvest = |min_sol_out, dest: &Pubkey| {
+        harvest::process_harvest(&authority, &vault, &mint, dest, 24_500, min_sol_out, &route)
+    };
+
+    // The pool can't fill the keeper's minimum: nothing moves.
+    let vault_before = bank.token_balance(&vault);
+    assert_error(bank.send(harvest(6_126, &dest), &[authority]), AMOUNT_OUT_BELOW_MINIMUM);
+
+    let (dest_before, authority_before) = (bank.lamports(&dest), bank.lamports(&authority));
+    let logs = bank.send(harvest(6_125, &dest), &[authority]).unwrap();
+    assert!(logs.iter().any(|l| l.contains("swapped 24500 raw tokens -> 6125 lamports")));
+    assert_eq!(bank.token_balance(&vault), vault_before - 24_500);
+    let pool_tokens = if pool.pool.token_mint_a == mint { pool.pool.token_vault_a } else { pool.pool.token_vault_b };
+    assert_eq!(bank.token_balance(&pool_tokens), 1_000_000_000 + 24_500);
+    assert_eq!(bank.lamports(&dest), dest_before + 6_125);
+    // The wrapped SOL account is closed again and its rent refunded.
+    assert_eq!(bank.lamports(&authority), authority_before);
+    let wsol = anchor_spl::associated_token::get_associated_token_address(&pda::vault_authority().0, &native_mint::ID);
+    assert!(bank.account(&wsol).is_none());
+    assert_eq!(bank.lamports(&pda::vault_authority().0), 0);
+
+    // Harvest is only allowed to pay the configured destination, and only the config authority may run it.
+    let other = bank.wallet();
+    assert!(bank.send(harvest(0, &other), &[authority]).is_err());
+    let keeper = bank.wallet();
+    let ix = harvest::process_harvest(&keeper, &vault, &mint, &dest, 1, 0, &route);
+    assert!(bank.send(ix, &[keeper]).is_err());
+}
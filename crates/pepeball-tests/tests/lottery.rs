@@ -0,0 +1,315 @@
+//! Lottery lifecycle: entries, EVEN rollover, ODD draw, verified winners, payout, pause, paid entries.
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use lottery::{ErrorCode as LotteryError, PaidEntryConfig, ParticipantAccount};
+use pepeball_draw::Parity;
+use pepeball_sdk::decode::{self, LotteryEvent};
+use pepeball_sdk::lottery::{self as ix, PaidEntryBucket};
+use pepeball_sdk::pda;
+use pepeball_tests::{assert_error, Bank, StoredAccount};
+
+fn events(logs: &[String]) -> Vec<LotteryEvent> {
+    decode::event_payloads(logs)
+        .iter()
+        .filter_map(|p| decode::decode_lottery_event(p))
+        .collect()
+}
+
+#[test]
+fn initialize_sets_defaults() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+
+    let l = lottery(&bank);
+    assert_eq!(l.admin, admin);
+    assert_eq!(l.jackpot_amount, JACKPOT);
+    assert_eq!((l.entry_min_cents, l.tier2_min_cents, l.tier3_min_cents), TIERS);
+    assert_eq!(l.base_snapshot_interval, 72 * 3600);
+    assert_eq!(l.fast_snapshot_interval, 48 * 3600);
+    assert_eq!(l.last_snapshot, bank.clock().unix_timestamp);
+    assert!(l.is_active);
+}
+
+#[test]
+fn entries_accumulate_tickets() {
+    let mut bank = Bank::new();
+    init_lottery(&mut bank);
+
+    let free = bank.wallet();
+    bank.send(ix::enter_lottery(&free, 3), &[free]).unwrap();
+    let usd = bank.wallet();
+    let logs = bank.send(ix::enter_lottery_with_usd_value(&usd, TIERS.1), &[usd]).unwrap();
+    match events(&logs).as_slice() {
+        [LotteryEvent::UsdEntryTiered(e)] => {
+            assert_eq!((e.wallet, e.usd_value, e.ticket_count), (usd, TIERS.1, 2));
+        }
+        _ => panic!("expected one UsdEntryTiered event"),
+    }
+    bank.send(ix::update_participant_tickets(&usd, 4, TIERS.2), &[usd]).unwrap();
+
+    let participant: ParticipantAccount = bank.anchor_account(&pda::participant(&lottery_key(), &usd).0);
+    assert_eq!(participant.wallet, usd);
+    assert_eq!(participant.ticket_count, 6);
+    assert_eq!(participant.usd_value, TIERS.1 + TIERS.2);
+
+    let l = lottery(&bank);
+    assert_eq!(l.total_participants, 2);
+    assert_eq!(l.total_tickets, 9);
+}
+
+#[test]
+fn even_snapshot_rolls_over_then_odd_snapshot_pays_out() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    let wallets = enter_participants(&mut bank, 10);
+    let total_tickets = lottery(&bank).total_tickets;
+
+    // EVEN: timer extended by the short rollover, participants kept, no seed to draw from.
+    let seed = warp_to_draw(&mut bank, Parity::Even);
+    let logs = bank.send(ix::take_snapshot(&admin), &[admin]).unwrap();
+    let now = bank.clock().unix_timestamp;
+    let l = lottery(&bank);
+    assert_eq!(l.total_snapshots, 1);
+    assert_eq!(l.rollover_count, 1);
+    assert_eq!(l.snapshot_seed, 0);
+    assert_eq!(l.pepe_ball_count % 2, 0);
+    assert_eq!(l.last_snapshot, now + pepeball_draw::ROLLOVER_SHORT_SECS);
+    assert_eq!(l.total_participants, 10);
+    match events(&logs).as_slice() {
+        [LotteryEvent::SnapshotTaken(e)] => {
+            assert_eq!(e.seed, seed);
+            assert_eq!(e.snapshot_number, 1);
+            assert_eq!(e.total_tickets, total_tickets);
+        }
+        other => panic!("unexpected events {:?}", other.iter().map(|e| e.name()).collect::<Vec<_>>()),
+    }
+
+    // Payout is refused while the draw is EVEN.
+    assert!(bank.send(ix::payout_winners(&admin), &[admin]).is_err());
+
+    // ODD: seed kept, winners recomputed on chain from the full participant set.
+    let seed = warp_to_draw(&mut bank, Parity::Odd);
+    bank.send(ix::take_snapshot(&admin), &[admin]).unwrap();
+    let l = lottery(&bank);
+    assert_eq!(l.snapshot_seed, seed);
+    assert_eq!(l.pepe_ball_count % 2, 1);
+
+    let (sorted, accounts, counts) = participant_set(&bank, &wallets);
+    let selection = pepeball_draw::select_winners(seed, &counts).unwrap();
+    let main = sorted[selection.main];
+    let minors: Vec<_> = selection.minors.iter().map(|i| sorted[*i]).collect();
+    bank.send(ix::set_winners_verified(&admin, main, minors.clone(), &accounts), &[admin])
+        .unwrap();
+    let l = lottery(&bank);
+    assert_eq!(l.winners.main_winner, Some(main));
+    assert_eq!(l.winners.minor_winners, minors);
+
+    let logs = bank.send(ix::payout_winners(&admin), &[admin]).unwrap();
+    match events(&logs).as_slice() {
+        [LotteryEvent::RoundPaidOut(e)] => {
+            assert_eq!(e.seed, seed);
+            assert_eq!(e.snapshot_number, 2);
+            assert_eq!(e.jackpot_amount, JACKPOT);
+            assert_eq!(e.main_winner, main);
+            assert_eq!(e.minor_winners, minors);
+        }
+        other => panic!("unexpected events {:?}", other.iter().map(|e| e.name()).collect::<Vec<_>>()),
+    }
+    let l = lottery(&bank);
+    assert_eq!(l.jackpot_amount, 0);
+    assert_eq!(l.total_participants, 0);
+    assert_eq!(l.total_tickets, 0);
+    assert_eq!(l.rollover_count, 0);
+    assert_eq!(l.snapshot_seed, 0);
+    assert_eq!(l.winners.main_winner, None);
+}
+
+#[test]
+fn fast_mode_shortens_the_snapshot_interval() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    enter_participants(&mut bank, 9);
+    let threshold = lottery(&bank).fast_mode_threshold;
+    bank.send(ix::update_fees_collected(&admin, threshold), &[admin]).unwrap();
+    assert!(lottery(&bank).is_fast_mode);
+
+    let start = lottery(&bank).last_snapshot;
+    warp_to_draw(&mut bank, Parity::Odd);
+    assert!(bank.clock().unix_timestamp - start < 72 * 3600);
+    bank.send(ix::take_snapshot(&admin), &[admin]).unwrap();
+    assert_eq!(lottery(&bank).total_snapshots, 1);
+}
+
+#[test]
+fn configure_timing_changes_intervals() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    enter_participants(&mut bank, 9);
+    bank.send(ix::configure_timing(&admin, 600, 300, 1), &[admin]).unwrap();
+
+    let start = lottery(&bank).last_snapshot;
+    warp_to_draw(&mut bank, Parity::Odd);
+    assert!(bank.clock().unix_timestamp - start < 48 * 3600);
+    bank.send(ix::take_snapshot(&admin), &[admin]).unwrap();
+}
+
+#[test]
+fn pause_toggles_entries() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+
+    bank.send(ix::emergency_pause_lottery(&admin), &[admin]).unwrap();
+    assert!(!lottery(&bank).is_active);
+    let wallet = bank.wallet();
+    assert!(bank.send(ix::enter_lottery(&wallet, 1), &[wallet]).is_err());
+
+    bank.send(ix::emergency_pause_lottery(&admin), &[admin]).unwrap();
+    assert!(lottery(&bank).is_active);
+    bank.send(ix::enter_lottery(&wallet, 1), &[wallet]).unwrap();
+}
+
+#[test]
+fn paid_sol_entries_split_between_vault_and_dev() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    let dev = bank.wallet();
+    bank.send(ix::initialize_paid_entry(&admin, paid_entry_args(None, dev, 5)), &[admin])
+        .unwrap();
+
+    let vault = pda::lottery_vault(&lottery_key()).0;
+    let (vault_start, dev_start) = (bank.lamports(&vault), bank.lamports(&dev));
+    let player = bank.wallet();
+    bank.send(ix::enter_lottery_paid_sol(&player, &dev, 3), &[player]).unwrap();
+    bank.send(ix::enter_lottery_paid_sol(&player, &dev, 2), &[player]).unwrap();
+
+    let total = 5 * TICKET_PRICE;
+    let config: PaidEntryConfig = bank.anchor_account(&pda::paid_entry(&lottery_key()).0);
+    assert_eq!(config.tickets_sold, 5);
+    assert_eq!(config.prize_pool, total * 8_000 / 10_000);
+    assert_eq!(config.reserve_pool, total * 1_500 / 10_000);
+    assert_eq!(config.dev_paid, total * 500 / 10_000);
+    assert_eq!(bank.lamports(&vault) - vault_start, config.prize_pool + config.reserve_pool);
+    assert_eq!(bank.lamports(&dev) - dev_start, config.dev_paid);
+
+    let participant: ParticipantAccount = bank.anchor_account(&pda::participant(&lottery_key(), &player).0);
+    assert_eq!(participant.ticket_count, 5);
+    assert_eq!(lottery(&bank).total_participants, 1);
+
+    let destination = bank.wallet();
+    let before = bank.lamports(&destination);
+    bank.send(ix::withdraw_paid_entry_sol(&admin, &destination, config.prize_pool, PaidEntryBucket::Prize), &[admin])
+        .unwrap();
+    assert_eq!(bank.lamports(&destination) - before, config.prize_pool);
+    let config: PaidEntryConfig = bank.anchor_account(&pda::paid_entry(&lottery_key()).0);
+    assert_eq!(config.prize_pool, 0);
+}
+
+/// Free tickets would skip the price and the cap, so paid mode closes them
+/// to everyone but the admin, and the admin stays within the cap.
+#[test]
+fn paid_entry_closes_the_free_paths() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    let early = bank.wallet();
+    bank.send(ix::enter_lottery(&early, 1), &[early]).unwrap();
+    let dev = bank.wallet();
+    bank.send(ix::initialize_paid_entry(&admin, paid_entry_args(None, dev, 5)), &[admin])
+        .unwrap();
+
+    let player = bank.wallet();
+    assert_error(bank.send(ix::enter_lottery(&player, 1), &[player]), LotteryError::PaidEntryRequired);
+    assert_error(
+        bank.send(ix::enter_lottery_with_usd_value(&player, TIERS.2), &[player]),
+        LotteryError::PaidEntryRequired,
+    );
+    assert_error(
+        bank.send(ix::update_participant_tickets(&early, 100, 0), &[early]),
+        LotteryError::PaidEntryRequired,
+    );
+    let stranger = bank.wallet();
+    assert_error(
+        bank.send(ix::with_admin_cosign(ix::enter_lottery(&player, 1), &stranger), &[player, stranger]),
+        LotteryError::PaidEntryRequired,
+    );
+
+    // The admin can still grant free tickets, up to the per-wallet cap.
+    let grant = |ix| ix::with_admin_cosign(ix, &admin);
+    assert_error(
+        bank.send(grant(ix::update_participant_tickets(&early, 5, 0)), &[early, admin]),
+        LotteryError::TicketCapExceeded,
+    );
+    bank.send(grant(ix::update_participant_tickets(&early, 4, 0)), &[early, admin]).unwrap();
+    bank.send(grant(ix::enter_lottery(&player, 2)), &[player, admin]).unwrap();
+    assert_error(
+        bank.send(ix::enter_lottery_paid_sol(&player, &dev, 4), &[player]),
+        LotteryError::TicketCapExceeded,
+    );
+    assert_eq!(lottery(&bank).total_tickets, 7);
+}
+
+#[test]
+fn paid_sol_entries_pay_a_multisig_dev_wallet() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    // A data account owned by another program, like a Squads vault config.
+    let dev = Pubkey::new_unique();
+    bank.set_account(
+        dev,
+        StoredAccount {
+            lamports: 1_000_000,
+            data: vec![7; 64],
+            owner: Pubkey::new_unique(),
+            executable: false,
+        },
+    );
+    bank.send(ix::initialize_paid_entry(&admin, paid_entry_args(None, dev, 5)), &[admin])
+        .unwrap();
+
+    let player = bank.wallet();
+    bank.send(ix::enter_lottery_paid_sol(&player, &dev, 1), &[player]).unwrap();
+    assert_eq!(bank.lamports(&dev) - 1_000_000, TICKET_PRICE * 500 / 10_000);
+}
+
+#[test]
+fn paid_token_entries_create_the_vault_ata() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    let dev = bank.wallet();
+    let usdc = bank.create_mint(&admin, 6);
+    let dev_ata = bank.create_ata(&usdc, &dev, 0);
+    bank.send(ix::initialize_paid_entry(&admin, paid_entry_args(Some(usdc), dev, 10)), &[admin])
+        .unwrap();
+
+    let player = bank.wallet();
+    let player_ata = bank.create_ata(&usdc, &player, 10 * TICKET_PRICE);
+    bank.send(ix::enter_lottery_paid_token(&player, &usdc, &dev, 4), &[player]).unwrap();
+
+    let total = 4 * TICKET_PRICE;
+    let vault = pda::lottery_vault(&lottery_key()).0;
+    let vault_ata = anchor_spl::associated_token::get_associated_token_address(&vault, &usdc);
+    assert_eq!(bank.token_balance(&player_ata), 6 * TICKET_PRICE);
+    assert_eq!(bank.token_balance(&vault_ata), total * 9_500 / 10_000);
+    assert_eq!(bank.token_balance(&dev_ata), total * 500 / 10_000);
+
+    let treasury = bank.create_ata(&usdc, &admin, 0);
+    let reserve = total * 1_500 / 10_000;
+    bank.send(ix::withdraw_paid_entry_token(&admin, &usdc, &treasury, reserve, PaidEntryBucket::Reserve), &[admin])
+        .unwrap();
+    assert_eq!(bank.token_balance(&treasury), reserve);
+    assert_eq!(bank.token_balance(&vault_ata), total * 8_000 / 10_000);
+}
+
+#[test]
+fn close_lottery_returns_rent_to_admin() {
+    let mut bank = Bank::new();
+    let admin = init_lottery(&mut bank);
+    let before = bank.lamports(&admin);
+    let rent = bank.lamports(&lottery_key());
+
+    bank.send(ix::close_lottery(&admin), &[admin]).unwrap();
+    assert!(bank.account(&lottery_key()).is_none());
+    assert_eq!(bank.lamports(&admin), before + rent);
+}
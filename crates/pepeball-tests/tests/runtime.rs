@@ -0,0 +1,31 @@
+//! The bank's own runtime rules: account modifications the real runtime rejects fail here too.
+
+use anchor_lang::prelude::{AccountMeta, ProgramError};
+use anchor_lang::solana_program::system_instruction;
+use anchor_spl::token::spl_token;
+use pepeball_tests::Bank;
+
+#[test]
+fn read_only_accounts_cannot_receive_lamports() {
+    let mut bank = Bank::new();
+    let (from, to) = (bank.wallet(), bank.wallet());
+    let mut ix = system_instruction::transfer(&from, &to, 1_000);
+    ix.accounts[1] = AccountMeta::new_readonly(to, false);
+    let err = bank.send(ix, &[from]).unwrap_err();
+    assert_eq!(err.error, ProgramError::InvalidAccountData);
+    assert!(err.logs.iter().any(|l| l.contains(&format!("modified read-only account {to}"))));
+}
+
+#[test]
+fn read_only_token_accounts_cannot_change() {
+    let mut bank = Bank::new();
+    let owner = bank.wallet();
+    let mint = bank.create_mint(&owner, 6);
+    let from = bank.create_ata(&mint, &owner, 500);
+    let recipient = bank.wallet();
+    let to = bank.create_ata(&mint, &recipient, 0);
+    let mut ix = spl_token::instruction::transfer(&spl_token::ID, &from, &to, &owner, &[], 100).unwrap();
+    ix.accounts[0] = AccountMeta::new_readonly(from, false);
+    assert_eq!(bank.send(ix, &[owner]).unwrap_err().error, ProgramError::InvalidAccountData);
+    assert_eq!(bank.token_balance(&from), 500);
+}
@@ -0,0 +1,582 @@
+//! Token-2022 mode: TransferFee + TransferHook mint created by
+//! `initialize_token`, pepball-hook rules on plain transfers, withheld fees
+//! collected and split by `withdraw_withheld_fees`.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account;
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_lang::solana_program::rent::Rent;
+use anchor_spl::token_2022::spl_token_2022::extension::metadata_pointer::MetadataPointer;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_hook::TransferHook;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_interface::spl_token_metadata_interface::state::TokenMetadata;
+use pepball_hook::HookError;
+use pepball_token::{ErrorCode as TokenError, TaxDirection, TokenInfo, VestingCategory};
+use pepeball_sdk::programs::pepball_token::instruction as token_ix;
+use pepeball_sdk::{accounts, harvest, hook, pda, token};
+use pepeball_tests::{assert_error, Bank};
+use tax_harvest::HarvestError;
+
+const DECIMALS: u8 = 6;
+
+struct FeeToken {
+    info: Pubkey,
+    mint: Pubkey,
+    admin: Pubkey,
+    creator: Pubkey,
+}
+
+fn fee_token(bank: &mut Bank) -> FeeToken {
+    let admin = bank.wallet();
+    let creator = bank.wallet();
+    let mint = Pubkey::new_unique();
+    bank.send(
+        token::initialize_token_2022(&mint, &admin, &admin, token::launch_metadata(), DECIMALS, creator),
+        &[mint, admin],
+    )
+    .unwrap();
+    let info = pda::token_info(&mint).0;
+    bank.send(hook::initialize_extra_account_meta_list(&admin, &mint, &info), &[admin])
+        .unwrap();
+    FeeToken {
+        info,
+        mint,
+        admin,
+        creator,
+    }
+}
+
+/// Token-2022 ATA of `owner`, created through the associated token program.
+fn ata(bank: &mut Bank, t: &FeeToken, owner: &Pubkey) -> Pubkey {
+    let payer = t.admin;
+    bank.send(create_associated_token_account(&payer, owner, &t.mint, &spl_token_2022::ID), &[payer])
+        .unwrap();
+    token::token_2022_ata(owner, &t.mint)
+}
+
+fn mint_withheld(bank: &Bank, mint: &Pubkey) -> u64 {
+    let account = bank.account(mint).unwrap();
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&account.data).unwrap();
+    state.get_extension::<TransferFeeConfig>().unwrap().withheld_amount.into()
+}
+
+fn withheld(bank: &Bank, key: &Pubkey) -> u64 {
+    accounts::withheld_fee(&bank.account(key).unwrap().data).unwrap()
+}
+
+/// Wallet holding at least `amount` in its ATA, funded from the distribution
+/// account (the admin's ATA holds the whole supply).
+fn holder(bank: &mut Bank, t: &FeeToken, amount: u64) -> (Pubkey, Pubkey) {
+    let holder = bank.wallet();
+    let from = ata(bank, t, &holder);
+    let distribution = token::token_2022_ata(&t.admin, &t.mint);
+    bank.send(transfer_ix(t, &distribution, &t.admin, &holder, amount * 2), &[t.admin])
+        .unwrap();
+    (holder, from)
+}
+
+/// Wallet-style `transfer_checked` to `recipient`'s ATA with the hook
+/// accounts appended.
+fn transfer_ix(t: &FeeToken, from: &Pubkey, owner: &Pubkey, recipient: &Pubkey, amount: u64) -> Instruction {
+    let to = token::token_2022_ata(recipient, &t.mint);
+    let mut ix =
+        spl_token_2022::instruction::transfer_checked(&spl_token_2022::ID, from, &t.mint, &to, owner, &[], amount, DECIMALS)
+            .unwrap();
+    ix.accounts.extend(hook::transfer_accounts(&t.mint, &t.info, owner, recipient));
+    ix
+}
+
+/// Funds a holder and sends `amount` to a new wallet with a plain Token-2022 transfer.
+fn plain_transfer(bank: &mut Bank, t: &FeeToken, amount: u64) -> (Pubkey, Pubkey) {
+    let (holder, from) = holder(bank, t, amount);
+    let recipient = bank.wallet();
+    let to = ata(bank, t, &recipient);
+    let fee = amount * pepball_token::TAX_RATE_BPS as u64 / 10_000;
+    bank.send(transfer_ix(t, &from, &holder, &recipient, amount), &[holder]).unwrap();
+    assert_eq!(bank.token_balance(&to), amount - fee);
+    (to, recipient)
+}
+
+#[test]
+fn initialize_creates_the_transfer_fee_mint() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+
+    let info: TokenInfo = bank.anchor_account(&t.info);
+    assert!(info.transfer_fee_mode);
+    assert_eq!(info.mint, t.mint);
+
+    let account = bank.account(&t.mint).unwrap();
+    assert_eq!(account.owner, spl_token_2022::ID);
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&account.data).unwrap();
+    assert_eq!(state.base.decimals, DECIMALS);
+    assert!(state.base.mint_authority.is_none());
+    assert_eq!(state.base.supply, info.total_supply);
+    let config = state.get_extension::<TransferFeeConfig>().unwrap();
+    let fee_authority = pda::fee_authority(&t.mint).0;
+    assert_eq!(Option::<Pubkey>::from(config.withdraw_withheld_authority), Some(fee_authority));
+    assert_eq!(Option::<Pubkey>::from(config.transfer_fee_config_authority), Some(fee_authority));
+    assert_eq!(u16::from(config.newer_transfer_fee.transfer_fee_basis_points), pepball_token::TAX_RATE_BPS);
+    let hook = state.get_extension::<TransferHook>().unwrap();
+    assert_eq!(Option::<Pubkey>::from(hook.program_id), Some(pepball_hook::ID));
+}
+
+#[test]
+fn hook_blocks_plain_transfers_while_paused() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let (owner, from) = holder(&mut bank, &t, 1_000_000);
+    let recipient = bank.wallet();
+    let to = ata(&mut bank, &t, &recipient);
+
+    bank.send(token::emergency_pause(&t.info, &t.admin), &[t.admin]).unwrap();
+    assert_error(bank.send(transfer_ix(&t, &from, &owner, &recipient, 10_000), &[owner]), HookError::TransfersPaused);
+
+    bank.send(token::emergency_pause(&t.info, &t.admin), &[t.admin]).unwrap();
+    bank.send(transfer_ix(&t, &from, &owner, &recipient, 10_000), &[owner]).unwrap();
+    assert_eq!(bank.token_balance(&to), 9_750);
+}
+
+#[test]
+fn hook_enforces_the_minimum_transfer() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let (owner, from) = holder(&mut bank, &t, 1_000_000);
+    let recipient = bank.wallet();
+    ata(&mut bank, &t, &recipient);
+
+    let min = pepball_token::MIN_TRANSFER_AMOUNT;
+    assert_error(bank.send(transfer_ix(&t, &from, &owner, &recipient, min - 1), &[owner]), HookError::AmountTooSmall);
+    bank.send(transfer_ix(&t, &from, &owner, &recipient, min), &[owner]).unwrap();
+}
+
+#[test]
+fn hook_lets_exempt_owners_skip_the_minimum_but_not_the_pause() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let (owner, from) = holder(&mut bank, &t, 1_000_000);
+    let pool = bank.wallet();
+    let to = ata(&mut bank, &t, &pool);
+
+    bank.send(token::add_exemption(&t.info, &t.admin, pool, false, true), &[t.admin]).unwrap();
+    bank.send(transfer_ix(&t, &from, &owner, &pool, 10), &[owner]).unwrap();
+    // Token-2022 still withholds its fee (rounded up); the exemption only
+    // covers the hook rules.
+    assert_eq!(bank.token_balance(&to), 9);
+
+    bank.send(token::emergency_pause(&t.info, &t.admin), &[t.admin]).unwrap();
+    assert_error(bank.send(transfer_ix(&t, &from, &owner, &pool, 10_000), &[owner]), HookError::TransfersPaused);
+}
+
+#[test]
+fn hook_enforces_launch_protection() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let (owner, from) = holder(&mut bank, &t, 10_000_000);
+    let recipient = bank.wallet();
+    ata(&mut bank, &t, &recipient);
+    let args = pepball_token::instruction::ConfigureLaunchProtection {
+        start_slot: 10,
+        window_slots: 100,
+        max_tx_amount: 1_000_000,
+        max_wallet_balance: 1_500_000,
+        cooldown_slots: 5,
+    };
+    bank.send(token::configure_launch_protection(&t.info, &t.admin, args), &[t.admin]).unwrap();
+
+    assert_error(bank.send(transfer_ix(&t, &from, &owner, &recipient, 10_000), &[owner]), HookError::LaunchNotStarted);
+    bank.warp(10, 0);
+    assert_error(
+        bank.send(transfer_ix(&t, &from, &owner, &recipient, 1_000_001), &[owner]),
+        HookError::MaxTransactionExceeded,
+    );
+    bank.send(transfer_ix(&t, &from, &owner, &recipient, 1_000_000), &[owner]).unwrap();
+    assert_error(bank.send(transfer_ix(&t, &from, &owner, &recipient, 10_000), &[owner]), HookError::WalletCooldown);
+    bank.warp(15, 0);
+    assert_error(
+        bank.send(transfer_ix(&t, &from, &owner, &recipient, 600_000), &[owner]),
+        HookError::MaxWalletExceeded,
+    );
+    bank.warp(110, 0);
+    bank.send(transfer_ix(&t, &from, &owner, &recipient, 2_000_000), &[owner]).unwrap();
+}
+
+#[test]
+fn hook_cooldown_table_fails_closed() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let (owner, from) = holder(&mut bank, &t, 10_000_000);
+    let args = pepball_token::instruction::ConfigureLaunchProtection {
+        start_slot: 10,
+        window_slots: 100,
+        max_tx_amount: 0,
+        max_wallet_balance: 0,
+        cooldown_slots: 5,
+    };
+    bank.send(token::configure_launch_protection(&t.info, &t.admin, args), &[t.admin]).unwrap();
+    bank.warp(10, 0);
+    let send_to_new_wallet = |bank: &mut Bank| {
+        let recipient = bank.wallet();
+        ata(bank, &t, &recipient);
+        bank.send(transfer_ix(&t, &from, &owner, &recipient, 10_000), &[owner])
+    };
+    for _ in 0..pepball_token::MAX_COOLDOWN_WALLETS {
+        send_to_new_wallet(&mut bank).unwrap();
+    }
+    assert_error(send_to_new_wallet(&mut bank), HookError::CooldownTableFull);
+    bank.warp(15, 0);
+    send_to_new_wallet(&mut bank).unwrap();
+}
+
+#[test]
+fn transfers_without_the_hook_accounts_fail() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let (owner, from) = holder(&mut bank, &t, 1_000_000);
+    let recipient = bank.wallet();
+    let to = ata(&mut bank, &t, &recipient);
+
+    let mut ix = transfer_ix(&t, &from, &owner, &recipient, 10_000);
+    ix.accounts.truncate(4);
+    assert!(bank.send(ix, &[owner]).is_err());
+    assert_eq!(bank.token_balance(&to), 0);
+}
+
+#[test]
+fn hook_cannot_be_called_directly() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let (owner, from) = holder(&mut bank, &t, 1_000_000);
+    let recipient = bank.wallet();
+    let to = ata(&mut bank, &t, &recipient);
+
+    let ix = Instruction {
+        program_id: pepball_hook::ID,
+        accounts: pepball_hook::accounts::TransferHook {
+            source_token: from,
+            mint: t.mint,
+            destination_token: to,
+            owner,
+            extra_account_meta_list: pda::extra_account_metas(&t.mint).0,
+            token_info: t.info,
+            exemption_list: pda::exemption_list(&t.info).0,
+            amm_pools: pda::amm_pools(&t.info).0,
+            launch_guard: pda::hook_launch_guard(&t.mint).0,
+            source_checkpoints: pda::hook_checkpoints(&t.mint, &owner).0,
+            destination_checkpoints: pda::hook_checkpoints(&t.mint, &recipient).0,
+            source_owner: owner,
+        }
+        .to_account_metas(None),
+        data: pepball_hook::instruction::TransferHook { amount: 10_000 }.data(),
+    };
+    assert_error(bank.send(ix, &[owner]), HookError::NotTransferring);
+}
+
+/// The hook has no payer, so it only updates checkpoints `checkpoint_balance`
+/// created; wallets without them transfer as before.
+#[test]
+fn hook_updates_created_checkpoints() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let (owner, from) = holder(&mut bank, &t, 1_000_000);
+    let recipient = bank.wallet();
+    let to = ata(&mut bank, &t, &recipient);
+    let payer = bank.wallet();
+    let funded = bank.token_balance(&from);
+
+    assert_error(
+        bank.send(hook::checkpoint_balance(&t.mint, &recipient, &from, &payer), &[payer]),
+        HookError::InvalidCheckpointAccount,
+    );
+    bank.send(hook::checkpoint_balance(&t.mint, &owner, &from, &payer), &[payer]).unwrap();
+    let checkpoints = |bank: &Bank, wallet: &Pubkey| {
+        let account = bank.account(&pda::hook_checkpoints(&t.mint, wallet).0)?;
+        Some(accounts::decode_hook_checkpoints(&account.data).unwrap().history)
+    };
+    assert_eq!(checkpoints(&bank, &owner).unwrap().balance_at(1).unwrap(), funded);
+
+    bank.warp(pepball_token::CHECKPOINT_INTERVAL_SLOTS + 1, 0);
+    bank.send(transfer_ix(&t, &from, &owner, &recipient, 400_000), &[owner]).unwrap();
+    let history = checkpoints(&bank, &owner).unwrap();
+    assert_eq!(history.checkpoints.len(), 2);
+    assert_eq!(history.balance_at(pepball_token::CHECKPOINT_INTERVAL_SLOTS + 1).unwrap(), funded - 400_000);
+    assert_eq!(history.balance_at(1).unwrap(), funded);
+    assert!(checkpoints(&bank, &recipient).is_none());
+    assert_eq!(bank.token_balance(&to), 390_000);
+}
+
+/// Plain transfers of a legacy mint never reach the hook, so its
+/// checkpoints would go stale: the hook only keeps them for its own mints.
+#[test]
+fn hook_checkpoints_need_a_hooked_mint() {
+    let mut bank = Bank::new();
+    let owner = bank.wallet();
+    let payer = bank.wallet();
+    let legacy_mint = bank.create_mint(&owner, DECIMALS);
+    let from = bank.create_ata(&legacy_mint, &owner, 1_000_000);
+    assert_error(
+        bank.send(hook::checkpoint_balance(&legacy_mint, &owner, &from, &payer), &[payer]),
+        HookError::MintNotHooked,
+    );
+}
+
+#[test]
+fn withheld_fees_are_split_between_jackpot_and_creator() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let (source, _) = plain_transfer(&mut bank, &t, 1_000_000);
+    assert_eq!(withheld(&bank, &source), 25_000);
+
+    let jackpot = ata(&mut bank, &t, &pepball_token::jackpot_vault_authority());
+    let creator_fund = ata(&mut bank, &t, &t.creator);
+    let cranker = bank.wallet();
+    bank.send(
+        token::withdraw_withheld_fees(&t.info, &t.mint, &jackpot, &pepball_token::jackpot_vault_authority(), &creator_fund, &t.creator, &cranker, &[source]),
+        &[cranker],
+    )
+    .unwrap();
+
+    // 25_000 withheld -> 24_500 jackpot / 500 creator; each payout is itself
+    // taxed and that fee stays withheld on the destination for the next run.
+    // The 500 payout is below the minimum: the hook exempts the fee authority.
+    assert_eq!(withheld(&bank, &source), 0);
+    assert_eq!(mint_withheld(&bank, &t.mint), 0);
+    assert_eq!(bank.token_balance(&jackpot) + withheld(&bank, &jackpot), 24_500);
+    assert_eq!(bank.token_balance(&creator_fund) + withheld(&bank, &creator_fund), 500);
+    assert_eq!(bank.token_balance(&token::fee_vault(&t.mint)), 0);
+    // Stats count what landed, not the fee re-withheld on the payouts.
+    let stats = bank.anchor_account::<TokenInfo>(&t.info).stats;
+    let (jackpot_paid, creator_paid) = (bank.token_balance(&jackpot), bank.token_balance(&creator_fund));
+    assert!(jackpot_paid < 24_500 && creator_paid < 500);
+    assert_eq!((stats.total_creator_tax, stats.total_jackpot_tax), (creator_paid, jackpot_paid));
+    assert_eq!(stats.transfer_count, 0);
+
+    // Second run picks up the fees withheld on the payouts.
+    bank.send(
+        token::withdraw_withheld_fees(
+            &t.info,
+            &t.mint,
+            &jackpot,
+            &pepball_token::jackpot_vault_authority(),
+            &creator_fund,
+            &t.creator,
+            &cranker,
+            &[jackpot, creator_fund],
+        ),
+        &[cranker],
+    )
+    .unwrap();
+    assert!(withheld(&bank, &jackpot) + withheld(&bank, &creator_fund) < 100);
+    let stats = bank.anchor_account::<TokenInfo>(&t.info).stats;
+    assert_eq!(
+        (stats.total_creator_tax, stats.total_jackpot_tax),
+        (bank.token_balance(&creator_fund), bank.token_balance(&jackpot))
+    );
+}
+
+#[test]
+fn withdraw_rejects_foreign_destinations() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let (source, recipient) = plain_transfer(&mut bank, &t, 1_000_000);
+    let jackpot = ata(&mut bank, &t, &pepball_token::jackpot_vault_authority());
+    let creator_fund = ata(&mut bank, &t, &t.creator);
+    let own = token::token_2022_ata(&recipient, &t.mint);
+    let cranker = bank.wallet();
+
+    let jackpot_owner = pepball_token::jackpot_vault_authority();
+
+    let ix = token::withdraw_withheld_fees(&t.info, &t.mint, &own, &recipient, &creator_fund, &t.creator, &cranker, &[source]);
+    assert_error(bank.send(ix, &[cranker]), TokenError::InvalidTaxDestination);
+    let ix = token::withdraw_withheld_fees(&t.info, &t.mint, &jackpot, &jackpot_owner, &own, &recipient, &cranker, &[source]);
+    assert_error(bank.send(ix, &[cranker]), TokenError::InvalidTaxDestination);
+}
+
+#[test]
+fn withdraw_needs_withheld_fees() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let jackpot = ata(&mut bank, &t, &pepball_token::jackpot_vault_authority());
+    let creator_fund = ata(&mut bank, &t, &t.creator);
+    let cranker = bank.wallet();
+    assert_error(
+        bank.send(
+            token::withdraw_withheld_fees(&t.info, &t.mint, &jackpot, &pepball_token::jackpot_vault_authority(), &creator_fund, &t.creator, &cranker, &[]),
+            &[cranker],
+        ),
+        TokenError::NoWithheldFees,
+    );
+}
+
+/// The jackpot share lands in tax-harvest's Token-2022 vault, which
+/// `process_harvest` cannot sell from.
+#[test]
+fn harvest_rejects_the_token_2022_jackpot_vault() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let (source, _) = plain_transfer(&mut bank, &t, 1_000_000);
+    let jackpot_owner = pepball_token::jackpot_vault_authority();
+    let jackpot = ata(&mut bank, &t, &jackpot_owner);
+    let creator_fund = ata(&mut bank, &t, &t.creator);
+    let cranker = bank.wallet();
+    let ix = token::withdraw_withheld_fees(&t.info, &t.mint, &jackpot, &jackpot_owner, &creator_fund, &t.creator, &cranker, &[source]);
+    bank.send(ix, &[cranker]).unwrap();
+    assert!(bank.token_balance(&jackpot) > 0);
+
+    let authority = bank.wallet();
+    let dest = bank.wallet();
+    bank.send(harvest::initialize(&authority, dest), &[authority]).unwrap();
+    // Any pool will do: the vault is rejected before the swap.
+    let other = bank.create_mint(&authority, DECIMALS);
+    let route = bank.create_whirlpool(&other, &native_mint::ID, 1 << 64, 1_000_000).route(&other);
+    let ix = harvest::process_harvest(&authority, &jackpot, &t.mint, &dest, 1, 0, &route);
+    assert_error(bank.send(ix, &[authority]), HarvestError::Token2022NotSupported);
+}
+
+/// Token-2022 charges one fee whatever the direction, so buy and sell rates
+/// would never be applied.
+#[test]
+fn tax_rates_are_fixed_by_the_mint() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    for direction in [TaxDirection::Transfer, TaxDirection::Buy, TaxDirection::Sell] {
+        assert_error(
+            bank.send(token::set_tax_rates(&t.info, &t.admin, direction, 5, 495, 0), &[t.admin]),
+            TokenError::TransferRateFixedByMint,
+        );
+    }
+}
+
+#[test]
+fn quote_transfer_reports_the_token_2022_fee() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let (owner, from) = holder(&mut bank, &t, 1_000_000);
+    let recipient = bank.wallet();
+    let to = ata(&mut bank, &t, &recipient);
+
+    let logs = bank.send(token::quote_transfer(&t.info, &t.mint, &from, &to, 10_001), &[]).unwrap();
+    let q = pepeball_sdk::decode::transfer_quote(&logs).unwrap();
+    // Token-2022 rounds its fee up: 250.025 -> 251, split 5/245 by ratio.
+    assert_eq!((q.net_amount, q.creator_tax, q.jackpot_tax), (9_750, 5, 246));
+    bank.send(transfer_ix(&t, &from, &owner, &recipient, 10_001), &[owner]).unwrap();
+    assert_eq!(bank.token_balance(&to), q.net_amount);
+    assert_eq!(withheld(&bank, &to), q.creator_tax + q.jackpot_tax);
+}
+
+fn mint_metadata(bank: &Bank, mint: &Pubkey) -> TokenMetadata {
+    let account = bank.account(mint).unwrap();
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&account.data).unwrap();
+    let pointer = state.get_extension::<MetadataPointer>().unwrap();
+    assert_eq!(Option::<Pubkey>::from(pointer.metadata_address), Some(*mint));
+    state.get_variable_len_extension::<TokenMetadata>().unwrap()
+}
+
+#[test]
+fn mint_carries_token_metadata_until_renounce() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let launch = token::launch_metadata();
+    assert_eq!((launch.name.as_str(), launch.symbol.as_str()), ("PEPEBALL", "PEPE"));
+
+    let metadata = mint_metadata(&bank, &t.mint);
+    assert_eq!((metadata.name, metadata.symbol, metadata.uri), (launch.name, launch.symbol, launch.uri));
+    assert_eq!(Option::<Pubkey>::from(metadata.update_authority), Some(t.info));
+
+    // A longer URI grows the mint; the admin covers the extra rent.
+    let uri = format!("{}?v=2", token::METADATA_URI);
+    let ix = token::update_metadata_uri(&t.info, &t.admin, &t.mint, &spl_token_2022::ID, uri.clone());
+    bank.send(ix, &[t.admin]).unwrap();
+    assert_eq!(mint_metadata(&bank, &t.mint).uri, uri);
+    assert_eq!(bank.anchor_account::<TokenInfo>(&t.info).uri, uri);
+    let mint = bank.account(&t.mint).unwrap();
+    assert!(mint.lamports >= Rent::default().minimum_balance(mint.data.len()));
+
+    bank.send(token::renounce_admin(&t.info, &t.admin), &[t.admin]).unwrap();
+    let ix = token::update_metadata_uri(&t.info, &t.admin, &t.mint, &spl_token_2022::ID, "ipfs://x".into());
+    assert_error(bank.send(ix, &[t.admin]), TokenError::AlreadyRenounced);
+}
+
+#[test]
+fn vesting_escrow_pays_the_transfer_fee_both_ways() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let program = spl_token_2022::ID;
+    let supply = token::token_2022_ata(&t.admin, &t.mint);
+    let marketing = bank.wallet();
+    let payout = ata(&mut bank, &t, &marketing);
+    let now = bank.clock().unix_timestamp;
+    let args = token_ix::CreateVesting {
+        category: VestingCategory::Marketing,
+        amount: 1_000_000,
+        start_ts: now,
+        cliff_ts: now,
+        end_ts: now + 100,
+        revocable: false,
+    };
+    bank.send(token::create_vesting(&t.mint, &program, &t.admin, &t.admin, &supply, &marketing, args), &[t.admin])
+        .unwrap();
+    let schedule = accounts::decode_vesting(&bank.account(&pda::vesting(&t.info, &marketing).0).unwrap().data).unwrap();
+    assert_eq!(schedule.total_amount, 975_000);
+
+    bank.warp(10, now + 100);
+    bank.send(token::claim_vested(&t.mint, &program, &marketing, &payout, &marketing), &[marketing]).unwrap();
+    assert_eq!(bank.token_balance(&payout), 975_000 - 24_375);
+    assert_eq!(bank.token_balance(&token::vesting_escrow(&t.mint, &program, &marketing)), 0);
+}
+
+/// The hook's minimum does not apply to vesting escrows, so a tail below it
+/// can still be claimed.
+#[test]
+fn vesting_tail_below_the_minimum_can_be_claimed() {
+    let mut bank = Bank::new();
+    let t = fee_token(&mut bank);
+    let program = spl_token_2022::ID;
+    let supply = token::token_2022_ata(&t.admin, &t.mint);
+    let team = bank.wallet();
+    let payout = ata(&mut bank, &t, &team);
+    let now = bank.clock().unix_timestamp;
+    let args = token_ix::CreateVesting {
+        category: VestingCategory::Team,
+        amount: 1_000_000,
+        start_ts: now,
+        cliff_ts: now,
+        end_ts: now + 10_000,
+        revocable: false,
+    };
+    bank.send(token::create_vesting(&t.mint, &program, &t.admin, &t.admin, &supply, &team, args), &[t.admin])
+        .unwrap();
+    let escrow = token::vesting_escrow(&t.mint, &program, &team);
+
+    bank.warp(10, now + 9_999);
+    bank.send(token::claim_vested(&t.mint, &program, &team, &payout, &team), &[team]).unwrap();
+    let tail = bank.token_balance(&escrow);
+    assert!(tail > 0 && tail < pepball_token::MIN_TRANSFER_AMOUNT);
+
+    bank.warp(20, now + 10_000);
+    bank.send(token::claim_vested(&t.mint, &program, &team, &payout, &team), &[team]).unwrap();
+    assert_eq!(bank.token_balance(&escrow), 0);
+}
+
+#[test]
+fn legacy_token_cannot_withdraw_fees() {
+    let mut bank = Bank::new();
+    let admin = bank.wallet();
+    let legacy_mint = Pubkey::new_unique();
+    bank.send(
+        token::initialize_token(&legacy_mint, &admin, &admin, token::launch_metadata(), DECIMALS, admin),
+        &[legacy_mint, admin],
+    )
+    .unwrap();
+    let info = pda::token_info(&legacy_mint).0;
+    let t = fee_token(&mut bank);
+    let jackpot = ata(&mut bank, &t, &pepball_token::jackpot_vault_authority());
+    let creator_fund = ata(&mut bank, &t, &t.creator);
+    let jackpot_owner = pepball_token::jackpot_vault_authority();
+    let ix = token::withdraw_withheld_fees(&info, &t.mint, &jackpot, &jackpot_owner, &creator_fund, &t.creator, &admin, &[]);
+    assert!(bank.send(ix, &[admin]).is_err());
+}
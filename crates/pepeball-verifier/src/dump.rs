@@ -0,0 +1,79 @@
+//! Load accounts from `solana account --output json` dumps, `solana-test-validator`
+//! account files, or a saved `getProgramAccounts` response.
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+use std::path::Path;
+use std::str::FromStr;
+
+pub struct DumpedAccount {
+    pub pubkey: Pubkey,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct RawEntry {
+    pubkey: String,
+    account: RawAccount,
+}
+
+#[derive(Deserialize)]
+struct RawAccount {
+    owner: String,
+    /// `["<base64>", "base64"]`
+    data: (String, String),
+}
+
+fn parse_entry(value: Value) -> Result<DumpedAccount> {
+    let raw: RawEntry = serde_json::from_value(value).context("not an account dump entry")?;
+    if raw.account.data.1 != "base64" {
+        bail!("{}: unsupported data encoding {}", raw.pubkey, raw.account.data.1);
+    }
+    Ok(DumpedAccount {
+        pubkey: Pubkey::from_str(&raw.pubkey).map_err(|e| anyhow!("{}: {e}", raw.pubkey))?,
+        owner: Pubkey::from_str(&raw.account.owner).map_err(|e| anyhow!("{}: {e}", raw.account.owner))?,
+        data: base64::engine::general_purpose::STANDARD
+            .decode(&raw.account.data.0)
+            .with_context(|| format!("{}: bad base64", raw.pubkey))?,
+    })
+}
+
+fn load_file(path: &Path, out: &mut Vec<DumpedAccount>) -> Result<()> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let value: Value = serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+    let value = match value {
+        Value::Object(mut obj) if obj.contains_key("result") => obj.remove("result").unwrap_or_default(),
+        v => v,
+    };
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                out.push(parse_entry(item).with_context(|| path.display().to_string())?);
+            }
+        }
+        v => out.push(parse_entry(v).with_context(|| path.display().to_string())?),
+    }
+    Ok(())
+}
+
+/// Every `*.json` in `path` (or `path` itself if it is a file).
+pub fn load(path: &Path) -> Result<Vec<DumpedAccount>> {
+    let mut out = Vec::new();
+    if path.is_dir() {
+        let mut files: Vec<_> = std::fs::read_dir(path)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+        for file in files {
+            load_file(&file, &mut out)?;
+        }
+    } else {
+        load_file(path, &mut out)?;
+    }
+    Ok(out)
+}
@@ -0,0 +1,228 @@
+//! `pepeball-verify` — replay a PEPEBALL draw from account dumps.
+//!
+//! Loads the lottery account and every participant account of the round, then
+//! recomputes the ball count, ODD/EVEN outcome and winners with `pepeball-draw`
+//! (the same code `take_snapshot`/`set_winners` run on chain). Any mismatch is
+//! reported and the process exits non-zero.
+//!
+//! Dump accounts with `solana account <addr> --output json` (or save a
+//! `getProgramAccounts` response) *before* `payout_winners` resets the round.
+
+mod dump;
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Parser;
+use pepeball_draw::Parity;
+use pepeball_sdk::accounts::{self, Lottery, ParticipantAccount};
+use pepeball_sdk::anchor_lang::Discriminator;
+use pepeball_sdk::{pda, program_ids};
+use serde::Deserialize;
+use solana_sdk::hash::hashv;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Signer};
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+
+#[derive(Parser)]
+#[command(name = "pepeball-verify", version, about = "Replay and verify a PEPEBALL draw")]
+struct Cli {
+    /// Account dump file or directory of `*.json` dumps
+    #[arg(long)]
+    accounts: PathBuf,
+    /// Published result to check (JSON: seed, pepe_ball_count, main_winner, minor_winners).
+    /// Defaults to the winners stored in the lottery account.
+    #[arg(long)]
+    claim: Option<PathBuf>,
+    /// Slot of the take_snapshot transaction, to re-derive the seed
+    #[arg(long, requires = "unix_timestamp")]
+    slot: Option<u64>,
+    /// Block time of the take_snapshot transaction
+    #[arg(long, requires = "slot")]
+    unix_timestamp: Option<i64>,
+    /// Keypair that signs the report
+    #[arg(long)]
+    sign_keypair: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct Claim {
+    seed: Option<u64>,
+    pepe_ball_count: Option<u8>,
+    main_winner: Option<String>,
+    #[serde(default)]
+    minor_winners: Vec<String>,
+}
+
+struct Check {
+    name: String,
+    passed: bool,
+}
+
+fn parse_pubkey(s: &str) -> Result<Pubkey> {
+    Pubkey::from_str(s).map_err(|e| anyhow!("bad pubkey {s}: {e}"))
+}
+
+fn main() {
+    match run() {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(2),
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run() -> Result<bool> {
+    let cli = Cli::parse();
+    let dumped = dump::load(&cli.accounts)?;
+    let lottery_key = pda::lottery().0;
+
+    let mut lottery: Option<Lottery> = None;
+    let mut participants: Vec<ParticipantAccount> = Vec::new();
+    for acc in dumped.iter().filter(|a| a.owner == program_ids::LOTTERY) {
+        if acc.data.starts_with(&Lottery::DISCRIMINATOR) && acc.pubkey == lottery_key {
+            lottery = Some(accounts::decode_lottery(&acc.data)?);
+        } else if acc.data.starts_with(&ParticipantAccount::DISCRIMINATOR) {
+            let p = accounts::decode_participant(&acc.data)?;
+            if p.lottery == lottery_key {
+                participants.push(p);
+            }
+        }
+    }
+    let lottery = lottery.with_context(|| format!("lottery account {lottery_key} not in dump"))?;
+    participants.sort_by_key(|p| p.wallet);
+    participants.dedup_by_key(|p| p.wallet);
+
+    let claim = match &cli.claim {
+        Some(path) => Some(
+            serde_json::from_str::<Claim>(
+                &std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?,
+            )
+            .with_context(|| format!("parsing {}", path.display()))?,
+        ),
+        None => None,
+    };
+
+    let mut checks: Vec<Check> = Vec::new();
+    let mut check = |name: String, passed: bool| checks.push(Check { name, passed });
+
+    // Seed: chain value, claimed value, and optionally re-derived from the snapshot block
+    let claimed_seed = claim.as_ref().and_then(|c| c.seed);
+    let seed = match (lottery.snapshot_seed, claimed_seed) {
+        (0, Some(s)) => s,
+        (0, None) => bail!("lottery has no payout seed and no --claim seed was given"),
+        (chain, Some(s)) => {
+            check(format!("claimed seed {s} matches lottery account"), chain == s);
+            chain
+        }
+        (chain, None) => chain,
+    };
+    let mut seed_source = "lottery account".to_string();
+    if let (Some(slot), Some(ts)) = (cli.slot, cli.unix_timestamp) {
+        let snapshots_before = lottery.total_snapshots.saturating_sub(1);
+        let derived = pepeball_draw::derive_seed(slot, ts, lottery.total_participants, snapshots_before);
+        check(format!("seed re-derived from slot {slot} / time {ts}"), derived == seed);
+        seed_source = format!("re-derived from slot {slot} / time {ts}");
+    }
+
+    let balls = pepeball_draw::ball_count(seed);
+    let parity = pepeball_draw::parity(balls);
+    if lottery.pepe_ball_count != 0 {
+        check(
+            format!("ball count {} in lottery account", lottery.pepe_ball_count),
+            lottery.pepe_ball_count == balls,
+        );
+    }
+    if let Some(claimed) = claim.as_ref().and_then(|c| c.pepe_ball_count) {
+        check(format!("claimed ball count {claimed}"), claimed == balls);
+    }
+    check("ODD draw (payout round)".to_string(), parity == Parity::Odd);
+
+    let ticket_counts: Vec<u32> = participants.iter().map(|p| p.ticket_count).collect();
+    let total_tickets: u64 = ticket_counts.iter().map(|c| *c as u64).sum();
+    check(
+        format!("{} participant accounts = total_participants {}", participants.len(), lottery.total_participants),
+        participants.len() as u64 == lottery.total_participants,
+    );
+    check(
+        format!("{total_tickets} tickets = total_tickets {}", lottery.total_tickets),
+        total_tickets == lottery.total_tickets,
+    );
+
+    let selection = pepeball_draw::select_winners(seed, &ticket_counts).context("round has no tickets")?;
+    let main_winner = participants[selection.main].wallet;
+    let minor_winners: Vec<Pubkey> = selection.minors.iter().map(|i| participants[*i].wallet).collect();
+
+    let (claimed_main, claimed_minors) = match &claim {
+        Some(c) => (
+            c.main_winner.as_deref().map(parse_pubkey).transpose()?,
+            c.minor_winners.iter().map(|s| parse_pubkey(s)).collect::<Result<Vec<_>>>()?,
+        ),
+        None => (lottery.winners.main_winner, lottery.winners.minor_winners.clone()),
+    };
+    match claimed_main {
+        Some(w) => check(format!("main winner {w}"), w == main_winner),
+        None => check("main winner published".to_string(), false),
+    }
+    check(
+        format!("{} minor winners in published order", claimed_minors.len()),
+        claimed_minors == minor_winners,
+    );
+
+    // Fingerprint of the exact ticket set the draw ran over
+    let set_bytes: Vec<[u8; 36]> = participants
+        .iter()
+        .map(|p| {
+            let mut b = [0u8; 36];
+            b[..32].copy_from_slice(p.wallet.as_ref());
+            b[32..].copy_from_slice(&p.ticket_count.to_le_bytes());
+            b
+        })
+        .collect();
+    let set_hash = hashv(&set_bytes.iter().map(|b| b.as_slice()).collect::<Vec<_>>());
+
+    let verified = checks.iter().all(|c| c.passed);
+    let mut body = String::new();
+    writeln!(body, "PEPEBALL DRAW PROOF")?;
+    writeln!(body, "===================")?;
+    writeln!(body, "lottery:         {lottery_key}")?;
+    writeln!(body, "seed:            {seed} ({seed_source})")?;
+    writeln!(
+        body,
+        "pepe balls:      {balls} ({})",
+        if parity == Parity::Odd { "ODD - payout" } else { "EVEN - rollover" }
+    )?;
+    writeln!(body, "participants:    {} ({total_tickets} tickets)", participants.len())?;
+    writeln!(body, "ticket set:      sha256 {set_hash}")?;
+    if let Some(idx) = pepeball_draw::main_ticket_index(seed, total_tickets) {
+        writeln!(body, "main winner:     {main_winner} (ticket #{idx})")?;
+    }
+    writeln!(body, "minor winners:")?;
+    for (i, w) in minor_winners.iter().enumerate() {
+        writeln!(body, "  {}. {w}", i + 1)?;
+    }
+    writeln!(body, "checks:")?;
+    for c in &checks {
+        writeln!(body, "  [{}] {}", if c.passed { "PASS" } else { "FAIL" }, c.name)?;
+    }
+    writeln!(body, "result:          {}", if verified { "VERIFIED" } else { "MISMATCH" })?;
+
+    print!("{body}");
+    println!("-----");
+    match &cli.sign_keypair {
+        Some(path) => {
+            let signer = read_keypair_file(path)
+                .map_err(|e| anyhow!("reading keypair {}: {e}", path.display()))?;
+            let sig = signer.sign_message(body.as_bytes());
+            println!("signer:          {}", signer.pubkey());
+            println!("signature:       {sig}");
+            println!("(ed25519 over every line above the dashes, including the trailing newline)");
+        }
+        None => println!("unsigned (pass --sign-keypair to sign)"),
+    }
+
+    Ok(verified)
+}
@@ -1,8 +1,18 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::clock::Clock;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use pepeball_draw::Parity;
 
 declare_id!("8xdCoGh7WrHrmpxMzqaXLfqJxYxU4mksQ3CBmztn13E7");
 
+/// Snapshot cadence set by `initialize_lottery` (changeable via `configure_timing`).
+pub const DEFAULT_BASE_SNAPSHOT_INTERVAL: u64 = 72 * 60 * 60;
+pub const DEFAULT_FAST_SNAPSHOT_INTERVAL: u64 = 48 * 60 * 60;
+/// Fees (lamports) at which draws switch to the fast interval.
+pub const DEFAULT_FAST_MODE_THRESHOLD: u64 = 200 * 1_000_000_000;
+
 #[program]
 pub mod lottery {
     use super::*;
@@ -26,9 +36,9 @@ pub mod lottery {
         lottery.tier3_min_cents = tier3_min_cents;
         lottery.carry_over_amount = 0;
         lottery.last_snapshot = Clock::get()?.unix_timestamp;
-        lottery.base_snapshot_interval = 72 * 60 * 60;
-        lottery.fast_snapshot_interval = 48 * 60 * 60;
-        lottery.fast_mode_threshold = 200 * 1_000_000_000;
+        lottery.base_snapshot_interval = DEFAULT_BASE_SNAPSHOT_INTERVAL;
+        lottery.fast_snapshot_interval = DEFAULT_FAST_SNAPSHOT_INTERVAL;
+        lottery.fast_mode_threshold = DEFAULT_FAST_MODE_THRESHOLD;
         lottery.fees_collected = 0;
         lottery.is_fast_mode = false;
         lottery.is_active = true;
@@ -67,6 +77,7 @@ pub mod lottery {
         
         let ticket_count = calculate_tickets_from_usd_value(usd_value, lottery.entry_min_cents, lottery.tier2_min_cents, lottery.tier3_min_cents);
         require!(ticket_count > 0, ErrorCode::InsufficientValue);
+        check_free_entry(lottery, &ctx.accounts.paid_entry, &ctx.accounts.admin, ticket_count)?;
         
         let participant_wallet = ctx.accounts.participant.key();
         let entry_time = Clock::get()?.unix_timestamp;
@@ -81,15 +92,23 @@ pub mod lottery {
         lottery.total_participants += 1;
         lottery.total_tickets += ticket_count as u64;
 
+        emit!(UsdEntryTiered {
+            wallet: participant_wallet,
+            usd_value,
+            ticket_count,
+        });
         Ok(())
     }
 
+    /// Free entry. Once paid entry is set up, free entries need the admin's
+    /// signature and stay within the per-wallet cap.
     pub fn enter_lottery(
         ctx: Context<EnterLottery>,
         ticket_count: u32,
     ) -> Result<()> {
         let lottery = &mut ctx.accounts.lottery;
         require!(lottery.is_active, ErrorCode::LotteryInactive);
+        check_free_entry(lottery, &ctx.accounts.paid_entry, &ctx.accounts.admin, ticket_count)?;
         
         let participant_wallet = ctx.accounts.participant.key();
         let entry_time = Clock::get()?.unix_timestamp;
@@ -107,6 +126,7 @@ pub mod lottery {
         Ok(())
     }
 
+    /// Adds free tickets; same paid-entry rules as `enter_lottery`.
     pub fn update_participant_tickets(
         ctx: Context<UpdateParticipant>,
         ticket_count: u32,
@@ -118,14 +138,194 @@ pub mod lottery {
             ctx.accounts.participant_account.lottery == lottery.key(),
             ErrorCode::Unauthorized
         );
-        
-        ctx.accounts.participant_account.ticket_count += ticket_count;
+        let new_count = ctx
+            .accounts
+            .participant_account
+            .ticket_count
+            .checked_add(ticket_count)
+            .ok_or(ErrorCode::MathOverflow)?;
+        check_free_entry(lottery, &ctx.accounts.paid_entry, &ctx.accounts.admin, new_count)?;
+
+        ctx.accounts.participant_account.ticket_count = new_count;
         ctx.accounts.participant_account.usd_value += usd_value;
         lottery.total_tickets += ticket_count as u64;
 
         Ok(())
     }
 
+    /// Admin: enable paid entries for this lottery. `payment_mint = None` takes SOL,
+    /// `Some(mint)` takes that SPL token (USDC). Split is in bps and must sum to 10000.
+    /// Admin seeds the SOL vault with its rent-exempt minimum so small entries can land.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_paid_entry(
+        ctx: Context<InitializePaidEntry>,
+        payment_mint: Option<Pubkey>,
+        ticket_price: u64,
+        max_tickets_per_wallet: u32,
+        prize_bps: u16,
+        reserve_bps: u16,
+        dev_bps: u16,
+        dev_wallet: Pubkey,
+    ) -> Result<()> {
+        require!(ctx.accounts.admin.key() == ctx.accounts.lottery.admin, ErrorCode::Unauthorized);
+        validate_paid_entry_config(ticket_price, max_tickets_per_wallet, prize_bps, reserve_bps, dev_bps)?;
+
+        let rent_min = Rent::get()?.minimum_balance(0);
+        let vault_balance = ctx.accounts.vault.lamports();
+        if vault_balance < rent_min {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                    },
+                ),
+                rent_min - vault_balance,
+            )?;
+        }
+
+        let paid_entry = &mut ctx.accounts.paid_entry;
+        paid_entry.lottery = ctx.accounts.lottery.key();
+        paid_entry.payment_mint = payment_mint;
+        paid_entry.ticket_price = ticket_price;
+        paid_entry.max_tickets_per_wallet = max_tickets_per_wallet;
+        paid_entry.prize_bps = prize_bps;
+        paid_entry.reserve_bps = reserve_bps;
+        paid_entry.dev_bps = dev_bps;
+        paid_entry.dev_wallet = dev_wallet;
+        paid_entry.prize_pool = 0;
+        paid_entry.reserve_pool = 0;
+        paid_entry.dev_paid = 0;
+        paid_entry.tickets_sold = 0;
+        paid_entry.is_enabled = true;
+        paid_entry.bump = ctx.bumps.paid_entry;
+        paid_entry.vault_bump = ctx.bumps.vault;
+
+        match payment_mint {
+            Some(mint) => msg!("Paid entry enabled: {} raw units of {} per ticket", ticket_price, mint),
+            None => msg!("Paid entry enabled: {} lamports per ticket", ticket_price),
+        }
+
+        Ok(())
+    }
+
+    /// Admin: change price, cap, split or dev wallet, or switch paid entry off.
+    /// The payment mint is fixed at init because the vault may already hold funds.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_paid_entry(
+        ctx: Context<UpdatePaidEntry>,
+        ticket_price: u64,
+        max_tickets_per_wallet: u32,
+        prize_bps: u16,
+        reserve_bps: u16,
+        dev_bps: u16,
+        dev_wallet: Pubkey,
+        is_enabled: bool,
+    ) -> Result<()> {
+        require!(ctx.accounts.admin.key() == ctx.accounts.lottery.admin, ErrorCode::Unauthorized);
+        validate_paid_entry_config(ticket_price, max_tickets_per_wallet, prize_bps, reserve_bps, dev_bps)?;
+
+        let paid_entry = &mut ctx.accounts.paid_entry;
+        paid_entry.ticket_price = ticket_price;
+        paid_entry.max_tickets_per_wallet = max_tickets_per_wallet;
+        paid_entry.prize_bps = prize_bps;
+        paid_entry.reserve_bps = reserve_bps;
+        paid_entry.dev_bps = dev_bps;
+        paid_entry.dev_wallet = dev_wallet;
+        paid_entry.is_enabled = is_enabled;
+
+        Ok(())
+    }
+
+    /// Buy `ticket_count` tickets with SOL. Prize + reserve go to the lottery vault,
+    /// dev share goes straight to the dev wallet.
+    pub fn enter_lottery_paid_sol(
+        ctx: Context<EnterLotteryPaidSol>,
+        ticket_count: u32,
+    ) -> Result<()> {
+        let split = record_paid_entry(
+            &mut ctx.accounts.lottery,
+            &mut ctx.accounts.paid_entry,
+            &mut ctx.accounts.participant_account,
+            ctx.accounts.participant.key(),
+            ticket_count,
+        )?;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.participant.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            split.vault_amount(),
+        )?;
+
+        if split.dev > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.participant.to_account_info(),
+                        to: ctx.accounts.dev_wallet.to_account_info(),
+                    },
+                ),
+                split.dev,
+            )?;
+        }
+
+        msg!("Paid entry: {} tickets for {} lamports", ticket_count, split.total());
+
+        Ok(())
+    }
+
+    /// Buy `ticket_count` tickets with the configured SPL mint (USDC).
+    /// Vault is the vault PDA's associated token account.
+    pub fn enter_lottery_paid_token(
+        ctx: Context<EnterLotteryPaidToken>,
+        ticket_count: u32,
+    ) -> Result<()> {
+        let split = record_paid_entry(
+            &mut ctx.accounts.lottery,
+            &mut ctx.accounts.paid_entry,
+            &mut ctx.accounts.participant_account,
+            ctx.accounts.participant.key(),
+            ticket_count,
+        )?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.participant_token_account.to_account_info(),
+                    to: ctx.accounts.vault_token_account.to_account_info(),
+                    authority: ctx.accounts.participant.to_account_info(),
+                },
+            ),
+            split.vault_amount(),
+        )?;
+
+        if split.dev > 0 {
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.participant_token_account.to_account_info(),
+                        to: ctx.accounts.dev_token_account.to_account_info(),
+                        authority: ctx.accounts.participant.to_account_info(),
+                    },
+                ),
+                split.dev,
+            )?;
+        }
+
+        msg!("Paid entry: {} tickets for {} raw units", ticket_count, split.total());
+
+        Ok(())
+    }
+
     // Snapshot with 50/50 rollover mechanic
     // For 20k participants, use off-chain indexing + on-chain verification
     pub fn take_snapshot(ctx: Context<TakeSnapshot>) -> Result<()> {
@@ -146,37 +346,45 @@ pub mod lottery {
             ErrorCode::DrawTooEarly
         );
         
-        require!(lottery.total_participants >= 9, ErrorCode::NotEnoughParticipants);
+        require!(lottery.total_participants >= pepeball_draw::MIN_PARTICIPANTS, ErrorCode::NotEnoughParticipants);
         require!(lottery.total_tickets > 0, ErrorCode::NotEnoughParticipants);
         
-        // Use deterministic randomness based on blockchain state
-        let seed = clock.slot
-            .wrapping_mul(clock.unix_timestamp as u64)
-            .wrapping_add(lottery.total_participants)
-            .wrapping_add(lottery.total_snapshots);
+        // Use deterministic randomness based on blockchain state (shared with off-chain tools)
+        let seed = pepeball_draw::derive_seed(
+            clock.slot,
+            clock.unix_timestamp,
+            lottery.total_participants,
+            lottery.total_snapshots,
+        );
         
         // 50/50 ROLLOVER MECHANIC: Calculate Pepe ball count (1-30)
         // Mix of numbered balls and Pepe balls - use randomness to determine count
-        let pepe_count = ((seed % 30) as u8) + 1; // 1-30 balls
+        let pepe_count = pepeball_draw::ball_count(seed);
         lottery.pepe_ball_count = pepe_count;
-        let is_odd = pepe_count % 2 == 1;
         
         lottery.snapshot_seed = seed;
         lottery.last_snapshot = clock.unix_timestamp;
         lottery.total_snapshots += 1;
         lottery.is_fast_mode = lottery.fees_collected >= lottery.fast_mode_threshold;
 
-        if is_odd {
+        // Seed is zeroed on EVEN below; the event keeps it for indexers and verifiers
+        emit!(SnapshotTaken {
+            snapshot_number: lottery.total_snapshots,
+            seed,
+            pepe_ball_count: pepe_count,
+            total_participants: lottery.total_participants,
+            total_tickets: lottery.total_tickets,
+            slot: clock.slot,
+            unix_timestamp: clock.unix_timestamp,
+        });
+
+        if pepeball_draw::parity(pepe_count) == Parity::Odd {
             // ODD = PAYOUT: wait for set_winners + payout_winners
         } else {
             // EVEN = ROLLOVER: extend timer, keep participants
             lottery.rollover_count += 1;
-            let extension = if lottery.rollover_count % 2 == 0 {
-                72 * 3600
-            } else {
-                48 * 3600
-            };
-            lottery.last_snapshot = clock.unix_timestamp + extension as i64;
+            let extension = pepeball_draw::rollover_extension_secs(lottery.rollover_count);
+            lottery.last_snapshot = clock.unix_timestamp + extension;
             lottery.snapshot_seed = 0;
         }
         
@@ -184,6 +392,9 @@ pub mod lottery {
     }
 
     // Verify and set winners (called after off-chain calculation)
+    // Optional on-chain check: pass every participant account of the round as
+    // remaining accounts, sorted by wallet, and the winners are recomputed with
+    // pepeball_draw. Rounds too large for one transaction skip the check.
     pub fn set_winners(
         ctx: Context<SetWinners>,
         main_winner: Pubkey,
@@ -192,7 +403,17 @@ pub mod lottery {
         let lottery = &mut ctx.accounts.lottery;
         require!(ctx.accounts.admin.key() == lottery.admin, ErrorCode::Unauthorized);
         require!(lottery.snapshot_seed > 0, ErrorCode::NoWinners);
-        require!(minor_winners.len() <= 8, ErrorCode::InvalidConfig);
+        require!(minor_winners.len() <= pepeball_draw::MINOR_WINNERS, ErrorCode::InvalidConfig);
+
+        if !ctx.remaining_accounts.is_empty() {
+            verify_winners(
+                ctx.program_id,
+                lottery,
+                ctx.remaining_accounts,
+                &main_winner,
+                &minor_winners,
+            )?;
+        }
         
         lottery.winners.main_winner = Some(main_winner);
         lottery.winners.minor_winners = minor_winners;
@@ -204,9 +425,19 @@ pub mod lottery {
         let lottery = &mut ctx.accounts.lottery;
         require!(ctx.accounts.admin.key() == lottery.admin, ErrorCode::Unauthorized);
         require!(lottery.winners.main_winner.is_some(), ErrorCode::NoWinners);
-        require!(lottery.pepe_ball_count % 2 == 1, ErrorCode::InvalidConfig); // Must be odd for payout
+        require!(pepeball_draw::parity(lottery.pepe_ball_count) == Parity::Odd, ErrorCode::InvalidConfig); // Must be odd for payout
         
-        // 50/50 payout: 50% main, 40% 8 minors, 8% rollover reserve, 2% dev (enforced off-chain)
+        emit!(RoundPaidOut {
+            snapshot_number: lottery.total_snapshots,
+            seed: lottery.snapshot_seed,
+            jackpot_amount: lottery.jackpot_amount,
+            main_winner: lottery.winners.main_winner.unwrap_or_default(),
+            minor_winners: lottery.winners.minor_winners.clone(),
+            total_participants: lottery.total_participants,
+            total_tickets: lottery.total_tickets,
+        });
+
+        // Split per pepeball_draw::*_BPS: 50% main, 40% 8 minors, 8% rollover reserve, 2% dev (enforced off-chain)
         // Reset for next round
         lottery.carry_over_amount = 0;
         lottery.jackpot_amount = 0;  // Winners paid; rollover reserve + fees handled off-chain
@@ -280,6 +511,60 @@ pub mod lottery {
         Ok(())
     }
 
+    /// Admin: move SOL out of the paid-entry vault (prize payout or reserve top-up).
+    pub fn withdraw_paid_entry_sol(
+        ctx: Context<WithdrawPaidEntrySol>,
+        amount: u64,
+        bucket: PaidEntryBucket,
+    ) -> Result<()> {
+        require!(ctx.accounts.admin.key() == ctx.accounts.lottery.admin, ErrorCode::Unauthorized);
+        require!(ctx.accounts.paid_entry.payment_mint.is_none(), ErrorCode::InvalidPaymentAccount);
+        debit_paid_entry_bucket(&mut ctx.accounts.paid_entry, amount, bucket)?;
+
+        let lottery_key = ctx.accounts.lottery.key();
+        let seeds: &[&[u8]] = &[b"lottery_vault", lottery_key.as_ref(), &[ctx.accounts.paid_entry.vault_bump]];
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    /// Admin: move SPL tokens out of the paid-entry vault.
+    pub fn withdraw_paid_entry_token(
+        ctx: Context<WithdrawPaidEntryToken>,
+        amount: u64,
+        bucket: PaidEntryBucket,
+    ) -> Result<()> {
+        require!(ctx.accounts.admin.key() == ctx.accounts.lottery.admin, ErrorCode::Unauthorized);
+        debit_paid_entry_bucket(&mut ctx.accounts.paid_entry, amount, bucket)?;
+
+        let lottery_key = ctx.accounts.lottery.key();
+        let seeds: &[&[u8]] = &[b"lottery_vault", lottery_key.as_ref(), &[ctx.accounts.paid_entry.vault_bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        Ok(())
+    }
+
     pub fn close_lottery(ctx: Context<CloseLottery>) -> Result<()> {
         let lottery = &ctx.accounts.lottery;
         require!(ctx.accounts.admin.key() == lottery.admin, ErrorCode::Unauthorized);
@@ -323,6 +608,13 @@ pub struct EnterLottery<'info> {
     pub participant: Signer<'info>,
     
     pub system_program: Program<'info, System>,
+
+    /// CHECK: paid-entry config PDA; empty unless paid entry is set up
+    #[account(seeds = [b"paid_entry", lottery.key().as_ref()], bump)]
+    pub paid_entry: UncheckedAccount<'info>,
+
+    /// Lottery admin, co-signing free entries while paid entry is set up
+    pub admin: Option<Signer<'info>>,
 }
 
 #[derive(Accounts)]
@@ -339,6 +631,157 @@ pub struct UpdateParticipant<'info> {
     
     #[account(mut)]
     pub participant: Signer<'info>,
+
+    /// CHECK: paid-entry config PDA; empty unless paid entry is set up
+    #[account(seeds = [b"paid_entry", lottery.key().as_ref()], bump)]
+    pub paid_entry: UncheckedAccount<'info>,
+
+    /// Lottery admin, co-signing free tickets while paid entry is set up
+    pub admin: Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePaidEntry<'info> {
+    pub lottery: Account<'info, Lottery>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + PaidEntryConfig::INIT_SPACE,
+        seeds = [b"paid_entry", lottery.key().as_ref()],
+        bump
+    )]
+    pub paid_entry: Account<'info, PaidEntryConfig>,
+
+    /// Holds SOL entries and is the authority of the SPL vault ATA
+    #[account(
+        mut,
+        seeds = [b"lottery_vault", lottery.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePaidEntry<'info> {
+    pub lottery: Account<'info, Lottery>,
+
+    #[account(
+        mut,
+        seeds = [b"paid_entry", lottery.key().as_ref()],
+        bump = paid_entry.bump,
+        has_one = lottery
+    )]
+    pub paid_entry: Account<'info, PaidEntryConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EnterLotteryPaidSol<'info> {
+    #[account(mut)]
+    pub lottery: Account<'info, Lottery>,
+
+    #[account(
+        mut,
+        seeds = [b"paid_entry", lottery.key().as_ref()],
+        bump = paid_entry.bump,
+        has_one = lottery,
+        constraint = paid_entry.payment_mint.is_none() @ ErrorCode::InvalidPaymentAccount
+    )]
+    pub paid_entry: Account<'info, PaidEntryConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = participant,
+        space = 8 + ParticipantAccount::INIT_SPACE,
+        seeds = [b"participant", lottery.key().as_ref(), participant.key().as_ref()],
+        bump
+    )]
+    pub participant_account: Account<'info, ParticipantAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"lottery_vault", lottery.key().as_ref()],
+        bump = paid_entry.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: any account the config names (wallet, PDA or multisig); only receives lamports
+    #[account(mut, address = paid_entry.dev_wallet @ ErrorCode::InvalidPaymentAccount)]
+    pub dev_wallet: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub participant: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EnterLotteryPaidToken<'info> {
+    #[account(mut)]
+    pub lottery: Account<'info, Lottery>,
+
+    #[account(
+        mut,
+        seeds = [b"paid_entry", lottery.key().as_ref()],
+        bump = paid_entry.bump,
+        has_one = lottery,
+        constraint = paid_entry.payment_mint == Some(payment_mint.key()) @ ErrorCode::InvalidPaymentAccount
+    )]
+    pub paid_entry: Account<'info, PaidEntryConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = participant,
+        space = 8 + ParticipantAccount::INIT_SPACE,
+        seeds = [b"participant", lottery.key().as_ref(), participant.key().as_ref()],
+        bump
+    )]
+    pub participant_account: Account<'info, ParticipantAccount>,
+
+    /// CHECK: vault PDA, only used as the ATA authority
+    #[account(
+        seeds = [b"lottery_vault", lottery.key().as_ref()],
+        bump = paid_entry.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    pub payment_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = participant
+    )]
+    pub participant_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = participant,
+        associated_token::mint = payment_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        constraint = dev_token_account.owner == paid_entry.dev_wallet @ ErrorCode::InvalidPaymentAccount
+    )]
+    pub dev_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub participant: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -404,6 +847,70 @@ pub struct EmergencyPauseLottery<'info> {
     pub admin: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct WithdrawPaidEntrySol<'info> {
+    pub lottery: Account<'info, Lottery>,
+
+    #[account(
+        mut,
+        seeds = [b"paid_entry", lottery.key().as_ref()],
+        bump = paid_entry.bump,
+        has_one = lottery
+    )]
+    pub paid_entry: Account<'info, PaidEntryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"lottery_vault", lottery.key().as_ref()],
+        bump = paid_entry.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawPaidEntryToken<'info> {
+    pub lottery: Account<'info, Lottery>,
+
+    #[account(
+        mut,
+        seeds = [b"paid_entry", lottery.key().as_ref()],
+        bump = paid_entry.bump,
+        has_one = lottery,
+        constraint = paid_entry.payment_mint == Some(payment_mint.key()) @ ErrorCode::InvalidPaymentAccount
+    )]
+    pub paid_entry: Account<'info, PaidEntryConfig>,
+
+    /// CHECK: vault PDA, signs for the vault ATA
+    #[account(
+        seeds = [b"lottery_vault", lottery.key().as_ref()],
+        bump = paid_entry.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    pub payment_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = payment_mint)]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub admin: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
 #[derive(Accounts)]
 pub struct CloseLottery<'info> {
     #[account(
@@ -456,6 +963,36 @@ pub struct ParticipantAccount {
     pub entry_time: i64,
 }
 
+/// Paid-entry mode for standalone rounds. Pools are running balances held in the
+/// vault (SOL PDA or its ATA); dev share is paid out at entry time.
+#[account]
+#[derive(InitSpace)]
+pub struct PaidEntryConfig {
+    pub lottery: Pubkey,
+    /// None = SOL entries, Some = SPL mint (USDC)
+    pub payment_mint: Option<Pubkey>,
+    /// Lamports or raw token units per ticket
+    pub ticket_price: u64,
+    pub max_tickets_per_wallet: u32,
+    pub prize_bps: u16,
+    pub reserve_bps: u16,
+    pub dev_bps: u16,
+    pub dev_wallet: Pubkey,
+    pub prize_pool: u64,
+    pub reserve_pool: u64,
+    pub dev_paid: u64,
+    pub tickets_sold: u64,
+    pub is_enabled: bool,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PaidEntryBucket {
+    Prize,
+    Reserve,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
 pub struct Winners {
     pub main_winner: Option<Pubkey>,
@@ -463,9 +1000,40 @@ pub struct Winners {
     pub minor_winners: Vec<Pubkey>,
 }
 
+#[event]
+pub struct SnapshotTaken {
+    pub snapshot_number: u64,
+    pub seed: u64,
+    pub pepe_ball_count: u8,
+    pub total_participants: u64,
+    pub total_tickets: u64,
+    pub slot: u64,
+    pub unix_timestamp: i64,
+}
+
+#[event]
+pub struct RoundPaidOut {
+    pub snapshot_number: u64,
+    pub seed: u64,
+    pub jackpot_amount: u64,
+    pub main_winner: Pubkey,
+    pub minor_winners: Vec<Pubkey>,
+    pub total_participants: u64,
+    pub total_tickets: u64,
+}
+
+/// Tickets a USD entry got under the thresholds in force when it landed,
+/// so indexers never re-tier history with later settings.
+#[event]
+pub struct UsdEntryTiered {
+    pub wallet: Pubkey,
+    pub usd_value: u64,
+    pub ticket_count: u32,
+}
+
 /// Ticket tiers: entry_min→1, tier2_min→2, tier3_min→4.
 /// Prod: 2000, 10000, 50000 ($20/$100/$500).
-fn calculate_tickets_from_usd_value(
+pub fn calculate_tickets_from_usd_value(
     usd_value: u64,
     entry_min_cents: u64,
     tier2_min_cents: u64,
@@ -483,6 +1051,165 @@ fn calculate_tickets_from_usd_value(
     4
 }
 
+fn validate_paid_entry_config(
+    ticket_price: u64,
+    max_tickets_per_wallet: u32,
+    prize_bps: u16,
+    reserve_bps: u16,
+    dev_bps: u16,
+) -> Result<()> {
+    require!(ticket_price > 0, ErrorCode::InvalidConfig);
+    require!(max_tickets_per_wallet > 0, ErrorCode::InvalidConfig);
+    require!(
+        prize_bps as u32 + reserve_bps as u32 + dev_bps as u32 == 10_000,
+        ErrorCode::InvalidConfig
+    );
+    Ok(())
+}
+
+/// Payment for one paid entry. Prize takes the rounding remainder.
+struct PaidEntrySplit {
+    prize: u64,
+    reserve: u64,
+    dev: u64,
+}
+
+impl PaidEntrySplit {
+    fn new(total: u64, reserve_bps: u16, dev_bps: u16) -> Result<Self> {
+        let reserve = (total as u128 * reserve_bps as u128 / 10_000) as u64;
+        let dev = (total as u128 * dev_bps as u128 / 10_000) as u64;
+        let prize = total
+            .checked_sub(reserve)
+            .and_then(|v| v.checked_sub(dev))
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(Self { prize, reserve, dev })
+    }
+
+    fn vault_amount(&self) -> u64 {
+        self.prize + self.reserve
+    }
+
+    fn total(&self) -> u64 {
+        self.prize + self.reserve + self.dev
+    }
+}
+
+/// Shared bookkeeping for SOL and SPL paid entries: cap check, participant
+/// upsert, pool counters. Caller moves the funds.
+fn record_paid_entry(
+    lottery: &mut Account<Lottery>,
+    paid_entry: &mut Account<PaidEntryConfig>,
+    participant_account: &mut Account<ParticipantAccount>,
+    participant_wallet: Pubkey,
+    ticket_count: u32,
+) -> Result<PaidEntrySplit> {
+    require!(lottery.is_active, ErrorCode::LotteryInactive);
+    require!(paid_entry.is_enabled, ErrorCode::PaidEntryDisabled);
+    require!(ticket_count > 0, ErrorCode::InsufficientValue);
+
+    let is_new = participant_account.wallet == Pubkey::default();
+    let held = if is_new { 0 } else { participant_account.ticket_count };
+    let new_count = held.checked_add(ticket_count).ok_or(ErrorCode::MathOverflow)?;
+    require!(new_count <= paid_entry.max_tickets_per_wallet, ErrorCode::TicketCapExceeded);
+
+    let total = paid_entry
+        .ticket_price
+        .checked_mul(ticket_count as u64)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let split = PaidEntrySplit::new(total, paid_entry.reserve_bps, paid_entry.dev_bps)?;
+
+    if is_new {
+        participant_account.lottery = lottery.key();
+        participant_account.wallet = participant_wallet;
+        participant_account.usd_value = 0;
+        participant_account.entry_time = Clock::get()?.unix_timestamp;
+        lottery.total_participants += 1;
+    }
+    participant_account.ticket_count = new_count;
+    lottery.total_tickets += ticket_count as u64;
+
+    paid_entry.prize_pool = paid_entry.prize_pool.checked_add(split.prize).ok_or(ErrorCode::MathOverflow)?;
+    paid_entry.reserve_pool = paid_entry.reserve_pool.checked_add(split.reserve).ok_or(ErrorCode::MathOverflow)?;
+    paid_entry.dev_paid = paid_entry.dev_paid.checked_add(split.dev).ok_or(ErrorCode::MathOverflow)?;
+    paid_entry.tickets_sold += ticket_count as u64;
+
+    Ok(split)
+}
+
+/// Free tickets bypass the price, so once a paid-entry config exists they
+/// need the admin's signature and `new_count` (the wallet's tickets after
+/// the entry) must stay within the per-wallet cap.
+fn check_free_entry(
+    lottery: &Account<Lottery>,
+    paid_entry: &UncheckedAccount,
+    admin: &Option<Signer>,
+    new_count: u32,
+) -> Result<()> {
+    if paid_entry.data_is_empty() {
+        return Ok(());
+    }
+    let config = PaidEntryConfig::try_deserialize(&mut &paid_entry.try_borrow_data()?[..])?;
+    require!(
+        admin.as_ref().is_some_and(|admin| admin.key() == lottery.admin),
+        ErrorCode::PaidEntryRequired
+    );
+    require!(new_count <= config.max_tickets_per_wallet, ErrorCode::TicketCapExceeded);
+    Ok(())
+}
+
+fn debit_paid_entry_bucket(
+    paid_entry: &mut Account<PaidEntryConfig>,
+    amount: u64,
+    bucket: PaidEntryBucket,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidConfig);
+    let pool = match bucket {
+        PaidEntryBucket::Prize => &mut paid_entry.prize_pool,
+        PaidEntryBucket::Reserve => &mut paid_entry.reserve_pool,
+    };
+    *pool = pool.checked_sub(amount).ok_or(ErrorCode::InsufficientPoolBalance)?;
+    Ok(())
+}
+
+/// Recompute winners from the full participant set and compare.
+fn verify_winners(
+    program_id: &Pubkey,
+    lottery: &Account<Lottery>,
+    participant_infos: &[AccountInfo],
+    main_winner: &Pubkey,
+    minor_winners: &[Pubkey],
+) -> Result<()> {
+    require!(
+        participant_infos.len() as u64 == lottery.total_participants,
+        ErrorCode::WinnersMismatch
+    );
+
+    let mut wallets: Vec<Pubkey> = Vec::with_capacity(participant_infos.len());
+    let mut ticket_counts: Vec<u32> = Vec::with_capacity(participant_infos.len());
+    for info in participant_infos {
+        require!(info.owner == program_id, ErrorCode::WinnersMismatch);
+        let participant = ParticipantAccount::try_deserialize(&mut &info.data.borrow()[..])?;
+        require!(participant.lottery == lottery.key(), ErrorCode::WinnersMismatch);
+        // Strictly ascending wallets: canonical order, no duplicates
+        if let Some(prev) = wallets.last() {
+            require!(*prev < participant.wallet, ErrorCode::WinnersMismatch);
+        }
+        wallets.push(participant.wallet);
+        ticket_counts.push(participant.ticket_count);
+    }
+    let total: u64 = ticket_counts.iter().map(|c| *c as u64).sum();
+    require!(total == lottery.total_tickets, ErrorCode::WinnersMismatch);
+
+    let selection = pepeball_draw::select_winners(lottery.snapshot_seed, &ticket_counts)
+        .ok_or(ErrorCode::NotEnoughParticipants)?;
+    require!(wallets[selection.main] == *main_winner, ErrorCode::WinnersMismatch);
+    require!(selection.minors.len() == minor_winners.len(), ErrorCode::WinnersMismatch);
+    for (idx, expected) in selection.minors.iter().zip(minor_winners) {
+        require!(wallets[*idx] == *expected, ErrorCode::WinnersMismatch);
+    }
+    Ok(())
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("Lottery is not active")]
@@ -499,4 +1226,18 @@ pub enum ErrorCode {
     InsufficientValue,
     #[msg("Invalid configuration values")]
     InvalidConfig,
+    #[msg("Paid entry is disabled")]
+    PaidEntryDisabled,
+    #[msg("Ticket cap per wallet exceeded")]
+    TicketCapExceeded,
+    #[msg("Payment account does not match paid entry config")]
+    InvalidPaymentAccount,
+    #[msg("Not enough funds in this pool")]
+    InsufficientPoolBalance,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Winners do not match the draw for this seed")]
+    WinnersMismatch,
+    #[msg("Paid entry is on: free tickets need the admin's signature")]
+    PaidEntryRequired,
 }
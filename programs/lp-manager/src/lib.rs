@@ -1,7 +1,5 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount};
-use anchor_spl::associated_token::AssociatedToken;
-use std::cmp::{min, max};
 
 declare_id!("G5WidJNwmdp33kQ6AeTcrsKJdP9rvuecfyZXCmQ6oSNG");
 
@@ -45,6 +43,7 @@ pub mod lp_manager {
         amount: u64,
     ) -> Result<()> {
         let lp_manager = &mut ctx.accounts.lp_manager;
+        require!(amount <= ctx.accounts.lp_pool.amount, ErrorCode::InsufficientFunds);
         
         // Transfer tokens to jackpot pool
         let transfer_ctx = CpiContext::new(
@@ -113,6 +112,7 @@ pub mod lp_manager {
     ) -> Result<()> {
         let lp_manager = &mut ctx.accounts.lp_manager;
         require!(ctx.accounts.admin.key() == lp_manager.admin, ErrorCode::Unauthorized);
+        require!(amount <= ctx.accounts.lp_pool.amount, ErrorCode::InsufficientFunds);
         
         msg!("EMERGENCY WITHDRAWAL: {} tokens", amount);
         msg!("This should only be used in extreme circumstances!");
@@ -140,6 +140,7 @@ pub mod lp_manager {
         let lp_manager = &mut ctx.accounts.lp_manager;
         require!(ctx.accounts.admin.key() == lp_manager.admin, ErrorCode::Unauthorized);
         require!(lp_manager.is_initialized, ErrorCode::NotInitialized);
+        require!(tick_lower < tick_upper, ErrorCode::InvalidTickRange);
         
         lp_manager.orca_position_initialized = true;
         lp_manager.tick_lower = tick_lower;
@@ -197,7 +198,7 @@ pub mod lp_manager {
     /// Auto-rebalance LP position to maintain delta-neutral exposure
     pub fn auto_rebalance(
         ctx: Context<AutoRebalance>,
-        current_price: u64,
+        _current_price: u64,
         current_delta: i64,
         rebalance_threshold: i64, // Minimum delta deviation to trigger rebalance
     ) -> Result<()> {
@@ -221,7 +222,7 @@ pub mod lp_manager {
         }
         
         // Calculate rebalance amount
-        let rebalance_amount = delta_deviation.abs() as u64;
+        let rebalance_amount = delta_deviation.unsigned_abs();
         
         if delta_deviation > 0 {
             // Positive delta - need to reduce long exposure
@@ -254,6 +255,7 @@ pub mod lp_manager {
         let lp_manager = &mut ctx.accounts.lp_manager;
         require!(ctx.accounts.admin.key() == lp_manager.admin, ErrorCode::Unauthorized);
         require!(lp_manager.is_initialized, ErrorCode::NotInitialized);
+        require!(new_tick_lower < new_tick_upper, ErrorCode::InvalidTickRange);
         
         lp_manager.tick_lower = new_tick_lower;
         lp_manager.tick_upper = new_tick_upper;
@@ -377,7 +379,7 @@ impl LPManager {
         // In reality, Orca uses: price = 1.0001^tick
         // For basis points: price_bp = (1.0001^tick) * 10000
         let base: f64 = 1.0001;
-        let price = base.powi(tick as i32);
+        let price = base.powi(tick);
         let price_bp = (price * 10000.0) as u64;
         Ok(price_bp)
     }
@@ -0,0 +1,416 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_lang::system_program::{self, CreateAccount};
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_hook::{self, TransferHookAccount};
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_2022::spl_token_2022::state::Account as Token2022Account;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use pepball_token::{
+    AmmPools, CheckpointHistory, CooldownTable, ExemptionList, LaunchViolation, TokenInfo, VestingSchedule,
+    AMM_POOLS_SEED, CHECKPOINTS_SEED, EXEMPTION_LIST_SEED, FEE_AUTHORITY_SEED, LAUNCH_GUARD_SEED, TOKEN_INFO_SEED,
+};
+use spl_tlv_account_resolution::account::ExtraAccountMeta;
+use spl_tlv_account_resolution::pubkey_data::PubkeyData;
+use spl_tlv_account_resolution::seeds::Seed;
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_transfer_hook_interface::instruction::{ExecuteInstruction, TransferHookInstruction};
+
+declare_id!("9ceGyFecRokeAPwsURaf6XgNZ9VVg6tB2WfAGCn3KXjC");
+
+/// Seed of the extra-account-metas PDA, fixed by the transfer-hook interface.
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+
+/// Token-2022 calls `execute` on every transfer of a PEPEBALL mint, so the
+/// token rules hold for wallet and DEX transfers too, not only for
+/// `transfer_with_tax` callers. This lives outside pepball-token because the
+/// runtime forbids re-entry: pepball-token's own payouts of the mint would
+/// otherwise call back into it through Token-2022.
+#[program]
+pub mod pepball_hook {
+    use super::*;
+
+    /// Writes the accounts Token-2022 appends to `execute`: the mint's
+    /// `TokenInfo`, `ExemptionList` and `AmmPools`, this program's
+    /// `LaunchGuard`, created here, the source and destination owners'
+    /// `BalanceCheckpoints` and the source owner itself. Permissionless — the
+    /// list only depends on the mint.
+    pub fn initialize_extra_account_meta_list(ctx: Context<InitializeExtraAccountMetaList>) -> Result<()> {
+        let metas = extra_account_metas(&ctx.accounts.token_info.key(), &ctx.accounts.launch_guard.key())?;
+        let space = ExtraAccountMetaList::size_of(metas.len())?;
+        let mint_key = ctx.accounts.mint.key();
+        let seeds: &[&[u8]] = &[
+            EXTRA_ACCOUNT_METAS_SEED,
+            mint_key.as_ref(),
+            &[ctx.bumps.extra_account_meta_list],
+        ];
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ctx.accounts.extra_account_meta_list.to_account_info(),
+                },
+                &[seeds],
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            ctx.program_id,
+        )?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(
+            &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
+            &metas,
+        )?;
+
+        ctx.accounts.launch_guard.mint = mint_key;
+
+        msg!("Transfer hook accounts initialized for mint {}", mint_key);
+
+        Ok(())
+    }
+
+    /// Checks one transfer. Payouts signed by pepball-token's fee authority
+    /// are exempt: they are program-internal and may be smaller than the minimum.
+    /// Exempt senders and recipients skip the minimum and the launch limits
+    /// but not the pause, and so do vesting escrows (owned by a pepball-token
+    /// `VestingSchedule`), whose last claim or refund may be below the minimum.
+    ///
+    /// Every transfer also records both sides' new balances in their owners'
+    /// `BalanceCheckpoints`. The hook has no payer, so wallets without any
+    /// are left out until `checkpoint_balance` creates them.
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        require!(is_transferring(&ctx.accounts.source_token)?, HookError::NotTransferring);
+
+        // Token-2022 runs the hook after the move, so the balances are final.
+        let slot = Clock::get()?.slot;
+        record_checkpoint(&ctx.accounts.source_checkpoints, &ctx.accounts.source_token, slot)?;
+        record_checkpoint(&ctx.accounts.destination_checkpoints, &ctx.accounts.destination_token, slot)?;
+
+        let (fee_authority, _) = Pubkey::find_program_address(
+            &[FEE_AUTHORITY_SEED, ctx.accounts.mint.key().as_ref()],
+            &pepball_token::ID,
+        );
+        if ctx.accounts.owner.key() == fee_authority {
+            return Ok(());
+        }
+
+        let token_info = &ctx.accounts.token_info;
+        require!(!token_info.is_paused, HookError::TransfersPaused);
+
+        let exemptions = &ctx.accounts.exemption_list;
+        if exemptions.is_exempt_sender(&ctx.accounts.source_token.owner)
+            || exemptions.is_exempt_recipient(&ctx.accounts.destination_token.owner)
+            || is_vesting_schedule(&ctx.accounts.source_owner)?
+        {
+            return Ok(());
+        }
+        require!(amount >= token_info.min_transfer_amount, HookError::AmountTooSmall);
+
+        let protection = token_info.launch_protection;
+        if protection.is_active(slot) {
+            let to_pool = ctx.accounts.amm_pools.contains(&ctx.accounts.destination_token.key());
+            let recipient_balance = (!to_pool).then_some(ctx.accounts.destination_token.amount);
+            protection.check(slot, amount, recipient_balance).map_err(launch_error)?;
+            if !to_pool {
+                ctx.accounts
+                    .launch_guard
+                    .cooldowns
+                    .touch(ctx.accounts.destination_token.owner, slot, protection.cooldown_slots)
+                    .map_err(launch_error)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Permissionless: records the current balance of `owner`'s associated
+    /// token account, creating the owner's checkpoints on first use (`payer`
+    /// funds them). From then on every transfer updates them. Only mints
+    /// whose transfers run this hook: elsewhere plain transfers would leave
+    /// the checkpoints stale.
+    pub fn checkpoint_balance(ctx: Context<CheckpointBalance>) -> Result<()> {
+        let hooked = {
+            let mint = ctx.accounts.mint.to_account_info();
+            let data = mint.try_borrow_data()?;
+            *mint.owner == spl_token_2022::ID
+                && StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)
+                    .is_ok_and(|state| transfer_hook::get_program_id(&state) == Some(crate::ID))
+        };
+        require!(hooked, HookError::MintNotHooked);
+
+        let slot = Clock::get()?.slot;
+        let balance = ctx.accounts.token_account.amount;
+        let checkpoints = &mut ctx.accounts.checkpoints;
+        checkpoints.mint = ctx.accounts.mint.key();
+        checkpoints.owner = ctx.accounts.owner.key();
+        checkpoints.bump = ctx.bumps.checkpoints;
+        checkpoints.history.record(slot, balance);
+
+        msg!("Checkpoint {}: {} at slot {}", checkpoints.owner, balance, slot);
+
+        Ok(())
+    }
+
+    /// Token-2022 invokes the hook with the interface's `Execute`
+    /// discriminator rather than Anchor's; route it to `transfer_hook`.
+    pub fn fallback<'info>(program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], data: &[u8]) -> Result<()> {
+        match TransferHookInstruction::unpack(data)? {
+            TransferHookInstruction::Execute { amount } => {
+                __private::__global::transfer_hook(program_id, accounts, &amount.to_le_bytes())
+            }
+            _ => Err(ProgramError::InvalidInstructionData.into()),
+        }
+    }
+}
+
+/// Accounts appended to every `execute`, in order.
+fn extra_account_metas(token_info: &Pubkey, launch_guard: &Pubkey) -> Result<Vec<ExtraAccountMeta>> {
+    let (exemption_list, _) =
+        Pubkey::find_program_address(&[EXEMPTION_LIST_SEED, token_info.as_ref()], &pepball_token::ID);
+    let (amm_pools, _) = Pubkey::find_program_address(&[AMM_POOLS_SEED, token_info.as_ref()], &pepball_token::ID);
+    Ok(vec![
+        ExtraAccountMeta::new_with_pubkey(token_info, false, false)?,
+        ExtraAccountMeta::new_with_pubkey(&exemption_list, false, false)?,
+        ExtraAccountMeta::new_with_pubkey(&amm_pools, false, false)?,
+        ExtraAccountMeta::new_with_pubkey(launch_guard, false, true)?,
+        // The owners' checkpoints: ["checkpoints", mint, owner of source / destination]
+        checkpoints_meta(0)?,
+        checkpoints_meta(2)?,
+        // The source token account's owner, read from its data
+        ExtraAccountMeta::new_with_pubkey_data(
+            &PubkeyData::AccountData {
+                account_index: 0,
+                data_index: 32,
+            },
+            false,
+            false,
+        )?,
+    ])
+}
+
+fn checkpoints_meta(token_account_index: u8) -> Result<ExtraAccountMeta> {
+    Ok(ExtraAccountMeta::new_with_seeds(
+        &[
+            Seed::Literal {
+                bytes: CHECKPOINTS_SEED.to_vec(),
+            },
+            Seed::AccountKey { index: 1 },
+            Seed::AccountData {
+                account_index: token_account_index,
+                data_index: 32,
+                length: 32,
+            },
+        ],
+        false,
+        true,
+    )?)
+}
+
+/// Records `token_account`'s balance if `checkpoints` were created and it is
+/// the owner's associated token account.
+fn record_checkpoint(checkpoints: &AccountInfo, token_account: &InterfaceAccount<TokenAccount>, slot: u64) -> Result<()> {
+    if checkpoints.owner != &crate::ID {
+        return Ok(());
+    }
+    let ata = get_associated_token_address_with_program_id(
+        &token_account.owner,
+        &token_account.mint,
+        token_account.to_account_info().owner,
+    );
+    if token_account.key() != ata {
+        return Ok(());
+    }
+    let mut account = BalanceCheckpoints::try_deserialize(&mut &checkpoints.try_borrow_data()?[..])?;
+    account.history.record(slot, token_account.amount);
+    account.try_serialize(&mut &mut checkpoints.try_borrow_mut_data()?[..])
+}
+
+/// Only pepball-token can sign for its `VestingSchedule` PDAs, and it only
+/// does so to release or refund an escrow.
+fn is_vesting_schedule(account: &AccountInfo) -> Result<bool> {
+    Ok(account.owner == &pepball_token::ID && account.try_borrow_data()?.starts_with(&VestingSchedule::DISCRIMINATOR))
+}
+
+fn launch_error(violation: LaunchViolation) -> Error {
+    match violation {
+        LaunchViolation::NotStarted => HookError::LaunchNotStarted,
+        LaunchViolation::MaxTransaction => HookError::MaxTransactionExceeded,
+        LaunchViolation::MaxWallet => HookError::MaxWalletExceeded,
+        LaunchViolation::Cooldown => HookError::WalletCooldown,
+        LaunchViolation::CooldownTableFull => HookError::CooldownTableFull,
+    }
+    .into()
+}
+
+/// Token-2022 sets `transferring` on the source for the duration of the CPI,
+/// so a direct call to `transfer_hook` fails here.
+fn is_transferring(source: &InterfaceAccount<TokenAccount>) -> Result<bool> {
+    let info = source.to_account_info();
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<Token2022Account>::unpack(&data)?;
+    Ok(state
+        .get_extension::<TransferHookAccount>()
+        .map(|ext| bool::from(ext.transferring))
+        .unwrap_or(false))
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: created and written here
+    #[account(mut, seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [TOKEN_INFO_SEED, mint.key().as_ref()],
+        bump = token_info.bump,
+        seeds::program = pepball_token::ID
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + LaunchGuard::INIT_SPACE,
+        seeds = [LAUNCH_GUARD_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub launch_guard: Account<'info, LaunchGuard>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Account order is fixed by the transfer-hook interface: source, mint,
+/// destination, owner, extra-account-metas, then the listed extras.
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: source owner or delegate; already authorized by Token-2022
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: the list Token-2022 resolved the extras from
+    #[account(seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [TOKEN_INFO_SEED, mint.key().as_ref()],
+        bump = token_info.bump,
+        seeds::program = pepball_token::ID
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(
+        seeds = [EXEMPTION_LIST_SEED, token_info.key().as_ref()],
+        bump,
+        seeds::program = pepball_token::ID
+    )]
+    pub exemption_list: Account<'info, ExemptionList>,
+
+    #[account(
+        seeds = [AMM_POOLS_SEED, token_info.key().as_ref()],
+        bump,
+        seeds::program = pepball_token::ID
+    )]
+    pub amm_pools: Account<'info, AmmPools>,
+
+    #[account(mut, seeds = [LAUNCH_GUARD_SEED, mint.key().as_ref()], bump)]
+    pub launch_guard: Account<'info, LaunchGuard>,
+
+    /// CHECK: `BalanceCheckpoints` of the source's owner, if created
+    #[account(mut, seeds = [CHECKPOINTS_SEED, mint.key().as_ref(), source_token.owner.as_ref()], bump)]
+    pub source_checkpoints: UncheckedAccount<'info>,
+
+    /// CHECK: `BalanceCheckpoints` of the destination's owner, if created
+    #[account(mut, seeds = [CHECKPOINTS_SEED, mint.key().as_ref(), destination_token.owner.as_ref()], bump)]
+    pub destination_checkpoints: UncheckedAccount<'info>,
+
+    /// CHECK: owner of `source_token`; read to recognise vesting escrows
+    #[account(address = source_token.owner)]
+    pub source_owner: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CheckpointBalance<'info> {
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + BalanceCheckpoints::INIT_SPACE,
+        seeds = [CHECKPOINTS_SEED, mint.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub checkpoints: Account<'info, BalanceCheckpoints>,
+
+    /// CHECK: any wallet; only names whose token account is read
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = token_account.key() == get_associated_token_address_with_program_id(
+            owner.key,
+            &mint.key(),
+            token_account.to_account_info().owner,
+        ) @ HookError::InvalidCheckpointAccount
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Launch cooldowns of Token-2022 transfers: `["launch_guard", mint]`.
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchGuard {
+    pub mint: Pubkey,
+    pub cooldowns: CooldownTable,
+}
+
+/// Balance history of Token-2022 transfers: `["checkpoints", mint, owner]`,
+/// for the owner's associated token account.
+#[account]
+#[derive(InitSpace)]
+pub struct BalanceCheckpoints {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub history: CheckpointHistory,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum HookError {
+    #[msg("Hook was not invoked by a Token-2022 transfer")]
+    NotTransferring,
+    #[msg("Token transfers are paused")]
+    TransfersPaused,
+    #[msg("Transfer amount is too small")]
+    AmountTooSmall,
+    #[msg("Trading has not started")]
+    LaunchNotStarted,
+    #[msg("Amount exceeds the launch max transaction")]
+    MaxTransactionExceeded,
+    #[msg("Recipient would exceed the launch max wallet")]
+    MaxWalletExceeded,
+    #[msg("Recipient is in its launch cooldown")]
+    WalletCooldown,
+    #[msg("Checkpoints track the owner's associated token account of this mint")]
+    InvalidCheckpointAccount,
+    #[msg("Mint does not run this transfer hook")]
+    MintNotHooked,
+    #[msg("Every launch cooldown slot is taken; retry once one ends")]
+    CooldownTableFull,
+}
@@ -1,35 +1,296 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
- 
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::pubkey;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use anchor_lang::system_program::{self, CreateAccount};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id, AssociatedToken, Create};
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType;
+use anchor_spl::token_2022::{self, spl_token_2022};
+use anchor_spl::token_interface::spl_pod::optional_keys::OptionalNonZeroPubkey;
+use anchor_spl::token_interface::spl_token_metadata_interface::state::{Field, TokenMetadata};
+use anchor_spl::token_interface::{
+    harvest_withheld_tokens_to_mint, initialize_mint2, metadata_pointer_initialize, mint_to, set_authority,
+    token_metadata_initialize, token_metadata_update_field, transfer_fee_initialize, transfer_hook_initialize,
+    withdraw_withheld_tokens_from_mint, HarvestWithheldTokensToMint, InitializeMint2, MetadataPointerInitialize,
+    Mint as InterfaceMint, MintTo, SetAuthority, TokenAccount as InterfaceTokenAccount, TokenInterface,
+    TokenMetadataInitialize, TokenMetadataUpdateField, TransferFeeInitialize, TransferHookInitialize,
+    WithdrawWithheldTokensFromMint,
+};
+
+pub mod metaplex;
 
 declare_id!("HArmxo4FBfy7RiT3iS7erxvC23L1AreU9AskyXc3iuhR");
 
+/// Transfer tax in basis points: creator fund + jackpot.
+pub const TAX_RATE_BPS: u16 = 250;
+pub const CREATOR_FUND_RATE_BPS: u16 = 5;
+pub const JACKPOT_RATE_BPS: u16 = 245;
+const _: () = assert!(TAX_RATE_BPS == CREATOR_FUND_RATE_BPS + JACKPOT_RATE_BPS);
+/// Recipients per `batch_transfer_with_tax`; with each recipient's
+/// checkpoints the batch still fits one legacy transaction.
+pub const MAX_BATCH_RECIPIENTS: usize = 8;
+/// Hard cap on the total tax of any direction (buy, sell, transfer).
+pub const MAX_TAX_RATE_BPS: u16 = 1_000;
+/// Smallest taxed transfer in raw units (prevents dust attacks).
+pub const MIN_TRANSFER_AMOUNT: u64 = 1000;
+/// Whole tokens minted at initialization; nothing can be minted after.
+pub const TOTAL_SUPPLY_TOKENS: u64 = 1_000_000_000;
+/// Metadata limits, in bytes (`TokenInfo` space).
+pub const MAX_NAME_LEN: usize = 32;
+pub const MAX_SYMBOL_LEN: usize = 10;
+pub const MAX_URI_LEN: usize = 200;
+
+/// `TokenInfo` PDA: `["token_info", mint]`. It is the mint authority for the
+/// one supply mint, so a mint has exactly one `TokenInfo`.
+pub const TOKEN_INFO_SEED: &[u8] = b"token_info";
+
+/// `ExemptionList` PDA: `["exemptions", token_info]`, created with the token.
+pub const EXEMPTION_LIST_SEED: &[u8] = b"exemptions";
+/// Entries in the exemption list (pools, vaults, program accounts).
+pub const MAX_EXEMPTIONS: usize = 32;
+
+/// `AmmPools` PDA: `["amm_pools", token_info]`, created with the token.
+pub const AMM_POOLS_SEED: &[u8] = b"amm_pools";
+/// Registered AMM pool token accounts.
+pub const MAX_AMM_POOLS: usize = 8;
+
+/// `LaunchGuard` PDA: `["launch_guard", token_info]`, per-wallet cooldowns
+/// of `transfer_with_tax` during the launch window.
+pub const LAUNCH_GUARD_SEED: &[u8] = b"launch_guard";
+/// Wallets tracked for the launch cooldown. Entries free up as their
+/// cooldown ends; while all are cooling down, new recipients are refused.
+pub const MAX_COOLDOWN_WALLETS: usize = 64;
+/// Longest launch window, in slots (~1 hour).
+pub const MAX_LAUNCH_WINDOW_SLOTS: u64 = 9_000;
+/// How far ahead of the current slot a launch window may start (~1 day).
+pub const MAX_LAUNCH_LEAD_SLOTS: u64 = 216_000;
+
+/// `VestingSchedule` PDA: `["vesting", token_info, beneficiary]`, one
+/// locked allocation per beneficiary.
+pub const VESTING_SEED: &[u8] = b"vesting";
+
+/// `BalanceCheckpoints` PDA: `["checkpoints", token_info, owner]`, the
+/// balances the taxed transfers leave in the owner's associated token
+/// account. Plain SPL transfers never reach the program, so these are no
+/// proof of holding; pepball-hook's `["checkpoints", mint, owner]`, which
+/// see every Token-2022 transfer, are the ones eligibility can count.
+pub const CHECKPOINTS_SEED: &[u8] = b"checkpoints";
+/// Checkpoints kept per wallet; the oldest is dropped once full.
+pub const MAX_CHECKPOINTS: usize = 16;
+/// One checkpoint per interval (~6 hours): records within it merge, so the
+/// kept history always spans 16 intervals, more than a 72-hour round.
+pub const CHECKPOINT_INTERVAL_SLOTS: u64 = 54_000;
+
+/// Delegate PDA `["permit_authority", token_info]`: holders approve it on
+/// their token account so relayers can move tokens with a signed permit.
+pub const PERMIT_AUTHORITY_SEED: &[u8] = b"permit_authority";
+/// `PermitNonce` PDA: `["permit_nonce", token_info, owner]`, the next nonce
+/// an owner's permit must carry.
+pub const PERMIT_NONCE_SEED: &[u8] = b"permit_nonce";
+/// Prefix of every permit message, so a permit signature is never valid as
+/// anything else.
+pub const PERMIT_DOMAIN: &[u8] = b"pepball-token:permit";
+
+/// What a holder signs (ed25519) to allow `transfer_with_tax_permit`:
+/// `PERMIT_DOMAIN || from || to || amount || nonce || expiry`, integers
+/// little-endian. `from`/`to` are token accounts, `expiry` a unix timestamp.
+pub fn permit_message(from: &Pubkey, to: &Pubkey, amount: u64, nonce: u64, expiry: i64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(PERMIT_DOMAIN.len() + 32 + 32 + 8 + 8 + 8);
+    message.extend_from_slice(PERMIT_DOMAIN);
+    message.extend_from_slice(from.as_ref());
+    message.extend_from_slice(to.as_ref());
+    message.extend_from_slice(&amount.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message.extend_from_slice(&expiry.to_le_bytes());
+    message
+}
+
+/// Token-2022 mode: PDA `["fee_authority", mint]` owns the transfer-fee config
+/// and withheld-fee withdrawals, so no wallet can redirect the tax.
+pub const FEE_AUTHORITY_SEED: &[u8] = b"fee_authority";
+/// Default jackpot vault owner: tax-harvest's `vault_authority` PDA, so the
+/// jackpot share can only leave through `process_harvest`. That only sells
+/// legacy SPL Token vaults; Token-2022 mints need `set_jackpot_vault_owner`.
+pub const TAX_HARVEST_PROGRAM_ID: Pubkey = pubkey!("Em261K95h8M48f52iuu5YSaTJXJTs1pqjZpRCPYFqXRx");
+
+pub fn jackpot_vault_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"vault_authority"], &TAX_HARVEST_PROGRAM_ID).0
+}
+
+/// pepball-hook: Token-2022 calls it on every transfer of the fee mint.
+pub const TRANSFER_HOOK_PROGRAM_ID: Pubkey = pubkey!("9ceGyFecRokeAPwsURaf6XgNZ9VVg6tB2WfAGCn3KXjC");
+
+/// pepball-hook's launch cooldown table for `mint`: `["launch_guard", mint]`.
+pub fn hook_launch_guard(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[LAUNCH_GUARD_SEED, mint.as_ref()], &TRANSFER_HOOK_PROGRAM_ID).0
+}
+
+/// pepball-hook's balance checkpoints of `owner`: `["checkpoints", mint, owner]`.
+pub fn hook_checkpoints(mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CHECKPOINTS_SEED, mint.as_ref(), owner.as_ref()], &TRANSFER_HOOK_PROGRAM_ID).0
+}
+
+/// The hook's extra-account-metas PDA for `mint`: `["extra-account-metas", mint]`.
+pub fn extra_account_metas(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], &TRANSFER_HOOK_PROGRAM_ID).0
+}
+
 #[program]
 pub mod pepball_token {
     use super::*;
 
+    /// Creates `mint` (a fresh keypair), mints the full supply to
+    /// `distribution_owner`'s associated token account, then revokes the mint
+    /// authority; the mint never has a freeze authority.
+    ///
+    /// With the Token-2022 program the mint also gets a TransferFee extension
+    /// charging `tax_rate` on every transfer, including plain wallet and DEX
+    /// transfers, and a TransferHook pointing at pepball-hook, which enforces
+    /// pause and minimum amount on those transfers; its account list is set up
+    /// by `pepball_hook::initialize_extra_account_meta_list`. The Token-2022
+    /// mint also carries its own token metadata (name, symbol, `uri`) with
+    /// `token_info` as update authority, so wallets and explorers can show it;
+    /// a legacy mint gets the same in a Metaplex metadata account, passed as
+    /// `metadata` with `token_metadata_program`.
     pub fn initialize_token(
         ctx: Context<InitializeToken>,
         name: String,
         symbol: String,
+        uri: String,
         decimals: u8,
         creator_fund_address: Pubkey,
     ) -> Result<()> {
+        require!(
+            name.len() <= MAX_NAME_LEN && symbol.len() <= MAX_SYMBOL_LEN && uri.len() <= MAX_URI_LEN,
+            ErrorCode::MetadataTooLong
+        );
+        let total_supply = 10_u64
+            .checked_pow(decimals as u32)
+            .and_then(|unit| unit.checked_mul(TOTAL_SUPPLY_TOKENS))
+            .ok_or(ErrorCode::InvalidDecimals)?;
+        let transfer_fee_mode = ctx.accounts.token_program.key() == token_2022::ID;
+
+        let mint_key = ctx.accounts.mint.key();
+        let metadata = TokenMetadata {
+            update_authority: OptionalNonZeroPubkey(ctx.accounts.token_info.key()),
+            mint: mint_key,
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            additional_metadata: Vec::new(),
+        };
+        create_mint(ctx.accounts, ctx.program_id, decimals, transfer_fee_mode.then_some(&metadata))?;
+
+        let token_program = ctx.accounts.token_program.to_account_info();
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: ctx.accounts.admin.to_account_info(),
+                associated_token: ctx.accounts.distribution.to_account_info(),
+                authority: ctx.accounts.distribution_owner.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: token_program.clone(),
+            },
+        ))?;
+
+        let seeds: &[&[u8]] = &[TOKEN_INFO_SEED, mint_key.as_ref(), &[ctx.bumps.token_info]];
+        let signer = &[seeds];
+        mint_to(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.distribution.to_account_info(),
+                    authority: ctx.accounts.token_info.to_account_info(),
+                },
+                signer,
+            ),
+            total_supply,
+        )?;
+        if transfer_fee_mode {
+            token_metadata_initialize(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    TokenMetadataInitialize {
+                        token_program_id: token_program.clone(),
+                        metadata: ctx.accounts.mint.to_account_info(),
+                        update_authority: ctx.accounts.token_info.to_account_info(),
+                        mint_authority: ctx.accounts.token_info.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                    },
+                    signer,
+                ),
+                metadata.name,
+                metadata.symbol,
+                metadata.uri,
+            )?;
+        } else {
+            let (Some(metaplex_metadata), Some(metaplex_program)) =
+                (&ctx.accounts.metadata, &ctx.accounts.token_metadata_program)
+            else {
+                return err!(ErrorCode::MetadataAccountsRequired);
+            };
+            metaplex::create(
+                metaplex_program,
+                metaplex_metadata,
+                &ctx.accounts.mint,
+                &ctx.accounts.token_info.to_account_info(),
+                &ctx.accounts.admin,
+                &ctx.accounts.system_program,
+                &name,
+                &symbol,
+                &uri,
+                signer,
+            )?;
+        }
+        set_authority(
+            CpiContext::new_with_signer(
+                token_program,
+                SetAuthority {
+                    current_authority: ctx.accounts.token_info.to_account_info(),
+                    account_or_mint: ctx.accounts.mint.to_account_info(),
+                },
+                signer,
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
+
         let token_info = &mut ctx.accounts.token_info;
         token_info.name = name;
         token_info.symbol = symbol;
+        token_info.uri = uri;
         token_info.decimals = decimals;
-        token_info.total_supply = 1_000_000_000 * 10_u64.pow(decimals as u32);
-        token_info.tax_rate = 250; // 2.5% total tax
-        token_info.creator_fund_rate = 5; // 0.05% to creator fund (Matt Furie)
-        token_info.jackpot_rate = 245; // 2.45% to jackpot
+        token_info.total_supply = total_supply;
+        token_info.tax_rate = TAX_RATE_BPS; // 2.5% total tax
+        token_info.creator_fund_rate = CREATOR_FUND_RATE_BPS; // 0.05% to creator fund (Matt Furie)
+        token_info.jackpot_rate = JACKPOT_RATE_BPS; // 2.45% to jackpot
+        token_info.buy_tax = TaxSplit::launch();
+        token_info.sell_tax = TaxSplit::launch();
         token_info.creator_fund_address = creator_fund_address;
         token_info.is_renounced = false;
         token_info.is_paused = false;
         token_info.admin = ctx.accounts.admin.key();
-        token_info.min_transfer_amount = 1000; // Minimum 1000 tokens (prevents dust attacks)
-        
+        token_info.min_transfer_amount = MIN_TRANSFER_AMOUNT;
+        token_info.mint = mint_key;
+        token_info.transfer_fee_mode = transfer_fee_mode;
+        token_info.bump = ctx.bumps.token_info;
+        token_info.jackpot_vault_owner = jackpot_vault_authority();
+        ctx.accounts.exemption_list.token_info = token_info.key();
+        ctx.accounts.amm_pools.token_info = token_info.key();
+        ctx.accounts.launch_guard.token_info = token_info.key();
+
         msg!("PEPEBALL Token initialized!");
+        msg!("Mint: {} ({} raw minted to {}, mint authority revoked)", mint_key, total_supply, ctx.accounts.distribution.key());
+        if transfer_fee_mode {
+            msg!("Token-2022 transfer fee: {} bps", TAX_RATE_BPS);
+        }
         msg!("Creator Fund Address: {}", creator_fund_address);
         msg!("Tax Rate: {}% ({}% creator + {}% jackpot)", 
              token_info.tax_rate as f64 / 100.0,
@@ -39,77 +300,855 @@ pub mod pepball_token {
         Ok(())
     }
 
-    pub fn transfer_with_tax(
-        ctx: Context<TransferWithTax>,
+    /// Tax only lands in accounts of the bound mint owned by
+    /// `creator_fund_address` and `jackpot_vault_owner`. Moves from an exempt
+    /// sender or to an exempt recipient (by token account owner) are passed
+    /// through untaxed and without the minimum. The rate depends on direction:
+    /// out of a registered AMM pool is a buy, into one a sell, anything else
+    /// a wallet-to-wallet transfer. During the launch window the transfer
+    /// must also pass the anti-bot limits.
+    ///
+    /// The sender's and recipient's `BalanceCheckpoints` get the new balances
+    /// of their associated token accounts; `authority` pays for any created.
+    pub fn transfer_with_tax(ctx: Context<TransferWithTax>, amount: u64) -> Result<()> {
+        ctx.accounts.taxed_move().execute(amount, &[])
+    }
+
+    /// `transfer_with_tax` on the owner's behalf, for relayers and bots. The
+    /// owner of `from` signs `permit_message(from, to, amount, nonce,
+    /// expiry)` off-chain; the instruction right before this one must be the
+    /// ed25519 precompile checking that signature. `from` must have approved
+    /// `permit_authority` as delegate for at least `amount`, `nonce` must be
+    /// the owner's next nonce and `expiry` must not have passed. The relayer
+    /// pays for the owner's `PermitNonce` and any checkpoints on first use.
+    pub fn transfer_with_tax_permit(
+        ctx: Context<TransferWithTaxPermit>,
         amount: u64,
+        nonce: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        require!(Clock::get()?.unix_timestamp <= expiry, ErrorCode::PermitExpired);
+        let owner = ctx.accounts.from.owner;
+        let message = permit_message(&ctx.accounts.from.key(), &ctx.accounts.to.key(), amount, nonce, expiry);
+        verify_permit_signature(&ctx.accounts.instructions, &owner, &message)?;
+
+        let permit_nonce = &mut ctx.accounts.permit_nonce;
+        require!(nonce == permit_nonce.nonce, ErrorCode::InvalidPermitNonce);
+        permit_nonce.nonce = nonce.checked_add(1).ok_or(ErrorCode::InvalidPermitNonce)?;
+        permit_nonce.bump = ctx.bumps.permit_nonce;
+        msg!("Permit {} of {}", nonce, owner);
+
+        let token_info = ctx.accounts.token_info.key();
+        let seeds: &[&[u8]] = &[PERMIT_AUTHORITY_SEED, token_info.as_ref(), &[ctx.bumps.permit_authority]];
+        ctx.accounts.taxed_move().execute(amount, &[seeds])
+    }
+
+    /// Pays `amounts[i]` to the i-th pair of remaining accounts: a token
+    /// account of the mint, then its owner's `BalanceCheckpoints`. Up to
+    /// `MAX_BATCH_RECIPIENTS` distinct recipients, none of them a registered
+    /// AMM pool: a batch is for wallets and is taxed at the wallet-to-wallet
+    /// rate. Every amount, exempt or not, must meet the minimum. The taxed
+    /// amounts are quoted once as a single transfer and each taxed recipient
+    /// gets its pro rata share of the net. Exemptions, the max wallet and
+    /// cooldown apply per recipient, the launch max transaction to the taxed
+    /// total. The sender's and recipients'
+    /// checkpoints get the new balances; `authority` pays for any created.
+    pub fn batch_transfer_with_tax<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchTransferWithTax<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        let recipients = ctx.remaining_accounts;
+        require!(
+            !amounts.is_empty() && amounts.len() <= MAX_BATCH_RECIPIENTS && amounts.len() * 2 == recipients.len(),
+            ErrorCode::InvalidBatch
+        );
+        require!(!ctx.accounts.token_info.is_paused, ErrorCode::TransfersPaused);
+
+        let sender_exempt = ctx.accounts.exemption_list.is_exempt_sender(&ctx.accounts.from.owner);
+        // Everything is checked and summed before anything moves.
+        let mut payees = Vec::with_capacity(amounts.len());
+        let mut total: u64 = 0;
+        let mut taxed_total: u64 = 0;
+        for (i, (pair, &amount)) in recipients.chunks_exact(2).zip(&amounts).enumerate() {
+            let recipient = &pair[0];
+            require!(
+                recipients[..2 * i].chunks_exact(2).all(|earlier| earlier[0].key != recipient.key),
+                ErrorCode::InvalidBatch
+            );
+            let to = Account::<TokenAccount>::try_from(recipient)?;
+            require!(to.mint == ctx.accounts.token_info.mint, ErrorCode::MintMismatch);
+            require!(!ctx.accounts.amm_pools.contains(recipient.key), ErrorCode::BatchToAmmPool);
+            require!(amount >= ctx.accounts.token_info.min_transfer_amount, ErrorCode::AmountTooSmall);
+            total = total.checked_add(amount).ok_or(ErrorCode::InvalidTaxCalculation)?;
+            let taxed = !sender_exempt && !ctx.accounts.exemption_list.is_exempt_recipient(&to.owner);
+            if taxed {
+                taxed_total += amount;
+            }
+            payees.push((to, taxed));
+        }
+
+        let quote = ctx.accounts.token_info.tax_split(TaxDirection::Transfer).quote(taxed_total);
+        require!(taxed_total == 0 || quote.net_amount > 0, ErrorCode::InvalidTaxCalculation);
+        let (mut net_left, mut taxed_left) = (quote.net_amount, taxed_total);
+        for ((pair, &amount), (to, taxed)) in recipients.chunks_exact(2).zip(&amounts).zip(&payees) {
+            let (recipient, checkpoints) = (&pair[0], &pair[1]);
+            let net = if *taxed {
+                // Pro rata share of the quoted net; the last taxed recipient
+                // takes what is left, so the nets add up to the quote.
+                let net = (net_left as u128 * amount as u128 / taxed_left as u128) as u64;
+                net_left -= net;
+                taxed_left -= amount;
+                // The max wallet is checked against the balance before the batch.
+                enforce_launch_protection(
+                    &mut ctx.accounts.token_info,
+                    &mut ctx.accounts.launch_guard,
+                    amount,
+                    Some((to.owner, to.amount.saturating_add(net))),
+                )?;
+                net
+            } else {
+                amount
+            };
+
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.from.to_account_info(),
+                        to: recipient.clone(),
+                        authority: ctx.accounts.authority.to_account_info(),
+                    },
+                ),
+                net,
+            )?;
+            record_checkpoint(
+                &ctx.accounts.token_info,
+                checkpoints,
+                recipient,
+                &ctx.accounts.authority,
+                &ctx.accounts.system_program,
+            )?;
+        }
+        if taxed_total > 0 {
+            // One batch is one transaction for the launch max transaction.
+            enforce_launch_protection(&mut ctx.accounts.token_info, &mut ctx.accounts.launch_guard, taxed_total, None)?;
+        }
+
+        let (creator_tax, jackpot_tax, burn_tax) = (quote.creator_tax, quote.jackpot_tax, quote.burn_tax);
+        for (destination, share) in [
+            (ctx.accounts.creator_fund.to_account_info(), creator_tax),
+            (ctx.accounts.jackpot_pool.to_account_info(), jackpot_tax),
+        ] {
+            if share == 0 {
+                continue;
+            }
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.from.to_account_info(),
+                        to: destination,
+                        authority: ctx.accounts.authority.to_account_info(),
+                    },
+                ),
+                share,
+            )?;
+        }
+        if burn_tax > 0 {
+            token::burn(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        from: ctx.accounts.from.to_account_info(),
+                        authority: ctx.accounts.authority.to_account_info(),
+                    },
+                ),
+                burn_tax,
+            )?;
+        }
+        record_checkpoint(
+            &ctx.accounts.token_info,
+            &ctx.accounts.from_checkpoints,
+            &ctx.accounts.from.to_account_info(),
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+        )?;
+
+        msg!("Batch: {} tokens to {} recipients", total, amounts.len());
+        msg!("Creator Tax: {} tokens, Jackpot Tax: {} tokens, Burn Tax: {} tokens", creator_tax, jackpot_tax, burn_tax);
+
+        let token_info = &mut ctx.accounts.token_info;
+        token_info
+            .stats
+            .record_transfers(amounts.len() as u64, total, creator_tax, jackpot_tax, burn_tax);
+        emit!(BatchTaxedTransfer {
+            token_info: token_info.key(),
+            from: ctx.accounts.from.key(),
+            recipients: amounts.len() as u8,
+            amount: total,
+            net_amount: total - (taxed_total - quote.net_amount),
+            creator_tax,
+            jackpot_tax,
+            burn_tax,
+        });
+
+        Ok(())
+    }
+
+    /// Token-2022 mode: harvest withheld fees from `remaining_accounts` into the
+    /// mint, withdraw everything withheld on the mint and pay it out in the
+    /// jackpot/creator split (245/5). Permissionless — destinations are fixed.
+    /// What the destinations receive counts toward the lifetime tax totals in
+    /// `TokenInfo::stats`.
+    ///
+    /// Payouts leave the fee vault as ordinary transfers, so they are taxed
+    /// too; that fee is withheld on the destinations and collected (and
+    /// counted) next time.
+    pub fn withdraw_withheld_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawWithheldFees<'info>>,
     ) -> Result<()> {
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let mint = ctx.accounts.mint.to_account_info();
+
+        if !ctx.remaining_accounts.is_empty() {
+            harvest_withheld_tokens_to_mint(
+                CpiContext::new(
+                    token_program.clone(),
+                    HarvestWithheldTokensToMint {
+                        token_program_id: token_program.clone(),
+                        mint: mint.clone(),
+                    },
+                ),
+                ctx.remaining_accounts.to_vec(),
+            )?;
+        }
+
+        let (withheld, fee_config) = {
+            let data = mint.try_borrow_data()?;
+            let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+            let config = *state.get_extension::<TransferFeeConfig>()?;
+            (u64::from(config.withheld_amount), config)
+        };
+        require!(withheld > 0, ErrorCode::NoWithheldFees);
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds: &[&[u8]] = &[FEE_AUTHORITY_SEED, mint_key.as_ref(), &[ctx.bumps.fee_authority]];
+        let signer = &[seeds];
+        withdraw_withheld_tokens_from_mint(CpiContext::new_with_signer(
+            token_program.clone(),
+            WithdrawWithheldTokensFromMint {
+                token_program_id: token_program.clone(),
+                mint: mint.clone(),
+                destination: ctx.accounts.fee_vault.to_account_info(),
+                authority: ctx.accounts.fee_authority.to_account_info(),
+            },
+            signer,
+        ))?;
+
+        let (creator_share, jackpot_share) = ctx.accounts.token_info.tax_split(TaxDirection::Transfer).split_fee(withheld);
+        let decimals = ctx.accounts.mint.decimals;
+        // The hook exempts transfers signed by the fee authority; it still
+        // needs its accounts to run.
+        let hook_accounts = [
+            ctx.accounts.token_info.to_account_info(),
+            ctx.accounts.exemption_list.to_account_info(),
+            ctx.accounts.amm_pools.to_account_info(),
+            ctx.accounts.hook_launch_guard.to_account_info(),
+            ctx.accounts.fee_authority_checkpoints.to_account_info(),
+            ctx.accounts.jackpot_checkpoints.to_account_info(),
+            ctx.accounts.creator_checkpoints.to_account_info(),
+            ctx.accounts.fee_authority.to_account_info(),
+            ctx.accounts.hook_program.to_account_info(),
+            ctx.accounts.extra_account_meta_list.to_account_info(),
+        ];
+        // Net of the fee the payout itself is charged
+        let epoch = Clock::get()?.epoch;
+        let mut received = [0u64; 2];
+        for ((destination, share), received) in [
+            (ctx.accounts.jackpot_pool.to_account_info(), jackpot_share),
+            (ctx.accounts.creator_fund.to_account_info(), creator_share),
+        ]
+        .into_iter()
+        .zip(&mut received)
+        {
+            if share == 0 {
+                continue;
+            }
+            let fee = fee_config
+                .calculate_epoch_fee(epoch, share)
+                .ok_or(ErrorCode::InvalidTaxCalculation)?;
+            *received = share - fee;
+            spl_token_2022::onchain::invoke_transfer_checked(
+                token_program.key,
+                ctx.accounts.fee_vault.to_account_info(),
+                mint.clone(),
+                destination,
+                ctx.accounts.fee_authority.to_account_info(),
+                &hook_accounts,
+                share,
+                decimals,
+                signer,
+            )?;
+        }
+
+        let [jackpot_received, creator_received] = received;
+        ctx.accounts.token_info.stats.record_fees(creator_received, jackpot_received);
+
+        msg!("Withheld fees: {} raw ({} jackpot, {} creator)", withheld, jackpot_received, creator_received);
+
+        Ok(())
+    }
+
+    /// Read-only: what moving `amount` from `from` to `to` would pay out,
+    /// returned as a `TransferQuote` in the return data. Fails like the
+    /// transfer would on pause or below the minimum; launch limits are not
+    /// checked. In Token-2022 mode the tax is the mint's transfer fee for
+    /// the current epoch.
+    pub fn quote_transfer(ctx: Context<QuoteTransfer>, amount: u64) -> Result<TransferQuote> {
         let token_info = &ctx.accounts.token_info;
-        
-        // CRITICAL FIX 1: Check if transfers are paused
         require!(!token_info.is_paused, ErrorCode::TransfersPaused);
-        
-        // CRITICAL FIX 2: Check minimum transfer amount
-        require!(amount >= token_info.min_transfer_amount, ErrorCode::AmountTooSmall);
-        
-        // Calculate taxes
-        let creator_tax = (amount.saturating_mul(token_info.creator_fund_rate as u64)) / 10000; // 0.05%
-        let jackpot_tax = (amount.saturating_mul(token_info.jackpot_rate as u64)) / 10000; // 2.45%
-        let total_tax = creator_tax + jackpot_tax;
-        
-        // CRITICAL FIX 3: Validate tax calculation
-        require!(total_tax < amount, ErrorCode::InvalidTaxCalculation);
-        require!(total_tax > 0, ErrorCode::InvalidTaxCalculation); // Ensure tax is applied
-        
-        let transfer_amount = amount - total_tax;
-        
-        msg!("Transfer: {} tokens", amount);
-        msg!("Creator Tax: {} tokens (0.05%)", creator_tax);
-        msg!("Jackpot Tax: {} tokens (2.45%)", jackpot_tax);
-        msg!("Net Transfer: {} tokens", transfer_amount);
-        
-        // Transfer to recipient
-        let transfer_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.from.to_account_info(),
-                to: ctx.accounts.to.to_account_info(),
-                authority: ctx.accounts.authority.to_account_info(),
-            },
+
+        let exemptions = &ctx.accounts.exemption_list;
+        let exempt = exemptions.is_exempt_sender(&ctx.accounts.from.owner)
+            || exemptions.is_exempt_recipient(&ctx.accounts.to.owner);
+        if !exempt {
+            require!(amount >= token_info.min_transfer_amount, ErrorCode::AmountTooSmall);
+        }
+
+        let (direction, tax) = if token_info.transfer_fee_mode {
+            let mint = ctx.accounts.mint.to_account_info();
+            let data = mint.try_borrow_data()?;
+            let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+            let fee = state
+                .get_extension::<TransferFeeConfig>()?
+                .calculate_epoch_fee(Clock::get()?.epoch, amount)
+                .ok_or(ErrorCode::InvalidTaxCalculation)?;
+            let (creator_tax, jackpot_tax) = token_info.tax_split(TaxDirection::Transfer).split_fee(fee);
+            let quote = TaxQuote {
+                net_amount: amount - fee,
+                creator_tax,
+                jackpot_tax,
+                burn_tax: 0,
+            };
+            (TaxDirection::Transfer, quote)
+        } else if exempt {
+            let quote = TaxQuote {
+                net_amount: amount,
+                ..TaxQuote::default()
+            };
+            (TaxDirection::Transfer, quote)
+        } else {
+            let pools = &ctx.accounts.amm_pools;
+            let direction = if pools.contains(&ctx.accounts.from.key()) {
+                TaxDirection::Buy
+            } else if pools.contains(&ctx.accounts.to.key()) {
+                TaxDirection::Sell
+            } else {
+                TaxDirection::Transfer
+            };
+            (direction, token_info.tax_split(direction).quote(amount))
+        };
+
+        Ok(TransferQuote {
+            direction,
+            exempt,
+            amount,
+            net_amount: tax.net_amount,
+            creator_tax: tax.creator_tax,
+            jackpot_tax: tax.jackpot_tax,
+            burn_tax: tax.burn_tax,
+        })
+    }
+
+    /// Read-only: the minted supply less what the burn share has destroyed,
+    /// returned as a `u64` in the return data.
+    pub fn circulating_supply(ctx: Context<CirculatingSupply>) -> Result<u64> {
+        Ok(ctx.accounts.token_info.circulating_supply())
+    }
+
+    /// Locks `amount` from `source` for `beneficiary` in an escrow, the
+    /// associated token account of the `VestingSchedule` PDA: nothing vests
+    /// before `cliff_ts`, then it vests linearly from `start_ts` to `end_ts`.
+    /// In Token-2022 mode the hook accounts follow as remaining accounts and
+    /// the schedule covers what the escrow received after the transfer fee.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_vesting<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateVesting<'info>>,
+        category: VestingCategory,
+        amount: u64,
+        start_ts: i64,
+        cliff_ts: i64,
+        end_ts: i64,
+        revocable: bool,
+    ) -> Result<()> {
+        let token_info = &ctx.accounts.token_info;
+        require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
+        require!(ctx.accounts.admin.key() == token_info.admin, ErrorCode::Unauthorized);
+        require!(
+            amount > 0 && start_ts < end_ts && (start_ts..=end_ts).contains(&cliff_ts),
+            ErrorCode::InvalidVestingSchedule
         );
-        token::transfer(transfer_ctx, transfer_amount)?;
-        
-        // Transfer creator fund tax to Matt Furie
-        let creator_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.from.to_account_info(),
-                to: ctx.accounts.creator_fund.to_account_info(),
-                authority: ctx.accounts.authority.to_account_info(),
-            },
+
+        spl_token_2022::onchain::invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.source.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.escrow.to_account_info(),
+            ctx.accounts.funder.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            ctx.accounts.mint.decimals,
+            &[],
+        )?;
+        ctx.accounts.escrow.reload()?;
+        let total_amount = ctx.accounts.escrow.amount;
+
+        ctx.accounts.vesting.set_inner(VestingSchedule {
+            token_info: ctx.accounts.token_info.key(),
+            beneficiary: ctx.accounts.beneficiary.key(),
+            category,
+            total_amount,
+            claimed: 0,
+            start_ts,
+            cliff_ts,
+            end_ts,
+            revocable,
+            revoked: false,
+            bump: ctx.bumps.vesting,
+        });
+
+        emit!(VestingCreated {
+            token_info: ctx.accounts.token_info.key(),
+            vesting: ctx.accounts.vesting.key(),
+            beneficiary: ctx.accounts.beneficiary.key(),
+            category,
+            amount: total_amount,
+            start_ts,
+            cliff_ts,
+            end_ts,
+            revocable,
+        });
+        msg!("Vesting {:?}: {} raw for {}", category, total_amount, ctx.accounts.beneficiary.key());
+
+        Ok(())
+    }
+
+    /// Sends everything vested and not yet claimed to `destination`.
+    pub fn claim_vested<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimVested<'info>>) -> Result<()> {
+        require!(!ctx.accounts.token_info.is_paused, ErrorCode::TransfersPaused);
+        let amount = ctx.accounts.vesting.claimable(Clock::get()?.unix_timestamp);
+        require!(amount > 0, ErrorCode::NothingVested);
+
+        release_vested(
+            &ctx.accounts.vesting,
+            &ctx.accounts.escrow,
+            &ctx.accounts.mint,
+            ctx.accounts.destination.to_account_info(),
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+            amount,
+        )?;
+
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.claimed += amount;
+
+        emit!(VestingClaimed {
+            token_info: vesting.token_info,
+            vesting: vesting.key(),
+            beneficiary: vesting.beneficiary,
+            amount,
+            claimed: vesting.claimed,
+        });
+
+        Ok(())
+    }
+
+    /// Ends a revocable schedule: what has vested stays claimable by the
+    /// beneficiary, the unvested rest goes to `refund`.
+    pub fn revoke_vesting<'info>(ctx: Context<'_, '_, 'info, 'info, RevokeVesting<'info>>) -> Result<()> {
+        let token_info = &ctx.accounts.token_info;
+        require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
+        require!(ctx.accounts.admin.key() == token_info.admin, ErrorCode::Unauthorized);
+        let vesting = &ctx.accounts.vesting;
+        require!(vesting.revocable && !vesting.revoked, ErrorCode::VestingNotRevocable);
+
+        let vested = vesting.vested(Clock::get()?.unix_timestamp);
+        let refunded = vesting.total_amount - vested;
+        if refunded > 0 {
+            release_vested(
+                vesting,
+                &ctx.accounts.escrow,
+                &ctx.accounts.mint,
+                ctx.accounts.refund.to_account_info(),
+                &ctx.accounts.token_program,
+                ctx.remaining_accounts,
+                refunded,
+            )?;
+        }
+
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.total_amount = vested;
+        vesting.revoked = true;
+
+        emit!(VestingRevoked {
+            token_info: vesting.token_info,
+            vesting: vesting.key(),
+            beneficiary: vesting.beneficiary,
+            vested,
+            refunded,
+        });
+
+        Ok(())
+    }
+
+    /// Points the jackpot share at token accounts owned by `owner`. Frozen
+    /// after `renounce_admin` or renouncing `Capability::Rates`.
+    pub fn set_jackpot_vault_owner(ctx: Context<SetJackpotVaultOwner>, owner: Pubkey) -> Result<()> {
+        let token_info = &mut ctx.accounts.token_info;
+        require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
+        require!(ctx.accounts.admin.key() == token_info.admin, ErrorCode::Unauthorized);
+        require!(!token_info.is_capability_renounced(Capability::Rates), ErrorCode::CapabilityRenounced);
+
+        token_info.jackpot_vault_owner = owner;
+
+        msg!("Jackpot vault owner set to {}", owner);
+
+        Ok(())
+    }
+
+    /// Adds `address` (a token account owner) or updates its flags. Frozen
+    /// after `renounce_admin` or renouncing `Capability::Exemptions`.
+    pub fn add_exemption(
+        ctx: Context<UpdateExemptions>,
+        address: Pubkey,
+        exempt_sender: bool,
+        exempt_recipient: bool,
+    ) -> Result<()> {
+        let token_info = &ctx.accounts.token_info;
+        require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
+        require!(ctx.accounts.admin.key() == token_info.admin, ErrorCode::Unauthorized);
+        require!(!token_info.is_capability_renounced(Capability::Exemptions), ErrorCode::CapabilityRenounced);
+        require!(exempt_sender || exempt_recipient, ErrorCode::InvalidExemption);
+
+        let list = &mut ctx.accounts.exemption_list;
+        let entry = Exemption {
+            address,
+            exempt_sender,
+            exempt_recipient,
+        };
+        match list.entries.iter_mut().find(|e| e.address == address) {
+            Some(existing) => *existing = entry,
+            None => {
+                require!(list.entries.len() < MAX_EXEMPTIONS, ErrorCode::ExemptionListFull);
+                list.entries.push(entry);
+            }
+        }
+
+        emit!(ExemptionAdded {
+            token_info: token_info.key(),
+            address,
+            exempt_sender,
+            exempt_recipient,
+        });
+
+        Ok(())
+    }
+
+    /// Frozen like `add_exemption`.
+    pub fn remove_exemption(ctx: Context<UpdateExemptions>, address: Pubkey) -> Result<()> {
+        let token_info = &ctx.accounts.token_info;
+        require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
+        require!(ctx.accounts.admin.key() == token_info.admin, ErrorCode::Unauthorized);
+        require!(!token_info.is_capability_renounced(Capability::Exemptions), ErrorCode::CapabilityRenounced);
+
+        let list = &mut ctx.accounts.exemption_list;
+        let index = list
+            .entries
+            .iter()
+            .position(|e| e.address == address)
+            .ok_or(ErrorCode::ExemptionNotFound)?;
+        list.entries.swap_remove(index);
+
+        emit!(ExemptionRemoved {
+            token_info: token_info.key(),
+            address,
+        });
+
+        Ok(())
+    }
+
+    /// Admin-only until renounce (full or `Capability::Rates`). Each
+    /// direction's total (creator + jackpot + burn) is capped at
+    /// `MAX_TAX_RATE_BPS`. In Token-2022 mode every transfer, buys and sells
+    /// included, pays the mint's transfer fee, so no rate can change.
+    pub fn set_tax_rates(
+        ctx: Context<SetTaxRates>,
+        direction: TaxDirection,
+        creator_fund_rate: u16,
+        jackpot_rate: u16,
+        burn_rate: u16,
+    ) -> Result<()> {
+        let token_info = &mut ctx.accounts.token_info;
+        require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
+        require!(ctx.accounts.admin.key() == token_info.admin, ErrorCode::Unauthorized);
+        require!(!token_info.is_capability_renounced(Capability::Rates), ErrorCode::CapabilityRenounced);
+        require!(!token_info.transfer_fee_mode, ErrorCode::TransferRateFixedByMint);
+        let split = TaxSplit {
+            creator_fund_rate,
+            jackpot_rate,
+            burn_rate,
+        };
+        require!(split.total() <= MAX_TAX_RATE_BPS, ErrorCode::TaxRateTooHigh);
+
+        match direction {
+            TaxDirection::Buy => token_info.buy_tax = split,
+            TaxDirection::Sell => token_info.sell_tax = split,
+            TaxDirection::Transfer => {
+                token_info.tax_rate = split.total();
+                token_info.creator_fund_rate = creator_fund_rate;
+                token_info.jackpot_rate = jackpot_rate;
+                token_info.burn_rate = burn_rate;
+            }
+        }
+
+        msg!(
+            "{:?} tax: {} bps ({} creator + {} jackpot + {} burn)",
+            direction,
+            split.total(),
+            creator_fund_rate,
+            jackpot_rate,
+            burn_rate
         );
-        token::transfer(creator_ctx, creator_tax)?;
-        
-        // Transfer jackpot tax to lottery pool
-        let jackpot_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.from.to_account_info(),
-                to: ctx.accounts.jackpot_pool.to_account_info(),
-                authority: ctx.accounts.authority.to_account_info(),
-            },
+
+        Ok(())
+    }
+
+    /// Registers an AMM pool's token account of the mint so transfers out of
+    /// it are taxed as buys and into it as sells. Part of `Capability::Rates`.
+    pub fn register_amm_pool(ctx: Context<UpdateAmmPools>, pool: Pubkey) -> Result<()> {
+        let token_info = &ctx.accounts.token_info;
+        require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
+        require!(ctx.accounts.admin.key() == token_info.admin, ErrorCode::Unauthorized);
+        require!(!token_info.is_capability_renounced(Capability::Rates), ErrorCode::CapabilityRenounced);
+
+        let pools = &mut ctx.accounts.amm_pools;
+        if !pools.contains(&pool) {
+            require!(pools.pools.len() < MAX_AMM_POOLS, ErrorCode::AmmPoolListFull);
+            pools.pools.push(pool);
+        }
+
+        msg!("AMM pool registered: {}", pool);
+
+        Ok(())
+    }
+
+    /// Part of `Capability::Rates`.
+    pub fn unregister_amm_pool(ctx: Context<UpdateAmmPools>, pool: Pubkey) -> Result<()> {
+        let token_info = &ctx.accounts.token_info;
+        require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
+        require!(ctx.accounts.admin.key() == token_info.admin, ErrorCode::Unauthorized);
+        require!(!token_info.is_capability_renounced(Capability::Rates), ErrorCode::CapabilityRenounced);
+
+        let pools = &mut ctx.accounts.amm_pools;
+        let index = pools
+            .pools
+            .iter()
+            .position(|p| *p == pool)
+            .ok_or(ErrorCode::AmmPoolNotFound)?;
+        pools.pools.swap_remove(index);
+
+        msg!("AMM pool unregistered: {}", pool);
+
+        Ok(())
+    }
+
+    /// Sets the anti-bot window: from `start_slot` (nothing but exempt
+    /// transfers move before it) for `window_slots`, transfers are capped at
+    /// `max_tx_amount`, recipients at `max_wallet_balance`, and a wallet can
+    /// receive once per `cooldown_slots`. Zero disables a limit. Registered
+    /// AMM pools are not held to the wallet limits. At most
+    /// `MAX_COOLDOWN_WALLETS` wallets can receive per `cooldown_slots`, so a
+    /// short cooldown keeps the launch open to many buyers. The window ends by
+    /// itself; admin-only and impossible after renounce (full or
+    /// `Capability::Pause`). The window is at
+    /// most `MAX_LAUNCH_WINDOW_SLOTS` long, starts within
+    /// `MAX_LAUNCH_LEAD_SLOTS` of now and is fixed once it has started.
+    pub fn configure_launch_protection(
+        ctx: Context<ConfigureLaunchProtection>,
+        start_slot: u64,
+        window_slots: u64,
+        max_tx_amount: u64,
+        max_wallet_balance: u64,
+        cooldown_slots: u64,
+    ) -> Result<()> {
+        let token_info = &mut ctx.accounts.token_info;
+        require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
+        require!(ctx.accounts.admin.key() == token_info.admin, ErrorCode::Unauthorized);
+        require!(!token_info.is_capability_renounced(Capability::Pause), ErrorCode::CapabilityRenounced);
+        let now = Clock::get()?.slot;
+        require!(!token_info.launch_protection.has_started(now), ErrorCode::LaunchWindowStarted);
+        require!(
+            window_slots > 0
+                && window_slots <= MAX_LAUNCH_WINDOW_SLOTS
+                && start_slot <= now.saturating_add(MAX_LAUNCH_LEAD_SLOTS),
+            ErrorCode::InvalidLaunchWindow
         );
-        token::transfer(jackpot_ctx, jackpot_tax)?;
-        
+        let end_slot = start_slot.checked_add(window_slots).ok_or(ErrorCode::InvalidLaunchWindow)?;
+
+        token_info.launch_protection = LaunchProtection {
+            enabled: true,
+            start_slot,
+            end_slot,
+            max_tx_amount,
+            max_wallet_balance,
+            cooldown_slots,
+        };
+
+        msg!("Launch protection: slots {}..{}", start_slot, end_slot);
+        msg!("Max tx: {}, max wallet: {}, cooldown: {} slots", max_tx_amount, max_wallet_balance, cooldown_slots);
+
+        Ok(())
+    }
+
+    /// Points the token metadata at a new JSON (e.g. after moving hosting).
+    /// On a Token-2022 mint the admin tops up the mint's rent if the metadata
+    /// grows; a legacy mint's Metaplex account is sized for any URI.
+    pub fn update_metadata_uri(ctx: Context<UpdateMetadataUri>, uri: String) -> Result<()> {
+        let token_info = &ctx.accounts.token_info;
+        require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
+        require!(ctx.accounts.admin.key() == token_info.admin, ErrorCode::Unauthorized);
+        require!(uri.len() <= MAX_URI_LEN, ErrorCode::MetadataTooLong);
+
+        let mint_key = token_info.mint;
+        let seeds: &[&[u8]] = &[TOKEN_INFO_SEED, mint_key.as_ref(), &[token_info.bump]];
+        if !token_info.transfer_fee_mode {
+            let (Some(metaplex_metadata), Some(metaplex_program)) =
+                (&ctx.accounts.metadata, &ctx.accounts.token_metadata_program)
+            else {
+                return err!(ErrorCode::MetadataAccountsRequired);
+            };
+            metaplex::update(
+                metaplex_program,
+                metaplex_metadata,
+                &token_info.to_account_info(),
+                &token_info.name,
+                &token_info.symbol,
+                &uri,
+                &[seeds],
+            )?;
+            msg!("Metadata URI set to {}", uri);
+            ctx.accounts.token_info.uri = uri;
+            return Ok(());
+        }
+
+        let mint = ctx.accounts.mint.to_account_info();
+        let new_len = {
+            let data = mint.try_borrow_data()?;
+            let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+            let mut metadata = state.get_variable_len_extension::<TokenMetadata>()?;
+            let old_size = metadata.tlv_size_of()?;
+            metadata.uri = uri.clone();
+            (data.len() + metadata.tlv_size_of()?).saturating_sub(old_size)
+        };
+        let shortfall = Rent::get()?.minimum_balance(new_len).saturating_sub(mint.lamports());
+        if shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: mint.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+
+        let token_program = ctx.accounts.token_program.to_account_info();
+        token_metadata_update_field(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TokenMetadataUpdateField {
+                    token_program_id: token_program,
+                    metadata: mint,
+                    update_authority: token_info.to_account_info(),
+                },
+                &[seeds],
+            ),
+            Field::Uri,
+            uri.clone(),
+        )?;
+
+        msg!("Metadata URI set to {}", uri);
+        ctx.accounts.token_info.uri = uri;
+
+        Ok(())
+    }
+
+    /// First half of an admin handoff (e.g. to a multisig): `new_admin`
+    /// takes over once it calls `accept_admin`. A later proposal replaces
+    /// this one.
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        let token_info = &mut ctx.accounts.token_info;
+        require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
+        require!(ctx.accounts.admin.key() == token_info.admin, ErrorCode::Unauthorized);
+
+        token_info.pending_admin = new_admin;
+
+        msg!("Admin handoff proposed to {}", new_admin);
+
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let token_info = &mut ctx.accounts.token_info;
+        require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
+        require!(
+            token_info.pending_admin != Pubkey::default() && ctx.accounts.new_admin.key() == token_info.pending_admin,
+            ErrorCode::Unauthorized
+        );
+
+        token_info.admin = token_info.pending_admin;
+        token_info.pending_admin = Pubkey::default();
+
+        msg!("Admin is now {}", token_info.admin);
+
+        Ok(())
+    }
+
+    /// Gives up one capability for good while keeping the others. Pause
+    /// cannot be given up while paused.
+    pub fn renounce_capability(ctx: Context<RenounceCapability>, capability: Capability) -> Result<()> {
+        let token_info = &mut ctx.accounts.token_info;
+        require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
+        require!(ctx.accounts.admin.key() == token_info.admin, ErrorCode::Unauthorized);
+        require!(!token_info.is_capability_renounced(capability), ErrorCode::CapabilityRenounced);
+
+        match capability {
+            Capability::Rates => token_info.rates_renounced = true,
+            Capability::Exemptions => token_info.exemptions_renounced = true,
+            Capability::Pause => {
+                require!(!token_info.is_paused, ErrorCode::RenounceWhilePaused);
+                token_info.pause_renounced = true;
+            }
+        }
+
+        msg!("{:?} capability renounced", capability);
+
         Ok(())
     }
 
+    /// Refuses while paused: nobody could lift the pause afterwards.
     pub fn renounce_admin(ctx: Context<RenounceAdmin>) -> Result<()> {
         let token_info = &mut ctx.accounts.token_info;
         require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
         require!(ctx.accounts.admin.key() == token_info.admin, ErrorCode::Unauthorized);
+        require!(!token_info.is_paused, ErrorCode::RenounceWhilePaused);
         
         token_info.is_renounced = true;
         token_info.admin = Pubkey::default();
+        token_info.pending_admin = Pubkey::default();
         
         msg!("Admin renounced! PEPEBALL is now fully decentralized!");
         
@@ -120,6 +1159,7 @@ pub mod pepball_token {
         let token_info = &mut ctx.accounts.token_info;
         require!(!token_info.is_renounced, ErrorCode::AlreadyRenounced);
         require!(ctx.accounts.admin.key() == token_info.admin, ErrorCode::Unauthorized);
+        require!(!token_info.is_capability_renounced(Capability::Pause), ErrorCode::CapabilityRenounced);
         
         token_info.is_paused = !token_info.is_paused;
         
@@ -133,41 +1173,657 @@ pub mod pepball_token {
     }
 }
 
+/// Moves `amount` out of a vesting escrow, signed by its schedule PDA.
+fn release_vested<'info>(
+    vesting: &Account<'info, VestingSchedule>,
+    escrow: &InterfaceAccount<'info, InterfaceTokenAccount>,
+    mint: &InterfaceAccount<'info, InterfaceMint>,
+    destination: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    hook_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<()> {
+    let seeds: &[&[u8]] = &[VESTING_SEED, vesting.token_info.as_ref(), vesting.beneficiary.as_ref(), &[vesting.bump]];
+    spl_token_2022::onchain::invoke_transfer_checked(
+        token_program.key,
+        escrow.to_account_info(),
+        mint.to_account_info(),
+        destination,
+        vesting.to_account_info(),
+        hook_accounts,
+        amount,
+        mint.decimals,
+        &[seeds],
+    )?;
+    Ok(())
+}
+
+/// Create the mint with `token_info` as mint authority and no freeze
+/// authority. Token-2022 mints also get TransferFee + TransferHook: fee,
+/// withdraw and hook authority are the fee PDA, no maximum fee.
+/// `metadata` is set in Token-2022 mode: the mint points at itself for its
+/// metadata and is funded for it; `token_metadata_initialize` writes it once
+/// the mint is initialized.
+fn create_mint(
+    accounts: &InitializeToken,
+    program_id: &Pubkey,
+    decimals: u8,
+    metadata: Option<&TokenMetadata>,
+) -> Result<()> {
+    let token_program = accounts.token_program.to_account_info();
+    let mint = accounts.mint.to_account_info();
+    let (space, metadata_len) = match metadata {
+        Some(metadata) => (
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+                ExtensionType::TransferFeeConfig,
+                ExtensionType::TransferHook,
+                ExtensionType::MetadataPointer,
+            ])?,
+            metadata.tlv_size_of()?,
+        ),
+        None => (spl_token_2022::state::Mint::LEN, 0),
+    };
+    system_program::create_account(
+        CpiContext::new(
+            accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: accounts.admin.to_account_info(),
+                to: mint.clone(),
+            },
+        ),
+        Rent::get()?.minimum_balance(space + metadata_len),
+        space as u64,
+        token_program.key,
+    )?;
+
+    if metadata.is_some() {
+        let (fee_authority, _) = Pubkey::find_program_address(&[FEE_AUTHORITY_SEED, mint.key.as_ref()], program_id);
+        transfer_fee_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                TransferFeeInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            Some(&fee_authority),
+            Some(&fee_authority),
+            TAX_RATE_BPS,
+            u64::MAX,
+        )?;
+        transfer_hook_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                TransferHookInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            Some(fee_authority),
+            Some(TRANSFER_HOOK_PROGRAM_ID),
+        )?;
+        metadata_pointer_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                MetadataPointerInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            None,
+            Some(*mint.key),
+        )?;
+    }
+
+    initialize_mint2(
+        CpiContext::new(token_program, InitializeMint2 { mint }),
+        decimals,
+        &accounts.token_info.key(),
+        None,
+    )
+}
+
 #[derive(Accounts)]
 pub struct InitializeToken<'info> {
     #[account(
         init,
         payer = admin,
-        space = 8 + TokenInfo::INIT_SPACE
+        space = 8 + TokenInfo::INIT_SPACE,
+        seeds = [TOKEN_INFO_SEED, mint.key().as_ref()],
+        bump
     )]
     pub token_info: Account<'info, TokenInfo>,
-    
-    #[account(mut)]
-    pub admin: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-}
 
-#[derive(Accounts)]
-pub struct TransferWithTax<'info> {
-    #[account(mut)]
-    pub token_info: Account<'info, TokenInfo>,
-    
-    #[account(mut)]
-    pub from: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ExemptionList::INIT_SPACE,
+        seeds = [EXEMPTION_LIST_SEED, token_info.key().as_ref()],
+        bump
+    )]
+    pub exemption_list: Account<'info, ExemptionList>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + AmmPools::INIT_SPACE,
+        seeds = [AMM_POOLS_SEED, token_info.key().as_ref()],
+        bump
+    )]
+    pub amm_pools: Account<'info, AmmPools>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + LaunchGuard::INIT_SPACE,
+        seeds = [LAUNCH_GUARD_SEED, token_info.key().as_ref()],
+        bump
+    )]
+    pub launch_guard: Account<'info, LaunchGuard>,
     
     #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
+    pub admin: Signer<'info>,
+
+    /// Fresh keypair, created here under `token_program`
+    #[account(mut)]
+    pub mint: Signer<'info>,
+
+    /// CHECK: any wallet or PDA; receives the full supply
+    pub distribution_owner: UncheckedAccount<'info>,
+
+    /// CHECK: `distribution_owner`'s associated token account, created here
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&distribution_owner.key(), &mint.key(), &token_program.key())
+    )]
+    pub distribution: UncheckedAccount<'info>,
+
+    /// SPL Token for the legacy mint, Token-2022 for the transfer-fee mint
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: `mint`'s Metaplex metadata PDA, created here for a legacy mint
+    /// (Metaplex checks the derivation); omitted in Token-2022 mode
+    #[account(mut)]
+    pub metadata: Option<UncheckedAccount<'info>>,
+    /// CHECK: Metaplex Token Metadata, legacy mints only
+    #[account(address = metaplex::TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawWithheldFees<'info> {
+    #[account(
+        seeds = [TOKEN_INFO_SEED, mint.key().as_ref()],
+        bump = token_info.bump,
+        mut,
+        constraint = token_info.transfer_fee_mode @ ErrorCode::TransferFeeModeRequired
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(seeds = [EXEMPTION_LIST_SEED, token_info.key().as_ref()], bump)]
+    pub exemption_list: Account<'info, ExemptionList>,
+
+    #[account(seeds = [AMM_POOLS_SEED, token_info.key().as_ref()], bump)]
+    pub amm_pools: Account<'info, AmmPools>,
+
+    /// CHECK: pepball-hook's cooldown table, writable for the hook
+    #[account(mut, address = crate::hook_launch_guard(&mint.key()))]
+    pub hook_launch_guard: UncheckedAccount<'info>,
+
+    /// CHECK: pepball-hook's checkpoints of the payouts' sender
+    #[account(mut, address = hook_checkpoints(&mint.key(), fee_authority.key))]
+    pub fee_authority_checkpoints: UncheckedAccount<'info>,
+
+    /// CHECK: pepball-hook's checkpoints of the jackpot payout's recipient
+    #[account(mut, address = hook_checkpoints(&mint.key(), &jackpot_pool.owner))]
+    pub jackpot_checkpoints: UncheckedAccount<'info>,
+
+    /// CHECK: pepball-hook's checkpoints of the creator payout's recipient
+    #[account(mut, address = hook_checkpoints(&mint.key(), &creator_fund.owner))]
+    pub creator_checkpoints: UncheckedAccount<'info>,
+
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    /// CHECK: PDA that signs the withdrawal and the payouts
+    #[account(seeds = [FEE_AUTHORITY_SEED, mint.key().as_ref()], bump)]
+    pub fee_authority: UncheckedAccount<'info>,
+
+    /// Staging account the withheld fees are withdrawn into before the split
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = fee_authority,
+        associated_token::token_program = token_program
+    )]
+    pub fee_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = jackpot_pool.owner == token_info.jackpot_vault_owner @ ErrorCode::InvalidTaxDestination
+    )]
+    pub jackpot_pool: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = creator_fund.owner == token_info.creator_fund_address @ ErrorCode::InvalidTaxDestination
+    )]
+    pub creator_fund: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: pepball-hook, invoked by Token-2022 on the payouts
+    #[account(address = TRANSFER_HOOK_PROGRAM_ID)]
+    pub hook_program: UncheckedAccount<'info>,
+
+    /// CHECK: the hook's account list for this mint
+    #[account(address = extra_account_metas(&mint.key()))]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Token-2022 once `token_info` is in fee mode; an interface so a legacy
+    /// token gets `TransferFeeModeRequired` rather than a program id error
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TransferWithTax<'info> {
+    #[account(mut, seeds = [TOKEN_INFO_SEED, token_info.mint.as_ref()], bump = token_info.bump)]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(seeds = [EXEMPTION_LIST_SEED, token_info.key().as_ref()], bump)]
+    pub exemption_list: Account<'info, ExemptionList>,
+
+    #[account(seeds = [AMM_POOLS_SEED, token_info.key().as_ref()], bump)]
+    pub amm_pools: Account<'info, AmmPools>,
+
+    #[account(mut, seeds = [LAUNCH_GUARD_SEED, token_info.key().as_ref()], bump)]
+    pub launch_guard: Account<'info, LaunchGuard>,
+
+    /// Supply shrinks by the burn share
+    #[account(mut, address = token_info.mint @ ErrorCode::MintMismatch)]
+    pub mint: Account<'info, Mint>,
     
+    #[account(mut, constraint = from.mint == token_info.mint @ ErrorCode::MintMismatch)]
+    pub from: Account<'info, TokenAccount>,
+    
+    #[account(mut, constraint = to.mint == token_info.mint @ ErrorCode::MintMismatch)]
+    pub to: Account<'info, TokenAccount>,
+
+    /// CHECK: `from`'s owner's `BalanceCheckpoints`; address checked when recorded
     #[account(mut)]
+    pub from_checkpoints: UncheckedAccount<'info>,
+
+    /// CHECK: `to`'s owner's `BalanceCheckpoints`; address checked when recorded
+    #[account(mut)]
+    pub to_checkpoints: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = creator_fund.mint == token_info.mint @ ErrorCode::MintMismatch,
+        constraint = creator_fund.owner == token_info.creator_fund_address @ ErrorCode::InvalidTaxDestination
+    )]
     pub creator_fund: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = jackpot_pool.mint == token_info.mint @ ErrorCode::MintMismatch,
+        constraint = jackpot_pool.owner == token_info.jackpot_vault_owner @ ErrorCode::InvalidTaxDestination
+    )]
     pub jackpot_pool: Account<'info, TokenAccount>,
     
+    /// Pays for checkpoints created on first use
+    #[account(mut)]
     pub authority: Signer<'info>,
     
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TransferWithTaxPermit<'info> {
+    #[account(mut, seeds = [TOKEN_INFO_SEED, token_info.mint.as_ref()], bump = token_info.bump)]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(seeds = [EXEMPTION_LIST_SEED, token_info.key().as_ref()], bump)]
+    pub exemption_list: Account<'info, ExemptionList>,
+
+    #[account(seeds = [AMM_POOLS_SEED, token_info.key().as_ref()], bump)]
+    pub amm_pools: Account<'info, AmmPools>,
+
+    #[account(mut, seeds = [LAUNCH_GUARD_SEED, token_info.key().as_ref()], bump)]
+    pub launch_guard: Account<'info, LaunchGuard>,
+
+    #[account(mut, address = token_info.mint @ ErrorCode::MintMismatch)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut, constraint = from.mint == token_info.mint @ ErrorCode::MintMismatch)]
+    pub from: Account<'info, TokenAccount>,
+
+    #[account(mut, constraint = to.mint == token_info.mint @ ErrorCode::MintMismatch)]
+    pub to: Account<'info, TokenAccount>,
+
+    /// CHECK: `from`'s owner's `BalanceCheckpoints`; address checked when recorded
+    #[account(mut)]
+    pub from_checkpoints: UncheckedAccount<'info>,
+
+    /// CHECK: `to`'s owner's `BalanceCheckpoints`; address checked when recorded
+    #[account(mut)]
+    pub to_checkpoints: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = creator_fund.mint == token_info.mint @ ErrorCode::MintMismatch,
+        constraint = creator_fund.owner == token_info.creator_fund_address @ ErrorCode::InvalidTaxDestination
+    )]
+    pub creator_fund: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = jackpot_pool.mint == token_info.mint @ ErrorCode::MintMismatch,
+        constraint = jackpot_pool.owner == token_info.jackpot_vault_owner @ ErrorCode::InvalidTaxDestination
+    )]
+    pub jackpot_pool: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA only; the token program checks it is `from`'s delegate
+    #[account(seeds = [PERMIT_AUTHORITY_SEED, token_info.key().as_ref()], bump)]
+    pub permit_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = 8 + PermitNonce::INIT_SPACE,
+        seeds = [PERMIT_NONCE_SEED, token_info.key().as_ref(), from.owner.as_ref()],
+        bump
+    )]
+    pub permit_nonce: Account<'info, PermitNonce>,
+
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: instructions sysvar, read for the ed25519 check
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BatchTransferWithTax<'info> {
+    #[account(mut, seeds = [TOKEN_INFO_SEED, token_info.mint.as_ref()], bump = token_info.bump)]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(seeds = [EXEMPTION_LIST_SEED, token_info.key().as_ref()], bump)]
+    pub exemption_list: Account<'info, ExemptionList>,
+
+    #[account(seeds = [AMM_POOLS_SEED, token_info.key().as_ref()], bump)]
+    pub amm_pools: Account<'info, AmmPools>,
+
+    #[account(mut, seeds = [LAUNCH_GUARD_SEED, token_info.key().as_ref()], bump)]
+    pub launch_guard: Account<'info, LaunchGuard>,
+
+    #[account(mut, address = token_info.mint @ ErrorCode::MintMismatch)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut, constraint = from.mint == token_info.mint @ ErrorCode::MintMismatch)]
+    pub from: Account<'info, TokenAccount>,
+
+    /// CHECK: `from`'s owner's `BalanceCheckpoints`; address checked when recorded
+    #[account(mut)]
+    pub from_checkpoints: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = creator_fund.mint == token_info.mint @ ErrorCode::MintMismatch,
+        constraint = creator_fund.owner == token_info.creator_fund_address @ ErrorCode::InvalidTaxDestination
+    )]
+    pub creator_fund: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = jackpot_pool.mint == token_info.mint @ ErrorCode::MintMismatch,
+        constraint = jackpot_pool.owner == token_info.jackpot_vault_owner @ ErrorCode::InvalidTaxDestination
+    )]
+    pub jackpot_pool: Account<'info, TokenAccount>,
+
+    /// Pays for checkpoints created on first use
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteTransfer<'info> {
+    #[account(seeds = [TOKEN_INFO_SEED, token_info.mint.as_ref()], bump = token_info.bump)]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(seeds = [EXEMPTION_LIST_SEED, token_info.key().as_ref()], bump)]
+    pub exemption_list: Account<'info, ExemptionList>,
+
+    #[account(seeds = [AMM_POOLS_SEED, token_info.key().as_ref()], bump)]
+    pub amm_pools: Account<'info, AmmPools>,
+
+    #[account(address = token_info.mint @ ErrorCode::MintMismatch)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(constraint = from.mint == token_info.mint @ ErrorCode::MintMismatch)]
+    pub from: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(constraint = to.mint == token_info.mint @ ErrorCode::MintMismatch)]
+    pub to: InterfaceAccount<'info, InterfaceTokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct CirculatingSupply<'info> {
+    #[account(seeds = [TOKEN_INFO_SEED, token_info.mint.as_ref()], bump = token_info.bump)]
+    pub token_info: Account<'info, TokenInfo>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVesting<'info> {
+    #[account(seeds = [TOKEN_INFO_SEED, mint.key().as_ref()], bump = token_info.bump)]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + VestingSchedule::INIT_SPACE,
+        seeds = [VESTING_SEED, token_info.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    pub vesting: Account<'info, VestingSchedule>,
+
+    /// CHECK: any wallet; the only signer that can claim
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = vesting,
+        associated_token::token_program = token_program
+    )]
+    pub escrow: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut, token::mint = mint, token::authority = funder, token::token_program = token_program)]
+    pub source: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub funder: Signer<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(seeds = [TOKEN_INFO_SEED, mint.key().as_ref()], bump = token_info.bump)]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(
+        mut,
+        seeds = [VESTING_SEED, token_info.key().as_ref(), beneficiary.key().as_ref()],
+        bump = vesting.bump
+    )]
+    pub vesting: Account<'info, VestingSchedule>,
+
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting,
+        associated_token::token_program = token_program
+    )]
+    pub escrow: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub destination: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    #[account(seeds = [TOKEN_INFO_SEED, mint.key().as_ref()], bump = token_info.bump)]
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(
+        mut,
+        seeds = [VESTING_SEED, token_info.key().as_ref(), vesting.beneficiary.as_ref()],
+        bump = vesting.bump
+    )]
+    pub vesting: Account<'info, VestingSchedule>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting,
+        associated_token::token_program = token_program
+    )]
+    pub escrow: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Receives the unvested tokens
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub refund: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMetadataUri<'info> {
+    #[account(mut, seeds = [TOKEN_INFO_SEED, mint.key().as_ref()], bump = token_info.bump)]
+    pub token_info: Account<'info, TokenInfo>,
+
+    /// Pays any rent top-up of the mint
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: legacy mints: `mint`'s Metaplex metadata, which only
+    /// `token_info` can update
+    #[account(mut)]
+    pub metadata: Option<UncheckedAccount<'info>>,
+    /// CHECK: Metaplex Token Metadata, legacy mints only
+    #[account(address = metaplex::TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct SetJackpotVaultOwner<'info> {
+    #[account(mut)]
+    pub token_info: Account<'info, TokenInfo>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateExemptions<'info> {
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(mut, seeds = [EXEMPTION_LIST_SEED, token_info.key().as_ref()], bump)]
+    pub exemption_list: Account<'info, ExemptionList>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetTaxRates<'info> {
+    #[account(mut)]
+    pub token_info: Account<'info, TokenInfo>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAmmPools<'info> {
+    pub token_info: Account<'info, TokenInfo>,
+
+    #[account(mut, seeds = [AMM_POOLS_SEED, token_info.key().as_ref()], bump)]
+    pub amm_pools: Account<'info, AmmPools>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureLaunchProtection<'info> {
+    #[account(mut)]
+    pub token_info: Account<'info, TokenInfo>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(mut)]
+    pub token_info: Account<'info, TokenInfo>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(mut)]
+    pub token_info: Account<'info, TokenInfo>,
+
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RenounceCapability<'info> {
+    #[account(mut)]
+    pub token_info: Account<'info, TokenInfo>,
+
+    pub admin: Signer<'info>,
 }
 
 #[derive(Accounts)]
@@ -189,9 +1845,9 @@ pub struct EmergencyPause<'info> {
 #[account]
 #[derive(InitSpace)]
 pub struct TokenInfo {
-    #[max_len(32)]
+    #[max_len(MAX_NAME_LEN)]
     pub name: String,
-    #[max_len(10)]
+    #[max_len(MAX_SYMBOL_LEN)]
     pub symbol: String,
     pub decimals: u8,
     pub total_supply: u64,
@@ -203,6 +1859,884 @@ pub struct TokenInfo {
     pub is_paused: bool,
     pub admin: Pubkey,
     pub min_transfer_amount: u64, // Minimum transfer to prevent dust attacks
+    pub mint: Pubkey, // The one mint this TokenInfo was created with
+    pub transfer_fee_mode: bool, // Mint is Token-2022 with TransferFee + TransferHook
+    pub bump: u8,
+    pub jackpot_vault_owner: Pubkey, // Jackpot share goes to token accounts owned by this
+    pub buy_tax: TaxSplit, // Out of a registered AMM pool
+    pub sell_tax: TaxSplit, // Into a registered AMM pool
+    pub launch_protection: LaunchProtection,
+    pub stats: TaxStats,
+    pub pending_admin: Pubkey, // Set by propose_admin until accept_admin
+    pub rates_renounced: bool, // Tax rates and AMM pools
+    pub exemptions_renounced: bool,
+    pub pause_renounced: bool,
+    #[max_len(MAX_URI_LEN)]
+    pub uri: String, // Off-chain metadata JSON; mirrored in the Token-2022 mint's metadata
+    pub burn_rate: u16, // Wallet-to-wallet share burned (0 at launch)
+}
+
+/// Admin powers that can be renounced one at a time.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Capability {
+    /// `set_tax_rates`, `register_amm_pool`, `unregister_amm_pool`,
+    /// `set_jackpot_vault_owner`
+    Rates,
+    /// `add_exemption`, `remove_exemption`
+    Exemptions,
+    /// `emergency_pause`, `configure_launch_protection`
+    Pause,
+}
+
+/// Lifetime totals in raw units. Volume and count cover `transfer_with_tax`;
+/// the tax totals also include Token-2022 fees paid out by
+/// `withdraw_withheld_fees`. `total_burned` is supply destroyed by the burn
+/// share.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct TaxStats {
+    pub transfer_count: u64,
+    pub total_volume: u128,
+    pub total_creator_tax: u64,
+    pub total_jackpot_tax: u64,
+    pub total_burned: u64,
+}
+
+impl TaxStats {
+    pub fn record_transfer(&mut self, amount: u64, creator_tax: u64, jackpot_tax: u64, burned: u64) {
+        self.record_transfers(1, amount, creator_tax, jackpot_tax, burned);
+    }
+
+    pub fn record_transfers(&mut self, count: u64, volume: u64, creator_tax: u64, jackpot_tax: u64, burned: u64) {
+        self.transfer_count = self.transfer_count.saturating_add(count);
+        self.total_volume = self.total_volume.saturating_add(volume as u128);
+        self.total_burned = self.total_burned.saturating_add(burned);
+        self.record_fees(creator_tax, jackpot_tax);
+    }
+
+    pub fn record_fees(&mut self, creator_tax: u64, jackpot_tax: u64) {
+        self.total_creator_tax = self.total_creator_tax.saturating_add(creator_tax);
+        self.total_jackpot_tax = self.total_jackpot_tax.saturating_add(jackpot_tax);
+    }
+}
+
+impl TokenInfo {
+    pub fn is_capability_renounced(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::Rates => self.rates_renounced,
+            Capability::Exemptions => self.exemptions_renounced,
+            Capability::Pause => self.pause_renounced,
+        }
+    }
+
+    /// Wallet-to-wallet transfers use `creator_fund_rate`/`jackpot_rate`/`burn_rate`.
+    pub fn tax_split(&self, direction: TaxDirection) -> TaxSplit {
+        match direction {
+            TaxDirection::Buy => self.buy_tax,
+            TaxDirection::Sell => self.sell_tax,
+            TaxDirection::Transfer => TaxSplit {
+                creator_fund_rate: self.creator_fund_rate,
+                jackpot_rate: self.jackpot_rate,
+                burn_rate: self.burn_rate,
+            },
+        }
+    }
+
+    /// Minted supply less everything the burn share has destroyed.
+    pub fn circulating_supply(&self) -> u64 {
+        self.total_supply.saturating_sub(self.stats.total_burned)
+    }
+}
+
+impl<'info> TransferWithTax<'info> {
+    fn taxed_move(&mut self) -> TaxedMove<'_, 'info> {
+        TaxedMove {
+            token_info: &mut self.token_info,
+            exemption_list: &self.exemption_list,
+            amm_pools: &self.amm_pools,
+            launch_guard: &mut self.launch_guard,
+            mint: &self.mint,
+            from: &self.from,
+            to: &self.to,
+            creator_fund: &self.creator_fund,
+            jackpot_pool: &self.jackpot_pool,
+            authority: self.authority.to_account_info(),
+            from_checkpoints: self.from_checkpoints.to_account_info(),
+            to_checkpoints: self.to_checkpoints.to_account_info(),
+            payer: self.authority.to_account_info(),
+            token_program: &self.token_program,
+            system_program: self.system_program.to_account_info(),
+        }
+    }
+}
+
+impl<'info> TransferWithTaxPermit<'info> {
+    fn taxed_move(&mut self) -> TaxedMove<'_, 'info> {
+        TaxedMove {
+            token_info: &mut self.token_info,
+            exemption_list: &self.exemption_list,
+            amm_pools: &self.amm_pools,
+            launch_guard: &mut self.launch_guard,
+            mint: &self.mint,
+            from: &self.from,
+            to: &self.to,
+            creator_fund: &self.creator_fund,
+            jackpot_pool: &self.jackpot_pool,
+            authority: self.permit_authority.to_account_info(),
+            from_checkpoints: self.from_checkpoints.to_account_info(),
+            to_checkpoints: self.to_checkpoints.to_account_info(),
+            payer: self.relayer.to_account_info(),
+            token_program: &self.token_program,
+            system_program: self.system_program.to_account_info(),
+        }
+    }
+}
+
+/// One taxed transfer, signed by the owner (`transfer_with_tax`) or by the
+/// permit delegate PDA (`transfer_with_tax_permit`).
+struct TaxedMove<'a, 'info> {
+    token_info: &'a mut Account<'info, TokenInfo>,
+    exemption_list: &'a Account<'info, ExemptionList>,
+    amm_pools: &'a Account<'info, AmmPools>,
+    launch_guard: &'a mut Account<'info, LaunchGuard>,
+    mint: &'a Account<'info, Mint>,
+    from: &'a Account<'info, TokenAccount>,
+    to: &'a Account<'info, TokenAccount>,
+    creator_fund: &'a Account<'info, TokenAccount>,
+    jackpot_pool: &'a Account<'info, TokenAccount>,
+    authority: AccountInfo<'info>,
+    from_checkpoints: AccountInfo<'info>,
+    to_checkpoints: AccountInfo<'info>,
+    /// Funds checkpoints created on first use
+    payer: AccountInfo<'info>,
+    token_program: &'a Program<'info, Token>,
+    system_program: AccountInfo<'info>,
+}
+
+impl<'info> TaxedMove<'_, 'info> {
+    /// Tax only lands in accounts of the bound mint owned by
+    /// `creator_fund_address` and `jackpot_vault_owner`; see
+    /// `transfer_with_tax` for exemptions, directions and launch limits.
+    fn execute(mut self, amount: u64, signer: &[&[&[u8]]]) -> Result<()> {
+        // CRITICAL FIX 1: Check if transfers are paused
+        require!(!self.token_info.is_paused, ErrorCode::TransfersPaused);
+
+        let exemptions = self.exemption_list;
+        if exemptions.is_exempt_sender(&self.from.owner) || exemptions.is_exempt_recipient(&self.to.owner) {
+            msg!("Exempt transfer: {} tokens", amount);
+            self.transfer(self.to.to_account_info(), amount, signer)?;
+            return self.update_checkpoints();
+        }
+        
+        // CRITICAL FIX 2: Check minimum transfer amount
+        require!(amount >= self.token_info.min_transfer_amount, ErrorCode::AmountTooSmall);
+        
+        let pools = self.amm_pools;
+        let direction = if pools.contains(&self.from.key()) {
+            TaxDirection::Buy
+        } else if pools.contains(&self.to.key()) {
+            TaxDirection::Sell
+        } else {
+            TaxDirection::Transfer
+        };
+        let split = self.token_info.tax_split(direction);
+
+        // Calculate taxes; the rounding remainder goes to the jackpot
+        let quote = split.quote(amount);
+        let (creator_tax, jackpot_tax, burn_tax) = (quote.creator_tax, quote.jackpot_tax, quote.burn_tax);
+        
+        // CRITICAL FIX 3: Validate tax calculation
+        require!(quote.net_amount > 0, ErrorCode::InvalidTaxCalculation);
+        
+        let transfer_amount = quote.net_amount;
+        self.enforce_launch_protection(direction, amount, transfer_amount)?;
+        
+        msg!("Transfer ({:?}): {} tokens", direction, amount);
+        msg!("Creator Tax: {} tokens ({} bps)", creator_tax, split.creator_fund_rate);
+        msg!("Jackpot Tax: {} tokens ({} bps)", jackpot_tax, split.jackpot_rate);
+        msg!("Burn Tax: {} tokens ({} bps)", burn_tax, split.burn_rate);
+        msg!("Net Transfer: {} tokens", transfer_amount);
+        
+        // Transfer to recipient
+        self.transfer(self.to.to_account_info(), transfer_amount, signer)?;
+        
+        // Transfer creator fund tax to Matt Furie
+        self.transfer(self.creator_fund.to_account_info(), creator_tax, signer)?;
+        
+        // Transfer jackpot tax to lottery pool
+        self.transfer(self.jackpot_pool.to_account_info(), jackpot_tax, signer)?;
+
+        // Burn share leaves the supply through the token program
+        if burn_tax > 0 {
+            self.burn(burn_tax, signer)?;
+        }
+
+        self.token_info.stats.record_transfer(amount, creator_tax, jackpot_tax, burn_tax);
+        emit!(TaxedTransfer {
+            token_info: self.token_info.key(),
+            from: self.from.key(),
+            to: self.to.key(),
+            direction,
+            amount,
+            net_amount: transfer_amount,
+            creator_tax,
+            jackpot_tax,
+            burn_tax,
+        });
+        
+        self.update_checkpoints()
+    }
+
+    /// Records the post-transfer balances in the sender's and recipient's
+    /// `BalanceCheckpoints`.
+    fn update_checkpoints(&self) -> Result<()> {
+        for (account, checkpoints) in [(self.from, &self.from_checkpoints), (self.to, &self.to_checkpoints)] {
+            record_checkpoint(
+                self.token_info,
+                checkpoints,
+                &account.to_account_info(),
+                &self.payer,
+                &self.system_program,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn transfer(&self, to: AccountInfo<'info>, amount: u64, signer: &[&[&[u8]]]) -> Result<()> {
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.from.to_account_info(),
+                    to,
+                    authority: self.authority.clone(),
+                },
+                signer,
+            ),
+            amount,
+        )
+    }
+
+    fn burn(&self, amount: u64, signer: &[&[&[u8]]]) -> Result<()> {
+        token::burn(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.mint.to_account_info(),
+                    from: self.from.to_account_info(),
+                    authority: self.authority.clone(),
+                },
+                signer,
+            ),
+            amount,
+        )
+    }
+
+    fn enforce_launch_protection(&mut self, direction: TaxDirection, amount: u64, net: u64) -> Result<()> {
+        // Pools take every sell; the wallet limits are for the buyer side.
+        let recipient = (direction != TaxDirection::Sell).then(|| (self.to.owner, self.to.amount.saturating_add(net)));
+        enforce_launch_protection(self.token_info, self.launch_guard, amount, recipient)
+    }
+}
+
+/// The instruction before the current one must be the ed25519 precompile
+/// with a single signature by `signer` over exactly `message`, all three
+/// inline in its data. The precompile already failed the transaction if the
+/// signature itself is wrong.
+fn verify_permit_signature(instructions: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let current = load_current_index_checked(instructions)? as usize;
+    require!(current > 0, ErrorCode::InvalidPermit);
+    let ix = load_instruction_at_checked(current - 1, instructions)?;
+    require!(ix.program_id == ed25519_program::ID, ErrorCode::InvalidPermit);
+
+    // [count, padding, 7 x u16 offsets: signature, its ix, public key, its ix,
+    // message, message size, its ix]; u16::MAX = this instruction.
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, ErrorCode::InvalidPermit);
+    let offset = |i: usize| u16::from_le_bytes([data[2 + 2 * i], data[3 + 2 * i]]) as usize;
+    let inline = [1, 3, 6].iter().all(|&i| offset(i) == u16::MAX as usize);
+    let public_key = data.get(offset(2)..offset(2) + 32);
+    let signed = data.get(offset(4)..offset(4) + offset(5));
+    require!(
+        inline && public_key == Some(signer.as_ref()) && signed == Some(message),
+        ErrorCode::InvalidPermit
+    );
+    Ok(())
+}
+
+/// Records `token_account`'s balance in its owner's `BalanceCheckpoints`,
+/// created on first use at `payer`'s expense. `checkpoints` must be that
+/// PDA; only the owner's associated token account is tracked, so any other
+/// account leaves it untouched.
+fn record_checkpoint<'info>(
+    token_info: &Account<'info, TokenInfo>,
+    checkpoints: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let token_info_key = token_info.key();
+    let owner = token::accessor::authority(token_account)?;
+    let (address, bump) =
+        Pubkey::find_program_address(&[CHECKPOINTS_SEED, token_info_key.as_ref(), owner.as_ref()], &crate::ID);
+    require_keys_eq!(checkpoints.key(), address, ErrorCode::InvalidCheckpointAccount);
+    if token_account.key() != get_associated_token_address_with_program_id(&owner, &token_info.mint, &token::ID) {
+        return Ok(());
+    }
+
+    let mut history = if checkpoints.owner == &crate::ID {
+        BalanceCheckpoints::try_deserialize(&mut &checkpoints.try_borrow_data()?[..])?
+    } else {
+        let seeds: &[&[u8]] = &[CHECKPOINTS_SEED, token_info_key.as_ref(), owner.as_ref(), &[bump]];
+        create_pda(payer, checkpoints, system_program, 8 + BalanceCheckpoints::INIT_SPACE, &[seeds])?;
+        BalanceCheckpoints {
+            token_info: token_info_key,
+            owner,
+            history: CheckpointHistory::default(),
+            bump,
+        }
+    };
+    history.history.record(Clock::get()?.slot, token::accessor::amount(token_account)?);
+    history.try_serialize(&mut &mut checkpoints.try_borrow_mut_data()?[..])
+}
+
+/// Allocates `account` for this program, as Anchor's `init` does: a PDA
+/// someone already sent lamports to is topped up instead of created.
+fn create_pda<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let lamports = account.lamports();
+    if lamports == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                signer,
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        );
+    }
+    if lamports < rent {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent - lamports,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate {
+                account_to_allocate: account.clone(),
+            },
+            signer,
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Assign {
+                account_to_assign: account.clone(),
+            },
+            signer,
+        ),
+        &crate::ID,
+    )
+}
+
+/// `recipient` is the recipient's owner and balance after the transfer, or
+/// `None` when it is a pool held to the max transaction only. Past the window
+/// the config switches itself off.
+fn enforce_launch_protection(
+    token_info: &mut TokenInfo,
+    guard: &mut LaunchGuard,
+    amount: u64,
+    recipient: Option<(Pubkey, u64)>,
+) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let protection = token_info.launch_protection;
+    if !protection.is_active(slot) {
+        if protection.enabled {
+            token_info.launch_protection.enabled = false;
+            msg!("Launch protection window ended");
+        }
+        return Ok(());
+    }
+
+    protection.check(slot, amount, recipient.map(|(_, balance)| balance)).map_err(launch_error)?;
+    if let Some((owner, _)) = recipient {
+        guard.cooldowns.touch(owner, slot, protection.cooldown_slots).map_err(launch_error)?;
+    }
+    Ok(())
+}
+
+fn launch_error(violation: LaunchViolation) -> Error {
+    match violation {
+        LaunchViolation::NotStarted => ErrorCode::LaunchNotStarted,
+        LaunchViolation::MaxTransaction => ErrorCode::MaxTransactionExceeded,
+        LaunchViolation::MaxWallet => ErrorCode::MaxWalletExceeded,
+        LaunchViolation::Cooldown => ErrorCode::WalletCooldown,
+        LaunchViolation::CooldownTableFull => ErrorCode::CooldownTableFull,
+    }
+    .into()
+}
+
+/// Anti-bot limits for the launch window `start_slot..end_slot`. Shared with
+/// pepball-hook, which enforces the same limits on Token-2022 transfers.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct LaunchProtection {
+    pub enabled: bool,
+    pub start_slot: u64,
+    pub end_slot: u64,
+    pub max_tx_amount: u64,      // 0 = no limit
+    pub max_wallet_balance: u64, // 0 = no limit
+    pub cooldown_slots: u64,     // 0 = no cooldown
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LaunchViolation {
+    NotStarted,
+    MaxTransaction,
+    MaxWallet,
+    Cooldown,
+    CooldownTableFull,
+}
+
+impl LaunchProtection {
+    /// Also true before `start_slot`: transfers are held until the launch.
+    pub fn is_active(&self, slot: u64) -> bool {
+        self.enabled && slot < self.end_slot
+    }
+
+    /// A window was configured and `slot` is at or past its start; stays
+    /// true after the window ends and switches off.
+    pub fn has_started(&self, slot: u64) -> bool {
+        self.end_slot > 0 && slot >= self.start_slot
+    }
+
+    /// `recipient_balance` is the recipient's balance after the transfer, or
+    /// `None` when the recipient is not held to the wallet cap.
+    pub fn check(&self, slot: u64, amount: u64, recipient_balance: Option<u64>) -> std::result::Result<(), LaunchViolation> {
+        if slot < self.start_slot {
+            return Err(LaunchViolation::NotStarted);
+        }
+        if self.max_tx_amount > 0 && amount > self.max_tx_amount {
+            return Err(LaunchViolation::MaxTransaction);
+        }
+        match recipient_balance {
+            Some(balance) if self.max_wallet_balance > 0 && balance > self.max_wallet_balance => {
+                Err(LaunchViolation::MaxWallet)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Last receive slot of wallets still in their cooldown, bounded at
+/// `MAX_COOLDOWN_WALLETS`. A full table fails closed: evicting a live entry
+/// would let a wallet cycling through others shake off its own cooldown.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct CooldownTable {
+    #[max_len(MAX_COOLDOWN_WALLETS)]
+    pub entries: Vec<WalletCooldown>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct WalletCooldown {
+    pub wallet: Pubkey,
+    pub last_slot: u64,
+}
+
+impl CooldownTable {
+    /// Records `wallet` at `slot`, failing if it was recorded less than
+    /// `cooldown` slots ago or if every entry is still cooling down.
+    pub fn touch(&mut self, wallet: Pubkey, slot: u64, cooldown: u64) -> std::result::Result<(), LaunchViolation> {
+        if cooldown == 0 {
+            return Ok(());
+        }
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.wallet == wallet) {
+            if slot < entry.last_slot.saturating_add(cooldown) {
+                return Err(LaunchViolation::Cooldown);
+            }
+            entry.last_slot = slot;
+            return Ok(());
+        }
+        self.entries.retain(|e| slot < e.last_slot.saturating_add(cooldown));
+        if self.entries.len() >= MAX_COOLDOWN_WALLETS {
+            return Err(LaunchViolation::CooldownTableFull);
+        }
+        self.entries.push(WalletCooldown { wallet, last_slot: slot });
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchGuard {
+    pub token_info: Pubkey,
+    pub cooldowns: CooldownTable,
+}
+
+/// Creator/jackpot/burn rates of one direction, in basis points.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct TaxSplit {
+    pub creator_fund_rate: u16,
+    pub jackpot_rate: u16,
+    pub burn_rate: u16,
+}
+
+impl TaxSplit {
+    pub fn launch() -> Self {
+        Self {
+            creator_fund_rate: CREATOR_FUND_RATE_BPS,
+            jackpot_rate: JACKPOT_RATE_BPS,
+            burn_rate: 0,
+        }
+    }
+
+    pub fn total(&self) -> u16 {
+        self.creator_fund_rate
+            .saturating_add(self.jackpot_rate)
+            .saturating_add(self.burn_rate)
+    }
+
+    /// `amount * total / 10000`, floored.
+    pub fn total_tax(&self, amount: u64) -> u64 {
+        bps_of(amount, self.total())
+    }
+
+    pub fn creator_tax(&self, amount: u64) -> u64 {
+        bps_of(amount, self.creator_fund_rate)
+    }
+
+    pub fn burn_tax(&self, amount: u64) -> u64 {
+        bps_of(amount, self.burn_rate)
+    }
+
+    /// The total tax is floored once; the creator and burn shares are
+    /// floored and the remainder of the rounding goes to the jackpot.
+    pub fn quote(&self, amount: u64) -> TaxQuote {
+        let total_tax = self.total_tax(amount);
+        let creator_tax = self.creator_tax(amount);
+        let burn_tax = self.burn_tax(amount);
+        TaxQuote {
+            net_amount: amount - total_tax,
+            creator_tax,
+            jackpot_tax: total_tax - creator_tax - burn_tax,
+            burn_tax,
+        }
+    }
+
+    /// Splits an already-collected Token-2022 `fee` by the creator/jackpot
+    /// ratio, remainder to the jackpot. Fees cannot be burned from the
+    /// withheld balance, so `burn_rate` is left out.
+    pub fn split_fee(&self, fee: u64) -> (u64, u64) {
+        let total = self.creator_fund_rate.saturating_add(self.jackpot_rate);
+        let creator = if total == 0 {
+            0
+        } else {
+            (fee as u128 * self.creator_fund_rate as u128 / total as u128) as u64
+        };
+        (creator, fee - creator)
+    }
+}
+
+/// Rates are capped at `MAX_TAX_RATE_BPS`, so this stays below `amount`.
+fn bps_of(amount: u64, bps: u16) -> u64 {
+    (amount as u128 * bps as u128 / 10_000) as u64
+}
+
+/// One transfer's tax, in raw units.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TaxQuote {
+    pub net_amount: u64,
+    pub creator_tax: u64,
+    pub jackpot_tax: u64,
+    pub burn_tax: u64,
+}
+
+/// Return data of `quote_transfer`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferQuote {
+    pub direction: TaxDirection,
+    pub exempt: bool,
+    pub amount: u64,
+    pub net_amount: u64,
+    pub creator_tax: u64,
+    pub jackpot_tax: u64,
+    pub burn_tax: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaxDirection {
+    Buy,
+    Sell,
+    Transfer,
+}
+
+/// One beneficiary's locked allocation; the tokens sit in the associated
+/// token account of this PDA until claimed.
+#[account]
+#[derive(InitSpace)]
+pub struct VestingSchedule {
+    pub token_info: Pubkey,
+    pub beneficiary: Pubkey,
+    pub category: VestingCategory,
+    pub total_amount: u64, // Escrowed at creation; cut to the vested amount on revoke
+    pub claimed: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+    pub revocable: bool,
+    pub revoked: bool,
+    pub bump: u8,
+}
+
+impl VestingSchedule {
+    /// Nothing before the cliff, then linear from start to end.
+    pub fn vested(&self, now: i64) -> u64 {
+        if self.revoked || now >= self.end_ts {
+            return self.total_amount;
+        }
+        if now < self.cliff_ts {
+            return 0;
+        }
+        let elapsed = (now as i128 - self.start_ts as i128) as u128;
+        let duration = (self.end_ts as i128 - self.start_ts as i128) as u128;
+        (self.total_amount as u128 * elapsed / duration) as u64
+    }
+
+    pub fn claimable(&self, now: i64) -> u64 {
+        self.vested(now).saturating_sub(self.claimed)
+    }
+}
+
+/// What a vesting allocation was promised for in the launch materials.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum VestingCategory {
+    Team,
+    CreatorFund,
+    Marketing,
+}
+
+/// Next permit nonce of one owner; every accepted permit bumps it.
+#[account]
+#[derive(InitSpace)]
+pub struct PermitNonce {
+    pub nonce: u64,
+    pub bump: u8,
+}
+
+/// Balance history of one wallet's associated token account.
+#[account]
+#[derive(InitSpace)]
+pub struct BalanceCheckpoints {
+    pub token_info: Pubkey,
+    pub owner: Pubkey,
+    pub history: CheckpointHistory,
+    pub bump: u8,
+}
+
+/// Checkpoints oldest first, at most one per `CHECKPOINT_INTERVAL_SLOTS`
+/// interval: each holds from its slot until the next one. Records cannot
+/// push out more than one interval each, so nobody can evict the history a
+/// round reads by recording often. Shared with pepball-hook.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct CheckpointHistory {
+    #[max_len(MAX_CHECKPOINTS)]
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct Checkpoint {
+    /// First record in the interval
+    pub slot: u64,
+    /// Latest balance recorded
+    pub balance: u64,
+    /// Lowest balance recorded in the interval
+    pub low: u64,
+}
+
+impl CheckpointHistory {
+    /// Merges into the checkpoint of `slot`'s interval; an unchanged balance
+    /// in a later interval adds nothing.
+    pub fn record(&mut self, slot: u64, balance: u64) {
+        match self.checkpoints.last_mut() {
+            Some(last) if same_interval(last.slot, slot) => {
+                last.balance = balance;
+                last.low = last.low.min(balance);
+            }
+            Some(last) if last.balance == balance => {}
+            _ => {
+                if self.checkpoints.len() == MAX_CHECKPOINTS {
+                    self.checkpoints.remove(0);
+                }
+                self.checkpoints.push(Checkpoint {
+                    slot,
+                    balance,
+                    low: balance,
+                });
+            }
+        }
+    }
+
+    /// Balance as of `slot`. Within the interval of a checkpoint, where the
+    /// records are merged, that is its lowest balance. Fails for a slot
+    /// before the kept history, where the balance is unknown.
+    pub fn balance_at(&self, slot: u64) -> Result<u64> {
+        let checkpoint = self
+            .checkpoints
+            .iter()
+            .rev()
+            .find(|checkpoint| checkpoint.slot <= slot)
+            .ok_or(ErrorCode::CheckpointHistoryTooShort)?;
+        Ok(if same_interval(checkpoint.slot, slot) {
+            checkpoint.low
+        } else {
+            checkpoint.balance
+        })
+    }
+
+    /// Lowest balance over `start_slot..=end_slot`: what was held through
+    /// the whole round, so tokens moved in just for the snapshot don't count.
+    pub fn min_balance(&self, start_slot: u64, end_slot: u64) -> Result<u64> {
+        Ok(self
+            .checkpoints
+            .iter()
+            .filter(|checkpoint| checkpoint.slot > start_slot && checkpoint.slot <= end_slot)
+            .map(|checkpoint| checkpoint.low)
+            .fold(self.balance_at(start_slot)?, u64::min))
+    }
+}
+
+fn same_interval(a: u64, b: u64) -> bool {
+    a / CHECKPOINT_INTERVAL_SLOTS == b / CHECKPOINT_INTERVAL_SLOTS
+}
+
+/// Token accounts of registered AMM pools; they decide buy/sell direction.
+#[account]
+#[derive(InitSpace)]
+pub struct AmmPools {
+    pub token_info: Pubkey,
+    #[max_len(MAX_AMM_POOLS)]
+    pub pools: Vec<Pubkey>,
+}
+
+impl AmmPools {
+    pub fn contains(&self, pool: &Pubkey) -> bool {
+        self.pools.contains(pool)
+    }
+}
+
+/// Token account owners that skip the tax when sending and/or receiving.
+#[account]
+#[derive(InitSpace)]
+pub struct ExemptionList {
+    pub token_info: Pubkey,
+    #[max_len(MAX_EXEMPTIONS)]
+    pub entries: Vec<Exemption>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct Exemption {
+    pub address: Pubkey,
+    pub exempt_sender: bool,
+    pub exempt_recipient: bool,
+}
+
+impl ExemptionList {
+    pub fn is_exempt_sender(&self, owner: &Pubkey) -> bool {
+        self.entries.iter().any(|e| e.exempt_sender && e.address == *owner)
+    }
+
+    pub fn is_exempt_recipient(&self, owner: &Pubkey) -> bool {
+        self.entries.iter().any(|e| e.exempt_recipient && e.address == *owner)
+    }
+}
+
+#[event]
+pub struct TaxedTransfer {
+    pub token_info: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub direction: TaxDirection,
+    pub amount: u64,
+    pub net_amount: u64,
+    pub creator_tax: u64,
+    pub jackpot_tax: u64,
+    pub burn_tax: u64,
+}
+
+#[event]
+pub struct BatchTaxedTransfer {
+    pub token_info: Pubkey,
+    pub from: Pubkey,
+    pub recipients: u8,
+    pub amount: u64,
+    pub net_amount: u64,
+    pub creator_tax: u64,
+    pub jackpot_tax: u64,
+    pub burn_tax: u64,
+}
+
+#[event]
+pub struct VestingCreated {
+    pub token_info: Pubkey,
+    pub vesting: Pubkey,
+    pub beneficiary: Pubkey,
+    pub category: VestingCategory,
+    pub amount: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+    pub revocable: bool,
+}
+
+#[event]
+pub struct VestingClaimed {
+    pub token_info: Pubkey,
+    pub vesting: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub claimed: u64,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub token_info: Pubkey,
+    pub vesting: Pubkey,
+    pub beneficiary: Pubkey,
+    pub vested: u64,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct ExemptionAdded {
+    pub token_info: Pubkey,
+    pub address: Pubkey,
+    pub exempt_sender: bool,
+    pub exempt_recipient: bool,
+}
+
+#[event]
+pub struct ExemptionRemoved {
+    pub token_info: Pubkey,
+    pub address: Pubkey,
 }
 
 #[error_code]
@@ -217,5 +2751,71 @@ pub enum ErrorCode {
     AmountTooSmall,
     #[msg("Invalid tax calculation")]
     InvalidTaxCalculation,
+    #[msg("Token is not in Token-2022 transfer-fee mode")]
+    TransferFeeModeRequired,
+    #[msg("Tax destination does not match the configured owner")]
+    InvalidTaxDestination,
+    #[msg("No withheld fees to withdraw")]
+    NoWithheldFees,
+    #[msg("Total supply does not fit in u64 at these decimals")]
+    InvalidDecimals,
+    #[msg("Token account is not for the token's mint")]
+    MintMismatch,
+    #[msg("Exemption must cover sending, receiving or both")]
+    InvalidExemption,
+    #[msg("Exemption list is full")]
+    ExemptionListFull,
+    #[msg("Address is not in the exemption list")]
+    ExemptionNotFound,
+    #[msg("Tax rate exceeds the cap")]
+    TaxRateTooHigh,
+    #[msg("Tax rates are the Token-2022 transfer fee and cannot change")]
+    TransferRateFixedByMint,
+    #[msg("AMM pool list is full")]
+    AmmPoolListFull,
+    #[msg("Pool is not registered")]
+    AmmPoolNotFound,
+    #[msg("Batch needs 1 to 8 distinct recipients, one per amount")]
+    InvalidBatch,
+    #[msg("Launch window must be 1 to MAX_LAUNCH_WINDOW_SLOTS long and start within MAX_LAUNCH_LEAD_SLOTS")]
+    InvalidLaunchWindow,
+    #[msg("Trading has not started")]
+    LaunchNotStarted,
+    #[msg("Amount exceeds the launch max transaction")]
+    MaxTransactionExceeded,
+    #[msg("Recipient would exceed the launch max wallet")]
+    MaxWalletExceeded,
+    #[msg("Recipient is in its launch cooldown")]
+    WalletCooldown,
+    #[msg("Cannot renounce while transfers are paused")]
+    RenounceWhilePaused,
+    #[msg("Capability has been renounced")]
+    CapabilityRenounced,
+    #[msg("Token name, symbol or metadata URI too long")]
+    MetadataTooLong,
+    #[msg("Vesting needs an amount and start <= cliff <= end, start < end")]
+    InvalidVestingSchedule,
+    #[msg("Nothing vested to claim")]
+    NothingVested,
+    #[msg("Vesting is not revocable or already revoked")]
+    VestingNotRevocable,
+    #[msg("Permit needs the owner's ed25519 signature over its message just before it")]
+    InvalidPermit,
+    #[msg("Permit has expired")]
+    PermitExpired,
+    #[msg("Permit nonce is not the owner's next nonce")]
+    InvalidPermitNonce,
+    #[msg("Checkpoints track the owner's associated token account of this mint")]
+    InvalidCheckpointAccount,
+    #[msg("Launch window has started and can no longer change")]
+    LaunchWindowStarted,
+    #[msg("Batch transfers cannot pay a registered AMM pool")]
+    BatchToAmmPool,
+    #[msg("Slot is before the kept checkpoint history")]
+    CheckpointHistoryTooShort,
+    #[msg("Legacy mints need the Metaplex metadata account and program")]
+    MetadataAccountsRequired,
+    #[msg("Every launch cooldown slot is taken; retry once one ends")]
+    CooldownTableFull,
 }
 
@@ -0,0 +1,93 @@
+//! Metaplex Token Metadata for legacy SPL mints, which have no metadata
+//! extension: `initialize_token` creates the metadata account with
+//! `token_info` as update authority and `update_metadata_uri` rewrites it.
+//! Instructions are built by hand; only the fungible fields are set.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+/// Metadata PDA under the Metaplex program: `["metadata", program, mint]`
+pub const METADATA_SEED: &[u8] = b"metadata";
+
+const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+const UPDATE_METADATA_ACCOUNT_V2: u8 = 15;
+
+/// Name, symbol and URI as a `DataV2` with no royalties, creators,
+/// collection or uses.
+fn data_v2(data: &mut Vec<u8>, name: &str, symbol: &str, uri: &str) {
+    for field in [name, symbol, uri] {
+        data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        data.extend_from_slice(field.as_bytes());
+    }
+    data.extend_from_slice(&0u16.to_le_bytes()); // seller_fee_basis_points
+    data.extend_from_slice(&[0, 0, 0]); // creators, collection, uses: None
+}
+
+/// `CreateMetadataAccountV3`, mutable, with `token_info` signing as both
+/// mint authority and update authority.
+#[allow(clippy::too_many_arguments)]
+pub fn create<'info>(
+    program: &AccountInfo<'info>,
+    metadata: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    name: &str,
+    symbol: &str,
+    uri: &str,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = vec![CREATE_METADATA_ACCOUNT_V3];
+    data_v2(&mut data, name, symbol, uri);
+    data.push(true as u8); // is_mutable
+    data.push(0); // collection_details: None
+
+    let ix = Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(metadata.key(), false),
+            AccountMeta::new_readonly(mint.key(), false),
+            AccountMeta::new_readonly(token_info.key(), true),
+            AccountMeta::new(payer.key(), true),
+            AccountMeta::new_readonly(token_info.key(), true),
+            AccountMeta::new_readonly(system_program.key(), false),
+        ],
+        data,
+    };
+    invoke_signed(
+        &ix,
+        &[metadata.clone(), mint.clone(), token_info.clone(), payer.clone(), system_program.clone(), program.clone()],
+        signer,
+    )?;
+    Ok(())
+}
+
+/// `UpdateMetadataAccountV2` rewriting the data; authority, primary sale and
+/// mutability are left as they are.
+pub fn update<'info>(
+    program: &AccountInfo<'info>,
+    metadata: &AccountInfo<'info>,
+    token_info: &AccountInfo<'info>,
+    name: &str,
+    symbol: &str,
+    uri: &str,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = vec![UPDATE_METADATA_ACCOUNT_V2, 1];
+    data_v2(&mut data, name, symbol, uri);
+    data.extend_from_slice(&[0, 0, 0]); // update_authority, primary_sale_happened, is_mutable: None
+
+    let ix = Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(metadata.key(), false),
+            AccountMeta::new_readonly(token_info.key(), true),
+        ],
+        data,
+    };
+    invoke_signed(&ix, &[metadata.clone(), token_info.clone(), program.clone()], signer)?;
+    Ok(())
+}
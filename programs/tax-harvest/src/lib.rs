@@ -1,10 +1,19 @@
 //! Locked harvest: the only thing this program can do with the tax vault
 //! is swap token -> SOL and send SOL to the configured jackpot address.
-//! Tax is auto-sent to the vault by pepball-token (or Token-2022); this program
-//! only converts it to SOL for the jackpot.
+//! Tax is auto-sent to the vault by pepball-token; this program only converts
+//! it to SOL for the jackpot, through the DEX adapters in `swap`. Legacy SPL
+//! Token vaults only: the adapters cannot move Token-2022 (or run its hook).
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint};
+use anchor_lang::system_program;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Mint};
+use anchor_spl::token_interface::{Mint as InterfaceMint, TokenAccount as InterfaceTokenAccount};
+
+pub mod swap;
+
+use swap::{Dex, SwapLegs};
 
 declare_id!("Em261K95h8M48f52iuu5YSaTJXJTs1pqjZpRCPYFqXRx");
 
@@ -24,12 +33,20 @@ pub mod tax_harvest {
 
     /// Locked function: sell `amount` of vault token for SOL and send SOL to jackpot.
     /// Only the program PDA can sign for the vault; this is the only way vault tokens leave.
-    /// TODO: Add CPI to DEX (Meteora DLMM / Raydium) to swap token -> SOL, then
-    /// SystemProgram::transfer received SOL to config.jackpot_sol_dest.
-    pub fn process_harvest(ctx: Context<ProcessHarvest>, amount: u64) -> Result<()> {
+    /// The sale goes through `dex`'s adapter (its accounts in `remaining_accounts`) into
+    /// vault_authority's wrapped SOL account, which is closed again: the SOL goes to
+    /// config.jackpot_sol_dest and the account rent back to `authority`. Only the config
+    /// authority may harvest, since whoever sets `min_sol_out` could sell into a sandwich.
+    /// Legacy SPL Token vaults only: a Token-2022 vault fails with `Token2022NotSupported`.
+    pub fn process_harvest<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ProcessHarvest<'info>>,
+        amount: u64,
+        min_sol_out: u64,
+        dex: Dex,
+    ) -> Result<()> {
         require!(amount > 0, HarvestError::InvalidAmount);
 
-        let (vault_pda, _) = Pubkey::find_program_address(&[b"vault_authority"], ctx.program_id);
+        let (vault_pda, vault_bump) = Pubkey::find_program_address(&[b"vault_authority"], ctx.program_id);
         require!(
             ctx.accounts.vault_authority.key() == vault_pda,
             HarvestError::VaultNotOwnedByProgram
@@ -39,14 +56,58 @@ pub mod tax_harvest {
         let vault_balance = vault.amount;
         require!(amount <= vault_balance, HarvestError::InsufficientVaultBalance);
 
-        let config = &ctx.accounts.config;
-
-        // TODO: CPI to DEX (Meteora/Raydium): swap `amount` of tax_vault token -> SOL.
-        // Use invoke_signed with seeds = [b"vault_authority", &[bump]] so vault_authority PDA signs.
-        // Then system_program::transfer(sol_received -> config.jackpot_sol_dest).
-        msg!("Harvest: would swap {} raw tokens -> SOL -> {}", amount, config.jackpot_sol_dest);
-        msg!("Implement DEX CPI (Meteora DLMM or Raydium) then SystemProgram::transfer");
-
+        let seeds: &[&[u8]] = &[b"vault_authority", &[vault_bump]];
+        let accounts = &ctx.accounts;
+        dex.swap(
+            &SwapLegs {
+                token_program: &accounts.token_program.to_account_info(),
+                authority: &accounts.vault_authority.to_account_info(),
+                token_mint: accounts.token_mint.key(),
+                source: &accounts.tax_vault.to_account_info(),
+                destination: &accounts.wsol_account.to_account_info(),
+            },
+            ctx.remaining_accounts,
+            amount,
+            min_sol_out,
+            &[seeds],
+        )?;
+
+        // Unwrap: closing the wrapped SOL account moves proceeds and rent to vault_authority.
+        ctx.accounts.wsol_account.reload()?;
+        let sol_out = ctx.accounts.wsol_account.amount;
+        let rent = ctx.accounts.wsol_account.to_account_info().lamports() - sol_out;
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.wsol_account.to_account_info(),
+                destination: ctx.accounts.vault_authority.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            &[seeds],
+        ))?;
+        for (to, lamports) in [
+            (ctx.accounts.jackpot_sol_dest.to_account_info(), sol_out),
+            (ctx.accounts.authority.to_account_info(), rent),
+        ] {
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.vault_authority.to_account_info(),
+                        to,
+                    },
+                    &[seeds],
+                ),
+                lamports,
+            )?;
+        }
+
+        msg!(
+            "Harvest: swapped {} raw tokens -> {} lamports -> {}",
+            amount,
+            sol_out,
+            ctx.accounts.config.jackpot_sol_dest
+        );
         Ok(())
     }
 }
@@ -74,29 +135,51 @@ pub struct ProcessHarvest<'info> {
         mut,
         seeds = [b"config"],
         bump = config.bump,
-        has_one = jackpot_sol_dest
+        has_one = jackpot_sol_dest,
+        has_one = authority
     )]
     pub config: Account<'info, HarvestConfig>,
 
-    /// Token account holding the tax; authority must be vault_authority PDA so only this program can move tokens
+    /// Token account holding the tax; authority must be vault_authority PDA so only this program can move tokens.
+    /// An interface so a Token-2022 vault gets `Token2022NotSupported` rather than an owner error
     #[account(
         mut,
+        constraint = *tax_vault.to_account_info().owner == token::ID @ HarvestError::Token2022NotSupported,
         constraint = tax_vault.owner == vault_authority.key() @ HarvestError::VaultNotOwnedByProgram
     )]
-    pub tax_vault: Account<'info, TokenAccount>,
+    pub tax_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
 
-    /// PDA that authorizes moves from tax_vault (validated in instruction)
+    /// PDA that authorizes moves from tax_vault (validated in instruction); briefly holds the unwrapped SOL
     /// CHECK: must be program PDA with seeds ["vault_authority"]
+    #[account(mut)]
     pub vault_authority: UncheckedAccount<'info>,
 
-    pub token_mint: Account<'info, Mint>,
+    #[account(address = tax_vault.mint)]
+    pub token_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    /// Wrapped SOL the sale lands in; closed again before the instruction ends
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = native_mint,
+        associated_token::authority = vault_authority
+    )]
+    pub wsol_account: Account<'info, TokenAccount>,
+
+    #[account(address = native_mint::ID)]
+    pub native_mint: Account<'info, Mint>,
 
     /// Destination for SOL (jackpot); must match config.jackpot_sol_dest
     /// CHECK: validated by config has_one
     #[account(mut)]
     pub jackpot_sol_dest: AccountInfo<'info>,
 
+    /// Config authority: pays the wrapped SOL account's rent and gets it back
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
@@ -116,4 +199,8 @@ pub enum HarvestError {
     InsufficientVaultBalance,
     #[msg("Tax vault must be owned by program PDA")]
     VaultNotOwnedByProgram,
+    #[msg("DEX accounts do not match the adapter's pool")]
+    InvalidSwapAccounts,
+    #[msg("Harvest only sells legacy SPL Token vaults")]
+    Token2022NotSupported,
 }
@@ -0,0 +1,174 @@
+//! DEX adapters for `process_harvest`. An adapter sells an exact amount of the
+//! tax token from the vault for wrapped SOL, signed by `vault_authority`, and
+//! takes the DEX's own accounts from `remaining_accounts`.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token::spl_token::native_mint;
+
+use crate::HarvestError;
+
+/// DEX a harvest sells through; picks the adapter.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dex {
+    OrcaWhirlpool,
+}
+
+impl Dex {
+    pub fn swap<'info>(
+        self,
+        legs: &SwapLegs<'_, 'info>,
+        dex_accounts: &[AccountInfo<'info>],
+        amount_in: u64,
+        min_out: u64,
+        signer: &[&[&[u8]]],
+    ) -> Result<()> {
+        match self {
+            Dex::OrcaWhirlpool => OrcaWhirlpool::swap(legs, dex_accounts, amount_in, min_out, signer),
+        }
+    }
+}
+
+/// The token accounts a swap moves between and the PDA that signs for them.
+pub struct SwapLegs<'a, 'info> {
+    pub token_program: &'a AccountInfo<'info>,
+    pub authority: &'a AccountInfo<'info>,
+    pub token_mint: Pubkey,
+    /// Tax token account sold from
+    pub source: &'a AccountInfo<'info>,
+    /// Wrapped SOL account the proceeds land in
+    pub destination: &'a AccountInfo<'info>,
+}
+
+/// One DEX integration. `swap` sells exactly `amount_in` from `legs.source`
+/// into `legs.destination` and must fail if less than `min_out` arrives.
+/// `accounts` are the DEX accounts from `remaining_accounts`, in the order
+/// the adapter documents.
+pub trait SwapAdapter {
+    fn swap<'info>(
+        legs: &SwapLegs<'_, 'info>,
+        accounts: &[AccountInfo<'info>],
+        amount_in: u64,
+        min_out: u64,
+        signer: &[&[&[u8]]],
+    ) -> Result<()>;
+}
+
+pub const WHIRLPOOL_PROGRAM_ID: Pubkey = pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+
+/// Orca Whirlpool `swap` on a tax token / SOL pool (SPL Token vaults).
+/// Accounts: Whirlpool program, whirlpool, token vault A, token vault B, the
+/// three tick arrays the swap may cross, oracle.
+pub struct OrcaWhirlpool;
+
+impl OrcaWhirlpool {
+    pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+    pub const ACCOUNT_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9];
+    // Field offsets in the `Whirlpool` account
+    pub const TICK_SPACING: usize = 41;
+    pub const SQRT_PRICE: usize = 65;
+    pub const TICK_CURRENT_INDEX: usize = 81;
+    pub const TOKEN_MINT_A: usize = 101;
+    pub const TOKEN_VAULT_A: usize = 133;
+    pub const TOKEN_MINT_B: usize = 181;
+    pub const TOKEN_VAULT_B: usize = 213;
+    /// Ends of the price range: the swap is bounded by `min_out` alone.
+    pub const MIN_SQRT_PRICE: u128 = 4_295_048_016;
+    pub const MAX_SQRT_PRICE: u128 = 79_226_673_515_401_279_992_447_579_055;
+
+    fn key_at(data: &[u8], offset: usize) -> Pubkey {
+        Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
+    }
+}
+
+impl SwapAdapter for OrcaWhirlpool {
+    fn swap<'info>(
+        legs: &SwapLegs<'_, 'info>,
+        accounts: &[AccountInfo<'info>],
+        amount_in: u64,
+        min_out: u64,
+        signer: &[&[&[u8]]],
+    ) -> Result<()> {
+        let [program, whirlpool, vault_a, vault_b, tick_array_0, tick_array_1, tick_array_2, oracle] = accounts else {
+            return err!(HarvestError::InvalidSwapAccounts);
+        };
+        require!(
+            program.key() == WHIRLPOOL_PROGRAM_ID && *whirlpool.owner == WHIRLPOOL_PROGRAM_ID,
+            HarvestError::InvalidSwapAccounts
+        );
+
+        let a_to_b = {
+            let data = whirlpool.try_borrow_data()?;
+            require!(
+                data.len() >= Self::TOKEN_VAULT_B + 32 && data[..8] == Self::ACCOUNT_DISCRIMINATOR,
+                HarvestError::InvalidSwapAccounts
+            );
+            require!(
+                vault_a.key() == Self::key_at(&data, Self::TOKEN_VAULT_A)
+                    && vault_b.key() == Self::key_at(&data, Self::TOKEN_VAULT_B),
+                HarvestError::InvalidSwapAccounts
+            );
+            // Selling the tax token: A -> B when it is mint A.
+            let mints = (Self::key_at(&data, Self::TOKEN_MINT_A), Self::key_at(&data, Self::TOKEN_MINT_B));
+            if mints == (legs.token_mint, native_mint::ID) {
+                true
+            } else if mints == (native_mint::ID, legs.token_mint) {
+                false
+            } else {
+                return err!(HarvestError::InvalidSwapAccounts);
+            }
+        };
+        let (owner_a, owner_b) = if a_to_b {
+            (legs.source, legs.destination)
+        } else {
+            (legs.destination, legs.source)
+        };
+        let sqrt_price_limit = if a_to_b { Self::MIN_SQRT_PRICE } else { Self::MAX_SQRT_PRICE };
+
+        let mut data = Vec::with_capacity(42);
+        data.extend_from_slice(&Self::SWAP_DISCRIMINATOR);
+        data.extend_from_slice(&amount_in.to_le_bytes());
+        data.extend_from_slice(&min_out.to_le_bytes());
+        data.extend_from_slice(&sqrt_price_limit.to_le_bytes());
+        data.push(true as u8); // amount_specified_is_input
+        data.push(a_to_b as u8);
+
+        let ix = Instruction {
+            program_id: WHIRLPOOL_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(legs.token_program.key(), false),
+                AccountMeta::new_readonly(legs.authority.key(), true),
+                AccountMeta::new(whirlpool.key(), false),
+                AccountMeta::new(owner_a.key(), false),
+                AccountMeta::new(vault_a.key(), false),
+                AccountMeta::new(owner_b.key(), false),
+                AccountMeta::new(vault_b.key(), false),
+                AccountMeta::new(tick_array_0.key(), false),
+                AccountMeta::new(tick_array_1.key(), false),
+                AccountMeta::new(tick_array_2.key(), false),
+                AccountMeta::new(oracle.key(), false),
+            ],
+            data,
+        };
+        invoke_signed(
+            &ix,
+            &[
+                program.clone(),
+                legs.token_program.clone(),
+                legs.authority.clone(),
+                whirlpool.clone(),
+                owner_a.clone(),
+                vault_a.clone(),
+                owner_b.clone(),
+                vault_b.clone(),
+                tick_array_0.clone(),
+                tick_array_1.clone(),
+                tick_array_2.clone(),
+                oracle.clone(),
+            ],
+            signer,
+        )?;
+        Ok(())
+    }
+}
@@ -30,6 +30,21 @@ const IX = {
   configureTiming: disc('global:configure_timing'),
 };
 
+/**
+ * Trailing accounts of the free entry instructions: the paid-entry config PDA
+ * and the optional admin co-signer (the program id stands for "none").
+ */
+function freeEntryTail() {
+  const [paidEntryPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from('paid_entry'), LOTTERY_PDA.toBuffer()],
+    LOTTERY_PROGRAM_ID
+  );
+  return [
+    { pubkey: paidEntryPDA, isWritable: false, isSigner: false },
+    { pubkey: LOTTERY_PROGRAM_ID, isWritable: false, isSigner: false },
+  ];
+}
+
 function readLotteryFields(data) {
   const jackpotLamports = Number(data.readBigUInt64LE(8));
   const lastSnapshot = Number(data.readBigInt64LE(24));
@@ -95,6 +110,7 @@ async function makeEntry(connection, wallet, usdCents) {
       { pubkey: participantPDA, isWritable: true, isSigner: false },
       { pubkey: wallet.publicKey, isWritable: true, isSigner: true },
       { pubkey: SystemProgram.programId, isWritable: false, isSigner: false },
+      ...freeEntryTail(),
     ],
     data: Buffer.concat([IX.enterUsd, amt]),
   });
@@ -116,7 +132,8 @@ async function updateParticipantTickets(connection, wallet, ticketCount, usdCent
     keys: [
       { pubkey: LOTTERY_PDA, isWritable: true, isSigner: false },
       { pubkey: participantPDA, isWritable: true, isSigner: false },
-      { pubkey: wallet.publicKey, isWritable: false, isSigner: true },
+      { pubkey: wallet.publicKey, isWritable: true, isSigner: true },
+      ...freeEntryTail(),
     ],
     data: Buffer.concat([IX.updateTickets, body]),
   });